@@ -20,8 +20,8 @@ pub enum Commands {
     #[clap(arg_required_else_help = true)]
     New {
         /// Project name
-        #[clap(value_parser)]
-        name: String,
+        #[clap(value_parser, required_unless_present_any = &["from_foundry", "from_hardhat"])]
+        name: Option<String>,
         /// Project type
         #[clap(short = 't', long = "type", value_name = "TYPE")]
         #[clap(name = "type", value_enum, default_value = "TypeScript")]
@@ -36,6 +36,18 @@ pub enum Commands {
             conflicts_with_all = &["type", "template"]
         )]
         from_repo: Option<GitUrl>,
+        /// Generate a Cubist config for an existing Foundry project (in place)
+        #[clap(
+            long = "from-foundry", value_name = "DIR", value_hint = clap::ValueHint::DirPath,
+            conflicts_with_all = &["name", "template", "from_repo", "from_hardhat", "dir"]
+        )]
+        from_foundry: Option<PathBuf>,
+        /// Generate a Cubist config for an existing Hardhat project (in place)
+        #[clap(
+            long = "from-hardhat", value_name = "DIR", value_hint = clap::ValueHint::DirPath,
+            conflicts_with_all = &["name", "template", "from_repo", "dir"]
+        )]
+        from_hardhat: Option<PathBuf>,
         /// Directory where to create project
         #[clap(long = "dir", value_parser, value_hint = clap::ValueHint::DirPath)]
         dir: Option<PathBuf>,
@@ -70,6 +82,80 @@ pub enum Commands {
         #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
         config: Option<PathBuf>,
     },
+    /// Deploy the contracts listed in a deployment plan (skipping already deployed contracts)
+    Deploy {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Deployment plan (TOML, or JSON if the extension is '.json'); defaults to 'deploy.toml'
+        /// in the project directory
+        #[clap(long = "plan", value_parser, value_hint = clap::ValueHint::FilePath)]
+        plan: Option<PathBuf>,
+        /// Print the transactions that would be sent instead of sending them
+        #[clap(long = "dry-run", action, default_value = "false")]
+        dry_run: bool,
+        /// Print the estimated cost of the deployment (and the sender's balance) on each chain
+        /// instead of deploying; fails if any chain is underfunded
+        #[clap(
+            long = "estimate",
+            action,
+            default_value = "false",
+            conflicts_with = "dry_run"
+        )]
+        estimate: bool,
+        /// Resume deployments that got interrupted (e.g., by a crash or a failed transaction),
+        /// reusing the contracts and shims they already deployed
+        #[clap(long = "resume", action, default_value = "false")]
+        resume: bool,
+        /// Write the transactions (unsigned) to this file for signing offline instead of sending
+        /// them (see 'cubist tx broadcast'); interrupted deployments must be resumed first
+        #[clap(
+            long = "unsigned",
+            value_parser,
+            value_hint = clap::ValueHint::FilePath,
+            conflicts_with_all = &["dry_run", "estimate", "resume"]
+        )]
+        unsigned: Option<PathBuf>,
+    },
+    /// Work with transaction bundles signed offline (see 'cubist deploy --unsigned')
+    Tx {
+        #[clap(subcommand)]
+        command: TxCommand,
+    },
+    /// Pause every shim of a deployed contract on every chain (stopping all cross-chain calls
+    /// to it)
+    Pause {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Contract name
+        #[clap(value_parser)]
+        contract: String,
+    },
+    /// Unpause every shim of a deployed contract on every chain (see 'cubist pause')
+    Unpause {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Contract name
+        #[clap(value_parser)]
+        contract: String,
+    },
+    /// Inspect and manage the generated shims of deployed contracts
+    Shims {
+        #[clap(subcommand)]
+        command: ShimsCommand,
+    },
+    /// Inspect and prune the deployment history of the current network profile
+    Deployments {
+        #[clap(subcommand)]
+        command: DeploymentsCommand,
+    },
+    /// Work with compiled contract artifacts
+    Artifacts {
+        #[clap(subcommand)]
+        command: ArtifactsCommand,
+    },
     /// Start a Cubist service (e.g., chains or relayer)
     Start {
         /// Explicit config file
@@ -91,3 +177,85 @@ pub enum Commands {
         json: bool,
     },
 }
+
+#[derive(Debug, Subcommand)]
+pub enum ArtifactsCommand {
+    /// Export compiled contracts (abi, bytecode, source maps, storage layout, build info) into
+    /// one chain-agnostic bundle per target
+    Export {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Output directory (defaults to 'export' in the build directory)
+        #[clap(long = "out-dir", value_parser, value_hint = clap::ValueHint::DirPath)]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TxCommand {
+    /// Broadcast the transactions of a signed transaction bundle (in order) and complete the
+    /// deployments they make
+    Broadcast {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Signed transaction bundle
+        #[clap(value_parser, value_hint = clap::ValueHint::FilePath)]
+        bundle: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ShimsCommand {
+    /// Print the current role holders (owner, pending owner, approved callers, and guardians)
+    /// of the shims of deployed contracts, per chain, and whether they are paused
+    Roles {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Only print the shims of this contract
+        #[clap(value_parser)]
+        contract: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DeploymentsCommand {
+    /// List recorded deployments and upgrades (oldest first)
+    List {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Only list deployments of this contract
+        #[clap(long = "contract", value_parser)]
+        contract: Option<String>,
+        /// Print the records as JSON (one per line)
+        #[clap(short = 'j', long = "json")]
+        json: bool,
+    },
+    /// Show the current and previous deployments of a contract
+    Show {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// Contract name
+        #[clap(value_parser)]
+        contract: String,
+    },
+    /// Remove stale deployments (with their manifests and receipts)
+    Prune {
+        /// Explicit config file
+        #[clap(short = 'c', long = "config", value_parser, value_hint = clap::ValueHint::FilePath)]
+        config: Option<PathBuf>,
+        /// How many of the most recent deployments of each contract (on each chain) to keep
+        #[clap(long = "keep", value_parser, default_value = "1")]
+        keep: usize,
+        /// Remove all deployments to the chain with this id (e.g., a restarted local chain)
+        #[clap(long = "chain-id", value_parser)]
+        chain_id: Option<u64>,
+        /// Print what would be removed instead of removing it
+        #[clap(long = "dry-run", action, default_value = "false")]
+        dry_run: bool,
+    },
+}
@@ -1,8 +1,13 @@
+pub mod artifacts;
 pub mod axelar;
 pub mod chain_manager;
 pub mod compile;
+pub mod deploy;
+pub mod deployments;
 pub mod gen;
 pub mod new;
 pub mod pre_compile;
 pub mod relayer;
+pub mod shims;
 mod tests;
+pub mod tx;
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use console::style;
+use cubist_config::{Config, Target};
+use cubist_sdk::core::{ContractData, ContractInfo, CubistInfo, SolidityArtifacts};
+use ethers_core::abi::Abi;
+use ethers_core::types::Bytes;
+use ethers_solc::artifacts::StorageLayout;
+use eyre::{Result, WrapErr};
+use serde::Serialize;
+
+/// Name of the bundle file written for each target.
+pub const BUNDLE_FILE_NAME: &str = "artifacts.json";
+
+/// Name of the directory (next to the bundle file) holding the referenced build info files.
+pub const BUILD_INFO_DIR_NAME: &str = "build-info";
+
+/// Chain-agnostic bundle of all compiled contracts for a target.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactBundle<'a> {
+    /// Target the contracts were compiled for
+    target: Target,
+    /// Compiled contracts
+    contracts: Vec<BundledContract<'a>>,
+}
+
+/// A single contract within an [`ArtifactBundle`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledContract<'a> {
+    /// Contract name
+    name: &'a str,
+    /// Source file (relative to the contracts directory of the target)
+    source: &'a Path,
+    /// Whether this is an auto-generated shim
+    is_shim: bool,
+    /// Contract abi
+    abi: &'a Abi,
+    /// Contract bytecode
+    bytecode: &'a Bytes,
+    /// Deployed (runtime) bytecode
+    deployed_bytecode: &'a Option<Bytes>,
+    /// Source map of the bytecode
+    source_map: &'a Option<String>,
+    /// Source map of the deployed bytecode
+    deployed_source_map: &'a Option<String>,
+    /// Storage layout
+    storage_layout: &'a Option<StorageLayout>,
+    /// Function signatures mapped to their selectors
+    method_identifiers: &'a BTreeMap<String, String>,
+    /// Build info file (relative to the bundle file)
+    build_info: Option<PathBuf>,
+}
+
+/// Command that exports compiled contracts (with their abi, bytecode, source maps, storage
+/// layout, method identifiers, and build info) into one chain-agnostic bundle per target.
+///
+/// ```text
+///   {out_dir}
+///   └── {target} (e.g., ethereum)
+///       ├── artifacts.json
+///       └── build-info
+///           └── <build-info-id>.json
+/// ```
+///
+/// # Arguments
+///
+/// * `cfg`     - Cubist config
+/// * `out_dir` - Directory to write bundles to (defaults to `{build_dir}/export`)
+pub fn export(cfg: Config, out_dir: Option<PathBuf>) -> Result<()> {
+    let out_dir = out_dir.unwrap_or_else(|| cfg.build_dir().join("export"));
+    let cubist = CubistInfo::new(cfg)?;
+    for (target, contracts) in &cubist.contracts {
+        if *target == Target::Stellar {
+            println!(
+                "{} target {} (only Solidity contracts can be exported)",
+                style("Skipping").bold().yellow(),
+                style(target).bold().blue()
+            );
+            continue;
+        }
+        let shims = cubist.shims.get(target).into_iter().flatten();
+        let target_dir = out_dir.join(target);
+        export_target(
+            *target,
+            contracts
+                .iter()
+                .map(|c| (c, false))
+                .chain(shims.map(|c| (c, true))),
+            &target_dir,
+        )?;
+        println!(
+            "{} artifacts for target {} to {}",
+            style("Exported").bold().green(),
+            style(target).bold().blue(),
+            target_dir.join(BUNDLE_FILE_NAME).display()
+        );
+    }
+    Ok(())
+}
+
+/// Write the bundle for a single target (copying referenced build info files next to it).
+fn export_target<'a>(
+    target: Target,
+    contracts: impl Iterator<Item = (&'a ContractInfo, bool)>,
+    target_dir: &Path,
+) -> Result<()> {
+    let build_info_dir = target_dir.join(BUILD_INFO_DIR_NAME);
+    fs::create_dir_all(&build_info_dir)
+        .wrap_err_with(|| format!("Failed to create {}", build_info_dir.display()))?;
+
+    let mut bundled = vec![];
+    for (contract, is_shim) in contracts {
+        let (abi, bytes, artifacts) = match &contract.data {
+            ContractData::SolidityData {
+                abi,
+                bytes,
+                artifacts,
+            } => (abi, bytes, artifacts),
+            ContractData::SorobanData { .. } => continue,
+        };
+        let SolidityArtifacts {
+            deployed_bytes,
+            source_map,
+            deployed_source_map,
+            storage_layout,
+            method_identifiers,
+            build_info,
+        } = artifacts.as_ref();
+        let build_info = match build_info {
+            Some(src) => {
+                let file_name = src.file_name().unwrap_or_default();
+                let dst = build_info_dir.join(file_name);
+                if !dst.is_file() {
+                    fs::copy(src, &dst)
+                        .wrap_err_with(|| format!("Failed to copy {}", src.display()))?;
+                }
+                Some(Path::new(BUILD_INFO_DIR_NAME).join(file_name))
+            }
+            None => None,
+        };
+        bundled.push(BundledContract {
+            name: &contract.fqn.name,
+            source: &contract.fqn.file,
+            is_shim,
+            abi,
+            bytecode: bytes,
+            deployed_bytecode: deployed_bytes,
+            source_map,
+            deployed_source_map,
+            storage_layout,
+            method_identifiers,
+            build_info,
+        });
+    }
+    bundled.sort_by(|a, b| (a.source, a.name).cmp(&(b.source, b.name)));
+
+    let bundle = ArtifactBundle {
+        target,
+        contracts: bundled,
+    };
+    let bundle_file = target_dir.join(BUNDLE_FILE_NAME);
+    fs::write(&bundle_file, serde_json::to_string_pretty(&bundle)?)
+        .wrap_err_with(|| format!("Failed to write {}", bundle_file.display()))
+}
@@ -1,8 +1,43 @@
 use eyre::Result;
 
 use cubist_config::network::NetworkProfile;
+use cubist_config::{Config, Target};
 use cubist_localchains::provider::Server;
+use cubist_sdk::TargetProjectInfo;
+use tracing::{debug, warn};
 
 pub async fn start(cfg: &NetworkProfile) -> Result<Vec<Box<dyn Server>>> {
     Ok(cubist_localchains::start(cfg).await?)
 }
+
+/// Predeploys the generated multicall contract (see [`cubist_sdk::core::Batch`]) on the local
+/// chains started for the targets of `cfg`.  Targets that haven't been built yet are skipped
+/// (the SDK deploys the contract on first use instead); failures are only logged.
+pub async fn deploy_multicall(cfg: &Config) {
+    for target in cfg.targets() {
+        if let Err(e) = try_deploy_multicall(cfg, target).await {
+            warn!("Failed to predeploy multicall contract on {target}: {e}");
+        }
+    }
+}
+
+async fn try_deploy_multicall(cfg: &Config, target: Target) -> Result<()> {
+    if target == Target::Stellar || !cfg.paths().for_target(target).manifest.is_file() {
+        return Ok(());
+    }
+    let project = TargetProjectInfo::new(cfg, target)?;
+    // only chains that Cubist started (and that don't use an existing Multicall3 contract)
+    let multicall = project
+        .network_config
+        .as_ref()
+        .and_then(|c| c.common().multicall);
+    if !project.is_started_locally()?
+        || multicall.is_some()
+        || project.multicall_contract().is_err()
+    {
+        return Ok(());
+    }
+    let address = project.connect().await?.deploy_multicall().await?;
+    debug!("Multicall contract on {target} at {address:?}");
+    Ok(())
+}
@@ -0,0 +1,796 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use console::style;
+use cubist_config::paths::{hex, ContractFQN, Paths};
+use cubist_config::{Config, ContractName, PreCompileManifest, Target};
+use cubist_sdk::core::{
+    Contract, ContractData, Cubist, DeploymentEstimate, DeploymentInfo, DeploymentManifest,
+    TxBundle,
+};
+use cubist_sdk::Http;
+use ethers_core::abi::token::{LenientTokenizer, Tokenizer};
+use ethers_core::abi::{Param, ParamType, Token};
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{NameOrAddress, H256};
+use ethers_core::utils::keccak256;
+use eyre::{bail, eyre, Result, WrapErr};
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::warn;
+
+/// Default name of the deployment plan file (in the project directory).
+pub const DEFAULT_PLAN_FILE: &str = "deploy.toml";
+
+/// A deployment plan: which contracts to deploy, with which constructor arguments, and in which
+/// order.
+///
+/// Plans are written in TOML (or in JSON, if the file has a `.json` extension):
+/// ```toml
+/// [[contracts]]
+/// name = "Receiver"
+///
+/// [[contracts]]
+/// name = "Sender"
+/// args = ["${Receiver.address.ethereum}", "1000000000000000000000"]
+/// ```
+///
+/// String arguments may reference the address of another contract on its own target
+/// (`${Name.address}`) or on any target it has a shim on (`${Name.address.<target>}`).  Large
+/// numbers should be given as strings, since TOML integers are limited to 64 bits.
+///
+/// Contracts are deployed in plan order, except that a contract is always deployed after the
+/// contracts it references, the contracts it calls cross-chain (see
+/// [`FileArtifact::contract_dependencies`](cubist_config::FileArtifact::contract_dependencies)),
+/// and the contracts it explicitly `depends_on`.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeployPlan {
+    /// Contracts to deploy
+    #[serde(default)]
+    pub contracts: Vec<PlannedContract>,
+}
+
+/// A single contract within a [`DeployPlan`].
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PlannedContract {
+    /// Contract name
+    pub name: ContractName,
+    /// Constructor arguments
+    #[serde(default)]
+    pub args: Vec<Value>,
+    /// Contracts to deploy before this one (in addition to the inferred dependencies)
+    #[serde(default)]
+    pub depends_on: Vec<ContractName>,
+    /// Initializer to call through the proxy (upgradeable contracts only)
+    #[serde(default)]
+    pub initializer: Option<String>,
+    /// Arguments of the initializer
+    #[serde(default)]
+    pub init_args: Vec<Value>,
+}
+
+/// A reference to the address of a contract in a [`DeployPlan`] argument.
+#[derive(Debug, PartialEq)]
+struct AddressRef {
+    /// Referenced contract
+    contract: ContractName,
+    /// Target to take the address on (defaults to the target of the contract)
+    target: Option<Target>,
+}
+
+impl DeployPlan {
+    /// Reads a plan from a TOML (or, if the extension is `.json`, JSON) file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read deployment plan {}", path.display()))?;
+        let plan = if path.extension() == Some("json".as_ref()) {
+            serde_json::from_str(&content).map_err(|e| eyre!(e))
+        } else {
+            toml::from_str(&content).map_err(|e| eyre!(e))
+        };
+        plan.wrap_err_with(|| format!("Invalid deployment plan {}", path.display()))
+    }
+
+    /// Returns the planned contracts in deployment order.
+    ///
+    /// # Arguments
+    /// * `calls` - contracts mapped to the contracts they call cross-chain
+    fn ordered<'a>(
+        &'a self,
+        calls: &HashMap<ContractName, HashSet<ContractName>>,
+    ) -> Result<Vec<&'a PlannedContract>> {
+        let mut names = HashSet::new();
+        for c in &self.contracts {
+            if !names.insert(c.name.as_str()) {
+                bail!(
+                    "Contract '{}' is listed more than once in the deployment plan",
+                    c.name
+                );
+            }
+        }
+
+        // dependencies (within the plan) of each planned contract
+        let mut deps = HashMap::new();
+        for c in &self.contracts {
+            let mut c_deps: HashSet<&str> = c.depends_on.iter().map(String::as_str).collect();
+            let refs = references(&c.args)?
+                .into_iter()
+                .chain(references(&c.init_args)?)
+                .map(|r| r.contract)
+                .collect::<Vec<_>>();
+            c_deps.extend(names.iter().filter(|n| refs.iter().any(|r| r == *n)));
+            if let Some(called) = calls.get(&c.name) {
+                c_deps.extend(called.iter().map(String::as_str).filter(|n| *n != c.name));
+            }
+            c_deps.retain(|n| names.contains(n));
+            deps.insert(c.name.as_str(), c_deps);
+        }
+
+        // repeatedly pick the first contract whose dependencies are all deployed
+        let mut ordered: Vec<&PlannedContract> = vec![];
+        let mut pending: Vec<&PlannedContract> = self.contracts.iter().collect();
+        while !pending.is_empty() {
+            let next = pending.iter().position(|c| {
+                deps[c.name.as_str()]
+                    .iter()
+                    .all(|d| ordered.iter().any(|o| o.name == *d))
+            });
+            match next {
+                Some(i) => ordered.push(pending.remove(i)),
+                None => {
+                    let cycle = pending.iter().map(|c| format!("'{}'", c.name)).join(", ");
+                    bail!("Cyclic dependencies between contracts {cycle} in the deployment plan")
+                }
+            }
+        }
+        Ok(ordered)
+    }
+}
+
+/// Parses a reference (the part between `${` and `}`).
+fn parse_ref(s: &str) -> Result<AddressRef> {
+    let parts: Vec<&str> = s.split('.').collect();
+    match parts.as_slice() {
+        [name, "address"] if !name.is_empty() => Ok(AddressRef {
+            contract: name.to_string(),
+            target: None,
+        }),
+        [name, "address", target] if !name.is_empty() => Ok(AddressRef {
+            contract: name.to_string(),
+            target: Some(
+                target
+                    .parse()
+                    .map_err(|_| eyre!("Unknown target '{target}' in reference '${{{s}}}'"))?,
+            ),
+        }),
+        _ => bail!(
+            "Invalid reference '${{{s}}}' (expected '${{<Contract>.address}}' or '${{<Contract>.address.<target>}}')"
+        ),
+    }
+}
+
+/// Replaces every reference (`${...}`) in `s` with the result of `resolve`.
+fn substitute(s: &str, resolve: &mut impl FnMut(AddressRef) -> Result<String>) -> Result<String> {
+    let mut result = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let len = rest[start..]
+            .find('}')
+            .ok_or_else(|| eyre!("Unterminated reference in '{s}'"))?;
+        result.push_str(&rest[..start]);
+        result.push_str(&resolve(parse_ref(&rest[start + 2..start + len])?)?);
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Replaces every reference in (the strings of) `value` with the result of `resolve`.
+fn substitute_value(
+    value: &Value,
+    resolve: &mut impl FnMut(AddressRef) -> Result<String>,
+) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(substitute(s, resolve)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| substitute_value(v, resolve))
+                .collect::<Result<_>>()?,
+        ),
+        _ => value.clone(),
+    })
+}
+
+/// Returns all references in `values`.
+fn references(values: &[Value]) -> Result<Vec<AddressRef>> {
+    let mut refs = vec![];
+    for value in values {
+        substitute_value(value, &mut |r| {
+            refs.push(r);
+            Ok(String::new())
+        })?;
+    }
+    Ok(refs)
+}
+
+/// Converts a plan argument into a token of type `param`.
+fn to_token(param: &ParamType, value: &Value) -> Result<Token> {
+    match (param, value) {
+        (ParamType::Array(inner), Value::Array(items)) => Ok(Token::Array(
+            items
+                .iter()
+                .map(|v| to_token(inner, v))
+                .collect::<Result<_>>()?,
+        )),
+        (ParamType::FixedArray(inner, len), Value::Array(items)) if items.len() == *len => {
+            Ok(Token::FixedArray(
+                items
+                    .iter()
+                    .map(|v| to_token(inner, v))
+                    .collect::<Result<_>>()?,
+            ))
+        }
+        (ParamType::Tuple(types), Value::Array(items)) if items.len() == types.len() => {
+            Ok(Token::Tuple(
+                types
+                    .iter()
+                    .zip(items)
+                    .map(|(t, v)| to_token(t, v))
+                    .collect::<Result<_>>()?,
+            ))
+        }
+        (_, Value::String(s)) => LenientTokenizer::tokenize(param, s)
+            .map_err(|e| eyre!("Cannot convert '{s}' to {param}: {e}")),
+        (_, Value::Number(_) | Value::Bool(_)) => {
+            LenientTokenizer::tokenize(param, &value.to_string())
+                .map_err(|e| eyre!("Cannot convert {value} to {param}: {e}"))
+        }
+        _ => bail!("Cannot convert {value} to {param}"),
+    }
+}
+
+/// Converts plan arguments into tokens for the function (or constructor) parameters `params`.
+fn to_tokens(params: &[Param], values: &[Value]) -> Result<Vec<Token>> {
+    if params.len() != values.len() {
+        bail!(
+            "Expected {} argument(s), got {}",
+            params.len(),
+            values.len()
+        );
+    }
+    params
+        .iter()
+        .zip(values)
+        .map(|(p, v)| {
+            to_token(&p.kind, v).wrap_err_with(|| format!("Invalid argument '{}'", p.name))
+        })
+        .collect()
+}
+
+/// Returns all contracts mapped to the contracts they call cross-chain (according to the
+/// pre-compile manifests of all targets).
+fn contract_calls(cfg: &Config) -> Result<HashMap<ContractName, HashSet<ContractName>>> {
+    let paths = cfg.paths();
+    let mut calls: HashMap<ContractName, HashSet<ContractName>> = HashMap::new();
+    for target in cfg.targets() {
+        let manifest_file = &paths.for_target(target).manifest;
+        let manifest = PreCompileManifest::from_file(manifest_file).wrap_err_with(|| {
+            format!(
+                "Failed to read {}. Did you run 'cubist build'?",
+                manifest_file.display()
+            )
+        })?;
+        for file in manifest.files.iter().filter(|f| !f.is_shim) {
+            for (name, deps) in &file.contract_dependencies {
+                calls
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(deps.iter().cloned());
+            }
+        }
+    }
+    Ok(calls)
+}
+
+/// Initializes the contracts (and shims) that are already deployed with the addresses in their
+/// deployment manifests (the latest manifest of each contract, if there are several).
+pub(crate) async fn load_deployments(cubist: &Cubist<Http>, paths: &Paths) -> Result<()> {
+    let dir = paths.deployment_manifest_dir();
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut manifests: Vec<(SystemTime, DeploymentManifest)> = vec![];
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension() != Some("json".as_ref()) {
+            continue;
+        }
+        match DeploymentManifest::from_file(&path) {
+            Ok(manifest) => manifests.push((fs::metadata(&path)?.modified()?, manifest)),
+            Err(e) => warn!(
+                "Ignoring invalid deployment manifest {}: {e}",
+                path.display()
+            ),
+        }
+    }
+    manifests.sort_by_key(|(modified, _)| *modified);
+    let mut latest: HashMap<(Target, ContractFQN), (Arc<Contract<Http>>, DeploymentManifest)> =
+        HashMap::new();
+    for (_, manifest) in manifests {
+        let target = manifest.deployment.target;
+        if target == Target::Stellar {
+            continue;
+        }
+        let Some(contract) = cubist.find_contract(target, &manifest.contract) else {
+            continue;
+        };
+        // ignore deployments to other chains (e.g., to a local chain that got restarted with
+        // another chain id)
+        if !on_current_chain(&contract, &manifest.deployment).await? {
+            continue;
+        }
+        latest.insert((target, manifest.contract.clone()), (contract, manifest));
+    }
+
+    for (contract, manifest) in latest.into_values() {
+        contract.at(&manifest.deployment.address).await?;
+        for shim_info in &manifest.shims {
+            if let Some(shim) = contract.shims.get(&shim_info.target) {
+                if on_current_chain(shim, shim_info).await? {
+                    shim.at(&shim_info.address).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a deployment was made to the chain that the contract's target currently uses
+/// (deployments recorded without a chain id are assumed to be).
+async fn on_current_chain(contract: &Contract<Http>, info: &DeploymentInfo) -> Result<bool> {
+    Ok(match info.chain_id {
+        Some(chain_id) => chain_id == contract.project.chain_id().await?,
+        None => true,
+    })
+}
+
+/// Resolves a reference to the (hex) address of a contract.  Contracts that are not deployed yet
+/// resolve to the address they get in `bundle` (if `bundle` creates them), or else (in dry runs)
+/// to the zero address.
+fn resolve_address(
+    cubist: &Cubist<Http>,
+    r: AddressRef,
+    dry_run: bool,
+    bundle: Option<&TxBundle>,
+) -> Result<String> {
+    let contract = cubist
+        .contract(&r.contract)
+        .ok_or_else(|| eyre!("Unknown contract '{}' in deployment plan", r.contract))?;
+    let target = r.target.unwrap_or_else(|| contract.target());
+    if target != contract.target() && !contract.shims.contains_key(&target) {
+        bail!("Contract '{}' has no shim on {target}", r.contract);
+    }
+    let address = contract.try_address_on(target).or_else(|| {
+        let fqn = match contract.shims.get(&target) {
+            Some(shim) if target != contract.target() => &shim.meta.fqn,
+            _ => &contract.meta.fqn,
+        };
+        let created = bundle?.created(target, fqn)?;
+        Some(created.as_bytes().to_vec())
+    });
+    match address {
+        Some(address) => Ok(format!("0x{}", hex(&address))),
+        None if dry_run => {
+            println!(
+                "   (address of '{}' on {target} is only known once it is deployed; using 0x0)",
+                r.contract
+            );
+            Ok(format!("{:?}", ethers_core::types::Address::zero()))
+        }
+        None => bail!("Contract '{}' is not deployed on {target}", r.contract),
+    }
+}
+
+/// Returns the constructor parameters of a contract.
+fn constructor_params(contract: &Contract<Http>) -> Result<Vec<Param>> {
+    match &contract.meta.data {
+        ContractData::SolidityData { abi, .. } => Ok(abi
+            .constructor()
+            .map(|c| c.inputs.clone())
+            .unwrap_or_default()),
+        ContractData::SorobanData { .. } => bail!(
+            "Cannot deploy {} (only Solidity contracts can be deployed from a plan)",
+            contract.full_name_with_target()
+        ),
+    }
+}
+
+/// Prints a transaction that would be sent in a dry run.
+fn print_tx(tx: &TypedTransaction) {
+    let data = tx.data().map(|d| d.to_vec()).unwrap_or_default();
+    let from = tx
+        .from()
+        .map(|a| format!("{a:?}"))
+        .unwrap_or_else(|| "(default sender)".to_owned());
+    let to = match tx.to() {
+        Some(NameOrAddress::Address(a)) => format!("{a:?}"),
+        Some(NameOrAddress::Name(n)) => n.clone(),
+        None => "(contract creation)".to_owned(),
+    };
+    println!("     from: {from}");
+    println!("     to:   {to}");
+    println!(
+        "     data: {} bytes (keccak256 {:?})",
+        data.len(),
+        H256::from(keccak256(&data))
+    );
+}
+
+/// Returns the contract named `name`.
+fn find_contract(cubist: &Cubist<Http>, name: &str) -> Result<Arc<Contract<Http>>> {
+    cubist
+        .contract(name)
+        .ok_or_else(|| eyre!("Contract '{name}' not found. Did you run 'cubist build'?"))
+}
+
+/// Estimates the cost of deploying the planned contracts that are not deployed yet (see
+/// [`Cubist::estimate_deploy`]), on each chain.  Addresses of contracts that are not deployed
+/// yet are replaced with the zero address.
+async fn estimate(
+    cubist: &Cubist<Http>,
+    ordered: &[&PlannedContract],
+) -> Result<DeploymentEstimate> {
+    let mut contracts = vec![];
+    for planned in ordered {
+        let contract = find_contract(cubist, &planned.name)?;
+        if contract.is_deployed() {
+            continue;
+        }
+        let mut resolve = |r| resolve_address(cubist, r, true, None);
+        let args = planned
+            .args
+            .iter()
+            .map(|v| substitute_value(v, &mut resolve))
+            .collect::<Result<Vec<_>>>()?;
+        let args = to_tokens(&constructor_params(&contract)?, &args)
+            .wrap_err_with(|| format!("Invalid constructor arguments for '{}'", planned.name))?;
+        contracts.push((contract, Token::Tuple(args)));
+    }
+    Ok(cubist.estimate_deploy(contracts).await?)
+}
+
+/// Command that deploys the contracts listed in a deployment plan (see [`DeployPlan`]),
+/// skipping contracts that are already deployed (according to their deployment manifests).
+///
+/// Before deploying anything, checks that the sender on each chain can afford the whole plan.
+/// Deployments that got interrupted (see [`Contract::is_deployment_interrupted`]) are only
+/// resumed if `resume` is set; otherwise, the command refuses to start.
+///
+/// If `unsigned` is set, no transactions are sent; instead, they are written (unsigned) to that
+/// file, for signing offline and broadcasting later (see [`crate::commands::tx::broadcast`]).
+/// Interrupted deployments must be resumed before bundling the rest.
+///
+/// # Arguments
+///
+/// * `cfg`           - Cubist config
+/// * `plan_file`     - Deployment plan (defaults to `deploy.toml` in the project directory)
+/// * `dry_run`       - Only print the transactions that would be sent
+/// * `estimate_only` - Only print the estimated cost (and the sender's balance) on each chain
+/// * `resume`        - Resume interrupted deployments
+/// * `unsigned`      - Write the transactions to this file (see [`TxBundle`]) instead of sending them
+pub async fn deploy(
+    cfg: Config,
+    plan_file: Option<PathBuf>,
+    dry_run: bool,
+    estimate_only: bool,
+    resume: bool,
+    unsigned: Option<PathBuf>,
+) -> Result<()> {
+    let paths = cfg.paths();
+    let plan_file = plan_file.unwrap_or_else(|| paths.project_dir.join(DEFAULT_PLAN_FILE));
+    let plan = DeployPlan::from_file(&plan_file)?;
+    let calls = contract_calls(&cfg)?;
+    let ordered = plan.ordered(&calls)?;
+
+    let cubist = Cubist::<Http>::new(cfg).await?;
+    load_deployments(&cubist, &paths).await?;
+
+    if estimate_only {
+        let estimate = estimate(&cubist, &ordered).await?;
+        print!("{estimate}");
+        let underfunded = estimate.underfunded().map(|c| c.target).join(", ");
+        if !underfunded.is_empty() {
+            bail!("Insufficient funds on {underfunded}");
+        }
+        return Ok(());
+    }
+    if !dry_run {
+        let interrupted = ordered
+            .iter()
+            .filter_map(|planned| cubist.contract(&planned.name))
+            .filter(|contract| contract.is_deployment_interrupted())
+            .map(|contract| format!("'{}'", contract.meta.fqn.name))
+            .join(", ");
+        if !interrupted.is_empty() && unsigned.is_some() {
+            bail!(
+                "Deployment of {interrupted} got interrupted. Resume it with '--resume' (without '--unsigned') before bundling the remaining deployments (or remove {} to start over).",
+                paths.deployment_journal_dir().display()
+            );
+        }
+        if !interrupted.is_empty() && !resume {
+            bail!(
+                "Deployment of {interrupted} got interrupted. Rerun with '--resume' to pick up where it left off (or remove {} to start over).",
+                paths.deployment_journal_dir().display()
+            );
+        }
+        match estimate(&cubist, &ordered).await {
+            Ok(estimate) => estimate.check()?,
+            Err(e) => warn!("Cannot estimate deployment cost: {e:#}"),
+        }
+    }
+
+    let mut bundle = unsigned.as_ref().map(|_| TxBundle::default());
+    for planned in ordered {
+        let contract = find_contract(&cubist, &planned.name)?;
+        let interrupted = contract.is_deployment_interrupted();
+        if let Some(address) = contract.address().filter(|_| !interrupted) {
+            println!(
+                "{} {} (already deployed at 0x{})",
+                style("Skipping").bold().yellow(),
+                contract.full_name_with_target(),
+                hex(&address)
+            );
+            continue;
+        }
+
+        let mut resolve = |r| resolve_address(&cubist, r, dry_run, bundle.as_ref());
+        let args = planned
+            .args
+            .iter()
+            .map(|v| substitute_value(v, &mut resolve))
+            .collect::<Result<Vec<_>>>()?;
+        let init_args = planned
+            .init_args
+            .iter()
+            .map(|v| substitute_value(v, &mut resolve))
+            .collect::<Result<Vec<_>>>()?;
+        let args = to_tokens(&constructor_params(&contract)?, &args)
+            .wrap_err_with(|| format!("Invalid constructor arguments for '{}'", planned.name))?;
+
+        if dry_run {
+            println!(
+                "{} {}",
+                style("Would deploy").bold().green(),
+                contract.full_name_with_target()
+            );
+            let tx = contract
+                .project
+                .deployment_tx(&contract.meta, Token::Tuple(args))
+                .await?;
+            print_tx(&tx);
+            if contract.is_upgradeable() {
+                println!("     + proxy in front of the implementation");
+            }
+            if let Some(initializer) = &planned.initializer {
+                println!(
+                    "     + call '{initializer}' (through the proxy) with {}",
+                    Value::Array(init_args)
+                );
+            }
+            for target in contract.shims.keys().filter(|t| **t != contract.target()) {
+                println!("     + shim on {target}");
+            }
+            continue;
+        }
+
+        if let Some(bundle) = bundle.as_mut() {
+            println!(
+                "{} {}",
+                style("Bundling").bold().green(),
+                contract.full_name_with_target()
+            );
+            let address = contract.deploy_unsigned(bundle, Token::Tuple(args)).await?;
+            println!(" - to be deployed at 0x{}", hex(&address));
+            continue;
+        }
+
+        let action = if interrupted { "Resuming" } else { "Deploying" };
+        println!(
+            "{} {}",
+            style(action).bold().green(),
+            contract.full_name_with_target()
+        );
+        let (address, _) = match &planned.initializer {
+            Some(initializer) => {
+                let params = match &contract.meta.data {
+                    ContractData::SolidityData { abi, .. } => abi
+                        .function(initializer)
+                        .map(|f| f.inputs.clone())
+                        .wrap_err_with(|| format!("Unknown initializer '{initializer}'"))?,
+                    ContractData::SorobanData { .. } => vec![],
+                };
+                let init_args = to_tokens(&params, &init_args).wrap_err_with(|| {
+                    format!("Invalid arguments for initializer '{initializer}'")
+                })?;
+                contract
+                    .deploy_with_initializer(
+                        Token::Tuple(args),
+                        initializer,
+                        Token::Tuple(init_args),
+                    )
+                    .await?
+            }
+            None => contract.deploy(Token::Tuple(args)).await?,
+        };
+        println!(" - deployed at 0x{}", hex(&address));
+        for proposal in contract.pending_safe_proposals() {
+            println!(
+                " - proposed {} admin transaction(s) to Safe {:?} on {} (import {} into the Safe Transaction Builder)",
+                proposal.transactions.len(),
+                proposal.safe,
+                proposal.target,
+                proposal.batch.display()
+            );
+        }
+    }
+
+    if let (Some(path), Some(bundle)) = (unsigned, bundle) {
+        if bundle.is_empty() {
+            println!("Nothing to sign (all contracts are already deployed)");
+            return Ok(());
+        }
+        bundle.write_file(&path)?;
+        println!(
+            "Wrote {} unsigned transactions to {}. Sign them and run 'cubist tx broadcast {}' to deploy.",
+            bundle.transactions.len(),
+            path.display(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn planned(name: &str, args: Vec<Value>, depends_on: &[&str]) -> PlannedContract {
+        PlannedContract {
+            name: name.to_owned(),
+            args,
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            initializer: None,
+            init_args: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_plan() {
+        let plan: DeployPlan = toml::from_str(
+            r#"
+            [[contracts]]
+            name = "Receiver"
+
+            [[contracts]]
+            name = "Sender"
+            args = ["${Receiver.address.polygon}", 42, [1, 2]]
+            depends_on = ["Util"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            DeployPlan {
+                contracts: vec![
+                    planned("Receiver", vec![], &[]),
+                    planned(
+                        "Sender",
+                        vec![
+                            json!("${Receiver.address.polygon}"),
+                            json!(42),
+                            json!([1, 2])
+                        ],
+                        &["Util"]
+                    ),
+                ]
+            },
+            plan
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let mut resolve = |r: AddressRef| Ok(format!("<{}@{:?}>", r.contract, r.target));
+        assert_eq!(
+            "a <Foo@None> b <Bar@Some(Polygon)>",
+            substitute("a ${Foo.address} b ${Bar.address.polygon}", &mut resolve).unwrap()
+        );
+        assert_eq!("plain", substitute("plain", &mut resolve).unwrap());
+        assert!(substitute("${Foo.address", &mut resolve).is_err());
+        assert!(substitute("${Foo.balance}", &mut resolve).is_err());
+        assert!(substitute("${Foo.address.mars}", &mut resolve).is_err());
+
+        let refs = references(&[json!(1), json!(["${Foo.address}", "${Bar.address}"])]).unwrap();
+        assert_eq!(
+            vec!["Foo", "Bar"],
+            refs.iter().map(|r| &r.contract).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_ordering() {
+        let plan = DeployPlan {
+            contracts: vec![
+                planned("Sender", vec![json!("${Receiver.address}")], &[]),
+                planned("Caller", vec![], &[]),
+                planned("Receiver", vec![], &["Util"]),
+                planned("Util", vec![], &[]),
+            ],
+        };
+        let calls = HashMap::from([(
+            "Caller".to_owned(),
+            HashSet::from(["Sender".to_owned(), "External".to_owned()]),
+        )]);
+        let ordered = plan.ordered(&calls).unwrap();
+        assert_eq!(
+            vec!["Util", "Receiver", "Sender", "Caller"],
+            ordered.iter().map(|c| c.name.as_str()).collect::<Vec<_>>()
+        );
+
+        // cycles and duplicates are rejected
+        let plan = DeployPlan {
+            contracts: vec![planned("A", vec![], &["B"]), planned("B", vec![], &["A"])],
+        };
+        assert!(plan.ordered(&HashMap::new()).is_err());
+        let plan = DeployPlan {
+            contracts: vec![planned("A", vec![], &[]), planned("A", vec![], &[])],
+        };
+        assert!(plan.ordered(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_to_token() {
+        let address = ethers_core::types::Address::random();
+        assert_eq!(
+            Token::Address(address),
+            to_token(&ParamType::Address, &json!(format!("{address:?}"))).unwrap()
+        );
+        assert_eq!(
+            Token::Uint(42.into()),
+            to_token(&ParamType::Uint(256), &json!(42)).unwrap()
+        );
+        assert_eq!(
+            Token::Bool(true),
+            to_token(&ParamType::Bool, &json!(true)).unwrap()
+        );
+        assert_eq!(
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            to_token(
+                &ParamType::Array(Box::new(ParamType::Uint(8))),
+                &json!([1, "2"])
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Token::Tuple(vec![Token::String("x".into()), Token::Int(7.into())]),
+            to_token(
+                &ParamType::Tuple(vec![ParamType::String, ParamType::Int(32)]),
+                &json!(["x", 7])
+            )
+            .unwrap()
+        );
+        assert!(to_token(&ParamType::Address, &json!("not an address")).is_err());
+        assert!(to_token(
+            &ParamType::FixedArray(Box::new(ParamType::Bool), 2),
+            &json!([true])
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use console::style;
+use cubist_config::paths::{hex, ContractFQN, Paths};
+use cubist_config::{Config, Target};
+use cubist_sdk::core::{DeploymentEvent, DeploymentHistory, DeploymentInfo, DeploymentRecord};
+use eyre::{bail, Result};
+
+/// A single deployment of a contract: its target, chain id, and address.
+type DeploymentKey = (Target, Option<u64>, Vec<u8>);
+
+fn key(info: &DeploymentInfo) -> DeploymentKey {
+    (info.target, info.chain_id, info.address.clone())
+}
+
+/// Loads the deployment history of the current network profile.
+fn history(cfg: &Config) -> Result<(DeploymentHistory, Vec<DeploymentRecord>)> {
+    let history = DeploymentHistory::new(cfg.paths().deployment_history());
+    let records = history.records()?;
+    Ok((history, records))
+}
+
+/// Describes how long ago `timestamp` (in seconds since the Unix epoch) was.
+fn age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let secs = now.saturating_sub(timestamp);
+    match secs {
+        0..=59 => format!("{secs}s ago"),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// Describes where a contract got deployed, e.g., "ethereum (chain 31337) at 0x...".
+fn location(info: &DeploymentInfo) -> String {
+    let chain = match info.chain_id {
+        Some(chain_id) => format!(" (chain {chain_id})"),
+        None => String::new(),
+    };
+    format!(
+        "{}{chain} at 0x{}",
+        style(info.target).bold().blue(),
+        hex(&info.address)
+    )
+}
+
+/// Command that lists the deployment history of the current network profile (oldest first).
+///
+/// # Arguments
+///
+/// * `cfg`      - Cubist config
+/// * `contract` - Only list deployments of the contract with this name
+/// * `json`     - Print the records as JSON (one per line)
+pub fn list(cfg: Config, contract: Option<String>, json: bool) -> Result<()> {
+    let (history, records) = history(&cfg)?;
+    let records = records.into_iter().filter(|r| match &contract {
+        Some(name) => r.contract.name == *name,
+        None => true,
+    });
+    let mut count = 0;
+    for record in records {
+        count += 1;
+        if json {
+            println!("{}", serde_json::to_string(&record)?);
+            continue;
+        }
+        let event = match record.event {
+            DeploymentEvent::Deployed => style("deployed").green(),
+            DeploymentEvent::Upgraded => style("upgraded").yellow(),
+        };
+        print!(
+            "{:>8}  {event} {} on {}",
+            age(record.timestamp),
+            style(&record.contract.name).bold(),
+            location(&record.deployment)
+        );
+        match record.implementation {
+            Some(implementation) => println!(" (implementation {implementation:?})"),
+            None => println!(),
+        }
+    }
+    if count == 0 && !json {
+        println!(
+            "No deployments recorded for network profile '{}' (in {})",
+            cfg.current_network_profile,
+            history.path().display()
+        );
+    }
+    Ok(())
+}
+
+/// Command that shows the current and previous deployments of a contract (on each target and
+/// chain it got deployed to with the current network profile).
+///
+/// # Arguments
+///
+/// * `cfg`      - Cubist config
+/// * `contract` - Contract name
+pub fn show(cfg: Config, contract: &str) -> Result<()> {
+    let paths = cfg.paths();
+    let (_, records) = history(&cfg)?;
+    let records: Vec<_> = records
+        .into_iter()
+        .filter(|r| r.contract.name == contract)
+        .collect();
+    if records.is_empty() {
+        bail!(
+            "No deployments of '{contract}' recorded for network profile '{}'",
+            cfg.current_network_profile
+        );
+    }
+
+    // group deployments by target and chain (newest last)
+    let mut deployments: BTreeMap<(String, Option<u64>), Vec<&DeploymentRecord>> = BTreeMap::new();
+    for record in records
+        .iter()
+        .filter(|r| r.event == DeploymentEvent::Deployed)
+    {
+        let info = &record.deployment;
+        deployments
+            .entry((info.target.to_string(), info.chain_id))
+            .or_default()
+            .push(record);
+    }
+
+    for deployed in deployments.values() {
+        let (current, previous) = deployed.split_last().expect("groups are not empty");
+        println!(
+            "{} on {}, deployed {}",
+            style(contract).bold(),
+            location(&current.deployment),
+            age(current.timestamp)
+        );
+        for shim in &current.shims {
+            println!("  shim on {}", location(shim));
+        }
+        let upgrade = records.iter().rev().find(|r| {
+            r.event == DeploymentEvent::Upgraded && key(&r.deployment) == key(&current.deployment)
+        });
+        match (upgrade, current.implementation) {
+            (Some(upgrade), _) => println!(
+                "  implementation {:?} (upgraded {})",
+                upgrade.implementation.unwrap_or_default(),
+                age(upgrade.timestamp)
+            ),
+            (None, Some(implementation)) => println!("  implementation {implementation:?}"),
+            (None, None) => {}
+        }
+        let manifest =
+            paths.for_deployment_manifest(&current.contract, &current.deployment.address);
+        if !current.shims.is_empty() {
+            let bridged = Paths::bridged_signal_for_manifest_file(&manifest).is_file();
+            println!("  bridged: {}", if bridged { "yes" } else { "no" });
+        }
+        for record in previous.iter().rev() {
+            println!(
+                "  previously at 0x{} (deployed {})",
+                hex(&record.deployment.address),
+                age(record.timestamp)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns the deployments that [`prune`] removes: all but the `keep` most recent deployments of
+/// each contract on each chain, and all deployments on chain `chain_id` (if given).
+///
+/// Records of a contract deployed to the same address (e.g., with CREATE2, or to a local chain
+/// that got restarted) are the same deployment: they are pruned together, and only once none of
+/// them is among the `keep` most recent ones.
+fn stale_deployments(
+    records: &[DeploymentRecord],
+    keep: usize,
+    chain_id: Option<u64>,
+) -> Vec<&DeploymentRecord> {
+    // deployments of each contract on each chain (oldest first), each with its records
+    let mut deployments: BTreeMap<_, Vec<Vec<&DeploymentRecord>>> = BTreeMap::new();
+    for record in records
+        .iter()
+        .filter(|r| r.event == DeploymentEvent::Deployed)
+    {
+        let info = &record.deployment;
+        let deployed = deployments
+            .entry((
+                record.contract.to_string(),
+                info.target.to_string(),
+                info.chain_id,
+            ))
+            .or_default();
+        // redeploying to the same address makes that deployment the most recent one
+        let mut same_address = match deployed
+            .iter()
+            .position(|d| d[0].deployment.address == info.address)
+        {
+            Some(i) => deployed.remove(i),
+            None => vec![],
+        };
+        same_address.push(record);
+        deployed.push(same_address);
+    }
+    deployments
+        .into_iter()
+        .flat_map(|((_, _, chain), deployed)| {
+            let stale = if chain_id.is_some() && chain == chain_id {
+                deployed.len()
+            } else {
+                deployed.len().saturating_sub(keep)
+            };
+            deployed.into_iter().take(stale).flatten()
+        })
+        .collect()
+}
+
+/// Files of the deployment recorded by `record`: its deployment manifest, the marker that it
+/// got bridged, and the receipts of the contract and its shims (those that exist).
+fn deployment_files(paths: &Paths, record: &DeploymentRecord) -> Result<Vec<PathBuf>> {
+    let manifest = paths.for_deployment_manifest(&record.contract, &record.deployment.address);
+    let mut files: Vec<_> = [
+        manifest.clone(),
+        Paths::bridged_signal_for_manifest_file(&manifest),
+    ]
+    .into_iter()
+    .filter(|f| f.is_file())
+    .collect();
+    for info in std::iter::once(&record.deployment).chain(&record.shims) {
+        let (Some(chain_id), Some(target_paths)) =
+            (info.chain_id, paths.try_for_target(info.target))
+        else {
+            continue;
+        };
+        let dir = target_paths.deployment_receipts_root(chain_id);
+        files.extend(find_receipts(&dir, &info.address)?);
+    }
+    Ok(files)
+}
+
+/// Files that pruning the records in `records` for which `is_stale` holds removes: the files of
+/// the stale deployments (see [`deployment_files`]), except for those that the deployments that
+/// are kept still use (e.g., shared shims).
+fn stale_files(
+    paths: &Paths,
+    records: &[DeploymentRecord],
+    is_stale: impl Fn(&DeploymentRecord) -> bool,
+) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    let mut kept_files = HashSet::new();
+    for record in records {
+        let record_files = deployment_files(paths, record)?;
+        if is_stale(record) {
+            files.extend(record_files);
+        } else {
+            kept_files.extend(record_files);
+        }
+    }
+    files.retain(|f| !kept_files.contains(f));
+    Ok(files)
+}
+
+/// Finds the receipts of the contract deployed at `address` (anywhere in `dir`).
+fn find_receipts(dir: &Path, address: &[u8]) -> Result<Vec<PathBuf>> {
+    let mut receipts = vec![];
+    if !dir.is_dir() {
+        return Ok(receipts);
+    }
+    let file_name = format!("{}.json", hex(address));
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            receipts.extend(find_receipts(&path, address)?);
+        } else if path.file_name() == Some(file_name.as_ref()) {
+            receipts.push(path);
+        }
+    }
+    Ok(receipts)
+}
+
+/// Command that prunes stale deployments from the deployment history of the current network
+/// profile, along with their deployment manifests and receipts.
+///
+/// # Arguments
+///
+/// * `cfg`      - Cubist config
+/// * `keep`     - How many of the most recent deployments of each contract (on each chain) to keep
+/// * `chain_id` - Prune all deployments to this chain (e.g., a local chain that got restarted)
+/// * `dry_run`  - Only print what would be removed
+pub fn prune(cfg: Config, keep: usize, chain_id: Option<u64>, dry_run: bool) -> Result<()> {
+    let paths = cfg.paths();
+    let (history, records) = history(&cfg)?;
+    let stale = stale_deployments(&records, keep, chain_id);
+    if stale.is_empty() {
+        println!("Nothing to prune");
+        return Ok(());
+    }
+
+    for record in &stale {
+        println!(
+            "{} {} on {} (deployed {})",
+            style(if dry_run { "Would prune" } else { "Pruning" })
+                .bold()
+                .yellow(),
+            style(&record.contract.name).bold(),
+            location(&record.deployment),
+            age(record.timestamp)
+        );
+    }
+
+    // all records of a stale deployment are stale (see `stale_deployments`)
+    let stale: HashSet<(ContractFQN, DeploymentKey)> = stale
+        .iter()
+        .map(|r| (r.contract.clone(), key(&r.deployment)))
+        .collect();
+    let is_stale = |r: &DeploymentRecord| stale.contains(&(r.contract.clone(), key(&r.deployment)));
+    for file in stale_files(&paths, &records, is_stale)? {
+        if !dry_run {
+            fs::remove_file(&file)?;
+        }
+        println!("   {}", file.display());
+    }
+
+    if !dry_run {
+        let removed = history.retain(|r| !is_stale(r))?;
+        println!(
+            "Removed {} record(s) from {}",
+            removed.len(),
+            history.path().display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn deployed(name: &str, chain_id: u64, address: u8) -> DeploymentRecord {
+        DeploymentRecord::now(
+            DeploymentEvent::Deployed,
+            ContractFQN::new("Counter.sol".into(), name.to_owned()),
+            DeploymentInfo {
+                target: Target::Ethereum,
+                address: vec![address; 20],
+                chain_id: Some(chain_id),
+            },
+            vec![],
+            None,
+        )
+    }
+
+    fn addresses(records: Vec<&DeploymentRecord>) -> Vec<u8> {
+        records.iter().map(|r| r.deployment.address[0]).collect()
+    }
+
+    #[test]
+    fn test_stale_deployments() {
+        let records = vec![
+            deployed("Counter", 31337, 1),
+            deployed("Counter", 31337, 2),
+            deployed("Counter", 5, 3),
+            deployed("Other", 31337, 4),
+            deployed("Counter", 31337, 5),
+        ];
+
+        // keep the latest deployment of each contract on each chain
+        assert_eq!(vec![1, 2], addresses(stale_deployments(&records, 1, None)));
+        assert_eq!(vec![1], addresses(stale_deployments(&records, 2, None)));
+        assert!(stale_deployments(&records, 3, None).is_empty());
+
+        // drop everything on a chain
+        assert_eq!(
+            vec![1, 2, 5, 4],
+            addresses(stale_deployments(&records, 1, Some(31337)))
+        );
+        assert_eq!(
+            vec![3, 1, 2],
+            addresses(stale_deployments(&records, 1, Some(5)))
+        );
+    }
+
+    #[test]
+    fn test_stale_deployments_same_address() {
+        // e.g., redeployed with CREATE2, or to a restarted local chain
+        let records = vec![
+            deployed("Counter", 31337, 1),
+            deployed("Counter", 31337, 2),
+            deployed("Counter", 31337, 1),
+        ];
+        assert_eq!(vec![2], addresses(stale_deployments(&records, 1, None)));
+        assert!(stale_deployments(&records, 2, None).is_empty());
+        assert_eq!(
+            vec![2, 1, 1],
+            addresses(stale_deployments(&records, 1, Some(31337)))
+        );
+
+        let records = vec![deployed("Counter", 31337, 1), deployed("Counter", 31337, 1)];
+        assert!(stale_deployments(&records, 1, None).is_empty());
+    }
+
+    #[test]
+    fn test_stale_files() {
+        let tmp = tempdir().unwrap();
+        let app_dir = tmp.path();
+        fs::create_dir_all(app_dir.join("contracts")).unwrap();
+        fs::write(app_dir.join("contracts").join("Counter.sol"), "").unwrap();
+        fs::write(
+            app_dir.join("cubist-config.json"),
+            serde_json::json!({
+                "type": "JavaScript",
+                "contracts": {
+                    "root_dir": "contracts",
+                    "targets": { "ethereum": { "files": [ "./contracts/Counter.sol" ] } }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let cfg = Config::from_dir(app_dir).unwrap();
+        let paths = cfg.paths();
+
+        // two deployments sharing a shim
+        let shim = DeploymentInfo {
+            target: Target::Ethereum,
+            address: vec![9; 20],
+            chain_id: Some(31337),
+        };
+        let mut old = deployed("Counter", 31337, 1);
+        old.shims = vec![shim.clone()];
+        let mut new = deployed("Counter", 31337, 2);
+        new.shims = vec![shim.clone()];
+        let receipts = paths
+            .for_target(Target::Ethereum)
+            .deployment_receipts_dir(31337, &old.contract);
+        fs::create_dir_all(&receipts).unwrap();
+        let mut all_files = vec![];
+        for record in [&old, &new] {
+            let manifest =
+                paths.for_deployment_manifest(&record.contract, &record.deployment.address);
+            all_files.push(manifest.clone());
+            all_files.push(Paths::bridged_signal_for_manifest_file(&manifest));
+            all_files.push(receipts.join(format!("{}.json", hex(&record.deployment.address))));
+        }
+        let shim_receipt = receipts.join(format!("{}.json", hex(&shim.address)));
+        all_files.push(shim_receipt.clone());
+        fs::create_dir_all(paths.deployment_manifest_dir()).unwrap();
+        for file in &all_files {
+            fs::write(file, "{}").unwrap();
+        }
+
+        let records = vec![old.clone(), new];
+        let is_old = |r: &DeploymentRecord| r.deployment.address == old.deployment.address;
+        let files = stale_files(&paths, &records, is_old).unwrap();
+        assert_eq!(
+            BTreeSet::from_iter(all_files[..3].iter().cloned()),
+            files,
+            "the shim is still used"
+        );
+        assert!(!files.contains(&shim_receipt));
+
+        // redeployed to the same address: nothing the kept record uses is stale
+        let records = vec![old.clone(), old.clone()];
+        let is_first = |r: &DeploymentRecord| std::ptr::eq(r, &records[0]);
+        assert!(stale_files(&paths, &records, is_first).unwrap().is_empty());
+    }
+}
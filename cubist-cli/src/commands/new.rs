@@ -3,8 +3,9 @@ use eyre::{Result, WrapErr};
 use std::path::Path;
 
 use crate::cube::git::{Git, GitUrl};
+use crate::cube::import::{assign_targets, ImportSource, ImportedProject};
 use crate::cube::template::{Template, TemplateCube};
-use crate::cube::{new_base_cube, CubeFactory};
+use crate::cube::{new_base_cube, write_config, CubeFactory};
 use cubist_config::ProjType;
 
 /// Command that creates new empty project
@@ -81,6 +82,50 @@ pub fn from_git_repo(name: &str, url: &GitUrl, dir: &Path, force: bool) -> Resul
     cube.new_project(force)
 }
 
+/// Command that turns an existing Foundry or Hardhat project into a Cubist project by generating
+/// a config (in the project directory) from the project's settings.
+///
+/// # Arguments
+///
+/// * `source` - Build tool the existing project uses
+/// * `type_`  - Project type/language
+/// * `dir`    - Directory of the existing project
+/// * `force`  - Force overwrite existing configuration if one exists
+pub fn from_existing(source: ImportSource, type_: ProjType, dir: &Path, force: bool) -> Result<()> {
+    println!(
+        "{} {:?} project in {}",
+        style("Importing").bold().green(),
+        source,
+        dir.display()
+    );
+    let project = ImportedProject::load(source, dir)
+        .wrap_err_with(|| format!("Failed to import {source:?} project"))?;
+    write_imported(&project, type_, force)?;
+    Ok(())
+}
+
+/// Generates a config (in the project directory) for an imported project, after assigning its
+/// contract files to targets, and returns it.
+pub(crate) fn write_imported(
+    project: &ImportedProject,
+    type_: ProjType,
+    force: bool,
+) -> Result<Config> {
+    let files = project.contract_files()?;
+    let targets = assign_targets(&files)?;
+    let cfg = project
+        .to_config(type_, targets)
+        .wrap_err("Imported settings are not a valid Cubist config")?;
+    if write_config(&cfg, force)? {
+        println!(
+            "{} {}",
+            style("Generated").bold().green(),
+            cfg.config_path.display()
+        );
+    }
+    Ok(cfg)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
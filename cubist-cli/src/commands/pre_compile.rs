@@ -1,20 +1,28 @@
 use console::style;
 use cubist_sdk::gen::backend::{Artifact, ArtifactMetadata, Backend};
+use cubist_sdk::gen::interface::file::Pragma;
 use cubist_sdk::gen::interface::Interfaces;
+use cubist_sdk::gen::multicall::{generate_multicall, MULTICALL_FILE};
+use cubist_sdk::gen::proxy::{generate_proxies, PROXIES_FILE};
 use cubist_sdk::parse::{get_import_path, parse_files, source_file::SourceFile};
 use eyre::{bail, eyre, Result, WrapErr};
 use itertools::Itertools;
-use std::collections::HashSet as Set;
+use std::collections::{BTreeMap, HashSet as Set};
 use std::process::Command;
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use cubist_config::{
     paths::{Paths, TargetPaths},
-    CompilerConfig, Config, ContractsConfig, FileArtifact, PreCompileManifest, Target,
-    TargetConfig,
+    Compiler, Config, ContractName, ContractsConfig, FileArtifact, PreCompileManifest, Target,
+    TargetConfig, VendorConfig,
 };
 use cubist_sdk::core::validate_file;
 use cubist_util::js_pkg_manager::js_pkg_manager_for_path;
+use cubist_util::vendor::Vendor;
 use tracing::{debug, warn};
 
 pub struct PreCompiler {
@@ -29,6 +37,15 @@ pub struct PreCompiler {
 
     /// Generated interfaces, as returned by the interface generator.
     interfaces: Interfaces,
+
+    /// Contract files for each target, along with the contracts (in those files) that run on that
+    /// target.  A file can appear under multiple targets if its contracts are annotated with
+    /// `@custom:cubist-target`.
+    target_files: HashMap<Target, Vec<(PathBuf, Vec<ContractName>)>>,
+
+    /// Pragmas of the contract files for each target that calls are batched through the
+    /// generated multicall contract on, i.e., that don't use an existing Multicall3 contract.
+    multicall_pragmas: HashMap<Target, Vec<Pragma>>,
 }
 
 impl PreCompiler {
@@ -80,18 +97,40 @@ impl PreCompiler {
         // would have to compile the Soroban contracts before validating the source files)
         if !contracts.targets.contains_key(&Target::Stellar) {
             debug!("Validating Solidity source files");
-            Self::validate_solidity_source_files(&config.get_compiler_config(), &contracts)?;
+            Self::validate_solidity_source_files(config, &contracts)?;
         } else {
             warn!("Skipping the validation of Solidity source files due to Soroban contracts");
         }
 
         // get interfaces to expose contracts cross-chain
         let interfaces = Interfaces::new(&source_files)?;
+        let mut target_files: HashMap<Target, Vec<_>> = HashMap::new();
+        for source in &source_files {
+            target_files
+                .entry(source.target)
+                .or_default()
+                .push((source.file_name.clone(), source.contract_names()));
+        }
+        Self::validate_upgradeable(&contracts, &target_files)?;
+        let mut multicall_pragmas: HashMap<Target, Vec<_>> = HashMap::new();
+        for source in &source_files {
+            let has_multicall = config
+                .network_for_target(source.target)
+                .map_or(false, |network| network.common().multicall.is_some());
+            if !has_multicall {
+                multicall_pragmas
+                    .entry(source.target)
+                    .or_default()
+                    .extend(source.pragmas());
+            }
+        }
         Ok(PreCompiler {
             paths,
             contracts,
             backend: <dyn Backend>::create(config),
             interfaces,
+            target_files,
+            multicall_pragmas,
         })
     }
 
@@ -108,25 +147,56 @@ impl PreCompiler {
             "Extracting import paths from {} source files",
             source_files.len()
         );
-        let imp_paths = source_files.iter().flat_map(|source| {
-            source
-                .import_directives()
-                .into_iter()
-                .map(|imp_path| get_import_path(&imp_path).clone())
-        });
+        let imp_paths: Vec<_> = source_files
+            .iter()
+            .flat_map(|source| {
+                source
+                    .import_directives()
+                    .into_iter()
+                    .map(|imp_path| (source.target, get_import_path(&imp_path).clone()))
+            })
+            .collect();
         // Filter out the paths that are actually JavaScript imports (they have format `@<import>`)
-        // and do not already exist in one of the import directories
+        // and do not resolve (through the remappings of their target, or in one of the import
+        // directories) to existing files
+        let mut target_ccs = HashMap::new();
         let missing_imports = imp_paths
-            .filter(|imp_path| imp_path.starts_with('@') && cc.search(imp_path).is_none())
+            .iter()
+            .filter(|(target, imp_path)| {
+                let target_cc = target_ccs
+                    .entry(*target)
+                    .or_insert_with(|| config.get_target_compiler_config(*target));
+                imp_path.starts_with('@') && target_cc.search(imp_path).is_none()
+            })
+            .map(|(_, imp_path)| imp_path.clone())
             .collect::<Set<_>>();
 
         let backend = <dyn Backend>::create(config);
-        let missing_deps = backend
-            .npm_dependencies()
-            .into_iter()
+        let npm_deps = backend.npm_dependencies();
+        let missing_deps = npm_deps
+            .iter()
             .filter(|t| cc.search(&t.0).is_none())
+            .cloned()
             .collect::<Set<_>>();
 
+        // Resolve natively (without a JavaScript package manager) if configured. This also
+        // verifies already vendored packages, so we do it even if nothing is missing.
+        if let Some(vendor_config) = config.vendor() {
+            let imported = imp_paths
+                .iter()
+                .filter_map(|(_, imp_path)| package_for_import(imp_path))
+                .chain(npm_deps.into_iter().map(|t| t.0))
+                .collect::<Set<_>>();
+            return Self::vendor_external_imports(
+                config,
+                paths,
+                vendor_config,
+                &imported,
+                &missing_imports,
+                &missing_deps,
+            );
+        }
+
         // Return if no external packages were found
         if missing_imports.is_empty() && missing_deps.is_empty() {
             debug!("No external imports found");
@@ -135,16 +205,11 @@ impl PreCompiler {
 
         // Return error if external packages were found but we are not allowed to download them
         if !config.allow_import_from_external() {
-            let import_paths = missing_imports
-                .iter()
-                .chain(missing_deps.iter().map(|t| &t.0))
-                .map(|p| format!("\n  {p}"))
-                .join("");
-            let msg = format!(
-                "Found external imports that are currently missing:
-                {import_paths}
-                \nSet `allow_import_from_external` to `true` in cubist-config.json to allow cubist to automatically download them");
-            return Err(eyre!(msg));
+            return Err(Self::missing_imports_error(
+                missing_imports
+                    .iter()
+                    .chain(missing_deps.iter().map(|t| &t.0)),
+            ));
         }
 
         // Determine the JavaScript package manager to use
@@ -194,6 +259,81 @@ impl PreCompiler {
         Ok(())
     }
 
+    /// Vendors external imports with the native dependency resolver (see [`Vendor`]). Declared
+    /// `solidity_dependencies` and packages already pinned in the lock file are always fetched
+    /// (if needed); other missing packages are only resolved if `allow_import_from_external` is
+    /// set.  Pinned packages that are neither declared nor `imported` anymore are removed.
+    fn vendor_external_imports(
+        config: &Config,
+        paths: &Paths,
+        vendor_config: &VendorConfig,
+        imported: &Set<String>,
+        missing_imports: &Set<String>,
+        missing_deps: &Set<(String, String)>,
+    ) -> Result<()> {
+        let mut vendor = Vendor::new(vendor_config, paths.lock_file.clone())?;
+
+        // Declared dependencies, plus the packages (`@scope/name`) providing missing imports
+        let mut deps: BTreeMap<String, String> = config
+            .contracts()
+            .solidity_dependencies
+            .clone()
+            .into_iter()
+            .collect();
+        let inferred = missing_imports
+            .iter()
+            .filter_map(|imp_path| package_for_import(imp_path))
+            .map(|pkg| (pkg, "latest".to_string()))
+            .chain(missing_deps.iter().cloned());
+        let mut undeclared = vec![];
+        for (pkg, spec) in inferred {
+            if deps.contains_key(&pkg) {
+                continue;
+            }
+            let spec = match vendor.lock().packages.get(&pkg) {
+                Some(locked) => locked.spec.clone(),
+                None => {
+                    undeclared.push(pkg.clone());
+                    spec
+                }
+            };
+            deps.insert(pkg, spec);
+        }
+        if !undeclared.is_empty() && !config.allow_import_from_external() {
+            return Err(Self::missing_imports_error(undeclared.iter()));
+        }
+        // Pinned packages that are still imported (and thus not missing, since they are vendored)
+        for pkg in imported {
+            if let Some(locked) = vendor.lock().packages.get(pkg) {
+                deps.entry(pkg.clone())
+                    .or_insert_with(|| locked.spec.clone());
+            }
+        }
+
+        let downloaded = vendor
+            .sync(deps.iter().map(|(pkg, spec)| (pkg.as_str(), spec.as_str())))
+            .wrap_err("Failed to vendor Solidity dependencies")?;
+        if !downloaded.is_empty() {
+            println!(
+                "{} {} into {}",
+                style("Vendored").bold().green(),
+                downloaded.join(","),
+                vendor_config.dir.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Error returned when external imports are missing but we are not allowed to download them.
+    fn missing_imports_error<'a>(import_paths: impl Iterator<Item = &'a String>) -> eyre::Report {
+        let import_paths = import_paths.map(|p| format!("\n  {p}")).join("");
+        let msg = format!(
+            "Found external imports that are currently missing:
+                {import_paths}
+                \nSet `allow_import_from_external` to `true` in cubist-config.json to allow cubist to automatically download them");
+        eyre!(msg)
+    }
+
     /// Checks for imports that will cause breakage when Cubist copies files
     pub fn validate_imports(config: &Config, source_files: &[SourceFile]) -> Result<()> {
         source_files.iter().try_for_each(|file| {
@@ -207,12 +347,13 @@ impl PreCompiler {
     /// Attempts to compile the original source files. This ensures that the later steps in our
     /// compilation process deal with sane source files.
     pub fn validate_solidity_source_files(
-        compiler_config: &CompilerConfig,
+        config: &Config,
         contracts: &ContractsConfig,
     ) -> Result<()> {
         for (target, target_config) in &contracts.targets {
             match target_config {
                 TargetConfig::EvmTargetConfig { compiler, .. } => {
+                    let compiler_config = config.get_target_compiler_config(*target);
                     let sources = target_config.source_files();
                     println!(
                         "{} {} file(s) for target {}",
@@ -223,7 +364,7 @@ impl PreCompiler {
                     sources
                         .iter()
                         .map(|src| {
-                            validate_file(compiler, compiler_config, src)
+                            validate_file(compiler, &compiler_config, src)
                                 .wrap_err(format!("Failed to validate {}", src.display()))
                         })
                         .collect::<Result<Vec<()>>>()?;
@@ -237,6 +378,27 @@ impl PreCompiler {
         Ok(())
     }
 
+    /// Checks that every contract marked as upgradeable exists and runs on an EVM target
+    fn validate_upgradeable(
+        contracts: &ContractsConfig,
+        target_files: &HashMap<Target, Vec<(PathBuf, Vec<ContractName>)>>,
+    ) -> Result<()> {
+        for name in contracts.upgradeable.keys().sorted() {
+            let targets = target_files
+                .iter()
+                .filter(|(_, files)| files.iter().any(|(_, names)| names.contains(name)))
+                .map(|(target, _)| *target)
+                .collect::<Vec<_>>();
+            if targets.is_empty() {
+                bail!("Upgradeable contract '{name}' not found in any contract file");
+            }
+            if targets.contains(&Target::Stellar) {
+                bail!("Upgradeable contract '{name}' cannot run on Stellar (proxies are EVM-only)");
+            }
+        }
+        Ok(())
+    }
+
     /// Generates build folders for all targets
     fn generate_all(&self) -> Result<()> {
         let mut artifacts: HashMap<Target, Vec<Artifact>> = HashMap::new();
@@ -251,12 +413,12 @@ impl PreCompiler {
         }
 
         let no_artifacts: Vec<Artifact> = vec![];
-        for (target, target_config) in &self.contracts.targets {
+        for target in self.contracts.targets.keys() {
             let target_paths = self.paths.for_target(*target);
             println!(" {}", style(target).bold().blue());
             self.prepare_target_dir(&self.contracts.root_dir, &target_paths.contracts)?;
             let target_artifacts = artifacts.get(target).unwrap_or(&no_artifacts);
-            self.generate_target(target_paths, target_config, target_artifacts)?;
+            self.generate_target(*target, target_paths, target_artifacts)?;
         }
 
         Ok(())
@@ -294,18 +456,63 @@ impl PreCompiler {
     /// - writes out artifacts for all interfaces associated with the given target
     fn generate_target(
         &self,
+        target: Target,
         target_paths: &TargetPaths,
-        target_config: &TargetConfig,
         target_artifacts: &Vec<Artifact>,
     ) -> Result<()> {
         // copy contracts corresponding to the target chain
         let mut files = Vec::new();
-        for contract_file in target_config.contract_files() {
-            let contract_rel_path = self.contracts.relative_to_root(&contract_file)?;
+        for (contract_file, contract_names) in self.target_files.get(&target).into_iter().flatten()
+        {
+            let contract_rel_path = self.contracts.relative_to_root(contract_file)?;
+            let contracts = self
+                .interfaces
+                .get_contracts_in_file(contract_file)
+                .into_iter()
+                .filter(|(name, _)| contract_names.contains(name))
+                .collect();
+            files.push(FileArtifact::native_contract(contract_rel_path, contracts));
+        }
+
+        // generate the proxy contracts if any contract on this target is upgradeable
+        let has_upgradeable = self
+            .target_files
+            .get(&target)
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, names)| names)
+            .any(|name| self.contracts.upgradeable.contains_key(name));
+        if has_upgradeable {
+            let proxies_file = target_paths.contracts.join(PROXIES_FILE);
+            fs::write(&proxies_file, generate_proxies())?;
+            // No contract dependencies: the proxies are compiled (and deployed by the SDK on
+            // demand) but they are not contracts of the project
             files.push(FileArtifact::native_contract(
-                contract_rel_path,
-                self.interfaces.get_contracts_in_file(&contract_file),
+                PROXIES_FILE.into(),
+                Default::default(),
             ));
+            println!(" - generated {}", proxies_file.display());
+        }
+
+        // generate the multicall contract (which calls are batched through) for solc targets
+        // that don't use an existing Multicall3 contract, with the same pragmas as the contracts
+        let is_solc = matches!(
+            self.contracts.targets.get(&target),
+            Some(TargetConfig::EvmTargetConfig {
+                compiler: Compiler::Solc,
+                ..
+            })
+        );
+        let multicall_pragmas = self.multicall_pragmas.get(&target).filter(|_| is_solc);
+        if let Some(pragmas) = multicall_pragmas {
+            let multicall_file = target_paths.contracts.join(MULTICALL_FILE);
+            fs::write(&multicall_file, generate_multicall(pragmas))?;
+            // Like the proxies, the multicall contract is not a contract of the project
+            files.push(FileArtifact::native_contract(
+                MULTICALL_FILE.into(),
+                Default::default(),
+            ));
+            println!(" - generated {}", multicall_file.display());
         }
 
         for target_artifact in target_artifacts {
@@ -342,3 +549,30 @@ pub fn pre_compile(config: &Config) -> Result<()> {
     let compiler = PreCompiler::new(config)?;
     compiler.generate_all()
 }
+
+/// Name of the npm package (`@scope/name`) providing a JavaScript-style import (e.g.,
+/// `@openzeppelin/contracts/token/ERC20/ERC20.sol`).
+fn package_for_import(imp_path: &str) -> Option<String> {
+    let mut parts = imp_path.splitn(3, '/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(scope), Some(name), Some(_)) if scope.len() > 1 && !name.is_empty() => {
+            Some(format!("{scope}/{name}"))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::package_for_import;
+
+    #[test]
+    fn test_package_for_import() {
+        assert_eq!(
+            Some("@openzeppelin/contracts".to_string()),
+            package_for_import("@openzeppelin/contracts/token/ERC20/ERC20.sol")
+        );
+        assert_eq!(None, package_for_import("@openzeppelin/contracts"));
+        assert_eq!(None, package_for_import("@/foo/bar.sol"));
+    }
+}
@@ -0,0 +1,195 @@
+use console::style;
+use cubist_config::paths::Paths;
+use cubist_config::Config;
+use cubist_sdk::core::{AdminTx, Contract, Cubist, ShimRoles};
+use cubist_sdk::Http;
+use eyre::{bail, eyre, Result};
+use std::sync::Arc;
+
+use crate::commands::deploy::load_deployments;
+
+/// Loads the deployed contracts and returns the one named `name`.
+async fn deployed_contract(
+    cubist: &Cubist<Http>,
+    paths: &Paths,
+    name: &str,
+) -> Result<Arc<Contract<Http>>> {
+    load_deployments(cubist, paths).await?;
+    let contract = cubist
+        .contract(name)
+        .ok_or_else(|| eyre!("Contract '{name}' not found. Did you run 'cubist build'?"))?;
+    if !contract.is_deployed() {
+        bail!("Contract '{name}' is not deployed");
+    }
+    Ok(contract)
+}
+
+/// Command that prints the current role holders (owner, pending owner, approved callers, and
+/// guardians) of the shims of deployed contracts, per chain, and whether they are paused.
+///
+/// # Arguments
+///
+/// * `cfg`      - Cubist config
+/// * `contract` - Only print the shims of the contract with this name
+pub async fn roles(cfg: Config, contract: Option<String>) -> Result<()> {
+    let paths = cfg.paths();
+    let cubist = Cubist::<Http>::new(cfg).await?;
+    match &contract {
+        Some(name) => {
+            deployed_contract(&cubist, &paths, name).await?;
+        }
+        None => load_deployments(&cubist, &paths).await?,
+    }
+
+    let mut contracts: Vec<_> = cubist
+        .contracts()
+        .filter(|c| {
+            contract
+                .as_ref()
+                .map_or(true, |name| &c.meta.fqn.name == name)
+        })
+        .filter(|c| c.is_deployed() && !c.shims.is_empty())
+        .collect();
+    if contracts.is_empty() {
+        println!("No deployed contracts with shims");
+        return Ok(());
+    }
+    contracts.sort_by_key(|c| c.full_name_with_target());
+    for c in contracts {
+        println!("{}", style(c.name_with_target_and_address()).bold());
+        for roles in c.shim_roles().await? {
+            for line in format_roles(&roles) {
+                println!("{line}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Formats the role holders of a shim (see [`roles`]), one line each.
+fn format_roles(roles: &ShimRoles) -> Vec<String> {
+    let mut lines = vec![format!(
+        "  {} shim at {:?}",
+        style(roles.target).bold().blue(),
+        roles.address
+    )];
+    if roles.paused {
+        lines.push(format!("    {}", style("PAUSED").red().bold()));
+    }
+    lines.push(format!("    owner:         {:?}", roles.owner));
+    if let Some(pending_owner) = roles.pending_owner {
+        lines.push(format!("    pending owner: {pending_owner:?}"));
+    }
+    if roles.callers.is_empty() {
+        lines.push("    callers:       (none)".to_owned());
+    }
+    for caller in &roles.callers {
+        lines.push(format!("    caller:        {caller:?}"));
+    }
+    for guardian in &roles.guardians {
+        lines.push(format!("    guardian:      {guardian:?}"));
+    }
+    lines
+}
+
+/// Command that pauses (or unpauses) every shim of a deployed contract on every chain (see
+/// [`Contract::pause_shims`]), printing the outcome on each chain.  Fails if the shims could not
+/// be paused on some chain (after trying all of them).
+///
+/// # Arguments
+///
+/// * `cfg`      - Cubist config
+/// * `contract` - Name of the contract whose shims to pause
+/// * `unpause`  - Unpause the shims instead
+pub async fn pause(cfg: Config, contract: &str, unpause: bool) -> Result<()> {
+    let paths = cfg.paths();
+    let cubist = Cubist::<Http>::new(cfg).await?;
+    let contract = deployed_contract(&cubist, &paths, contract).await?;
+    if contract.shims.is_empty() {
+        bail!("Contract '{}' has no shims", contract.meta.fqn.name);
+    }
+
+    let (action, verb, outcomes) = match unpause {
+        false => ("pause", "Paused", contract.pause_shims().await?),
+        true => ("unpause", "Unpaused", contract.unpause_shims().await?),
+    };
+    let mut failed = vec![];
+    for (target, outcome) in outcomes {
+        let target_name = style(target).bold().blue();
+        match outcome {
+            Ok(AdminTx::Sent(receipt)) => println!(
+                "{} {target_name} (tx {:?})",
+                style(verb).bold().green(),
+                receipt.transaction_hash
+            ),
+            Ok(AdminTx::Proposed { safe, batch }) => println!(
+                "{} {target_name}: proposed to Safe {safe:?} (import {})",
+                style("Pending").bold().yellow(),
+                batch.display()
+            ),
+            Err(e) => {
+                println!("{} {target_name}: {e}", style("FAILED").bold().red());
+                failed.push(target.to_string());
+            }
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "Failed to {action} shims of '{}' on {}",
+            contract.meta.fqn.name,
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cubist_config::Target;
+    use ethers_core::types::Address;
+
+    #[test]
+    fn test_format_roles() {
+        let mut roles = ShimRoles {
+            target: Target::Polygon,
+            address: Address::repeat_byte(0xaa),
+            owner: Address::repeat_byte(1),
+            pending_owner: None,
+            callers: vec![],
+            guardians: vec![],
+            paused: false,
+        };
+        let lines = |roles: &ShimRoles| {
+            format_roles(roles)
+                .iter()
+                .map(|line| console::strip_ansi_codes(line).into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                format!("  polygon shim at {:?}", roles.address),
+                format!("    owner:         {:?}", roles.owner),
+                "    callers:       (none)".to_owned(),
+            ],
+            lines(&roles)
+        );
+
+        roles.paused = true;
+        roles.pending_owner = Some(Address::repeat_byte(2));
+        roles.callers = vec![Address::repeat_byte(3), Address::repeat_byte(4)];
+        roles.guardians = vec![Address::repeat_byte(5)];
+        assert_eq!(
+            vec![
+                format!("  polygon shim at {:?}", roles.address),
+                "    PAUSED".to_owned(),
+                format!("    owner:         {:?}", roles.owner),
+                format!("    pending owner: {:?}", Address::repeat_byte(2)),
+                format!("    caller:        {:?}", Address::repeat_byte(3)),
+                format!("    caller:        {:?}", Address::repeat_byte(4)),
+                format!("    guardian:      {:?}", Address::repeat_byte(5)),
+            ],
+            lines(&roles)
+        );
+    }
+}
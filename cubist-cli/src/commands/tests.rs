@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod commad_tests {
+    use crate::commands::artifacts::{self, BUNDLE_FILE_NAME};
     use crate::commands::compile::compile;
     use crate::commands::new;
     use crate::commands::pre_compile::pre_compile;
+    use crate::cube::import::ImportedProject;
     use cubist_config::{paths::Paths, *};
+    use cubist_sdk::gen::multicall::MULTICALL_FILE;
     use std::{collections::HashMap, fs, path::PathBuf};
     use tempfile::tempdir;
 
@@ -210,4 +213,266 @@ mod commad_tests {
         assert_compiler_artifact(Target::Polygon, "PolySender.sol", "PolySender");
         assert_compiler_artifact(Target::Polygon, "EthUtil.sol", "EthUtil");
     }
+
+    #[test]
+    fn test_pre_compile_remapped_import() {
+        let tmp = tempdir().unwrap().into_path();
+        new::empty("my-app", ProjType::JavaScript, &tmp, false).unwrap();
+        let app_dir = tmp.join("my-app");
+
+        // '@lib/Math.sol' is remapped to 'lib/Math.sol' (outside of the contracts root)
+        fs::create_dir_all(app_dir.join("contracts")).unwrap();
+        fs::create_dir_all(app_dir.join("lib")).unwrap();
+        fs::write(
+            app_dir.join("lib").join("Math.sol"),
+            r#"
+            // SPDX-License-Identifier: UNLICENSED
+            pragma solidity >=0.8.16;
+            library Math {
+                function double(uint256 x) internal pure returns (uint256) { return 2 * x; }
+            }"#,
+        )
+        .unwrap();
+        let eth_util = format!("import '@lib/Math.sol'; {ETH_UTIL_CONTRACT}");
+        fs::write(app_dir.join("contracts").join("EthUtil.sol"), &eth_util).unwrap();
+        fs::write(
+            app_dir.join("cubist-config.json"),
+            serde_json::json!({
+                "type": "JavaScript",
+                "build_dir": "build",
+                "deploy_dir": "deploy",
+                "allow_import_from_external": false,
+                "contracts": {
+                    "root_dir": "contracts",
+                    "targets": {
+                        "ethereum": {
+                            "files": [ "./contracts/EthUtil.sol" ],
+                        }
+                    },
+                    "solc": {
+                        "remappings": [ "@lib/=lib/" ]
+                    }
+                },
+                "network_profiles": {
+                    "default": {}
+                },
+                "current_network_profile": "default",
+                "bridge_provider": "Cubist"
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let cfg = Config::from_file(app_dir.join("cubist-config.json")).unwrap();
+
+        // the remapped import is found (rather than reported as a missing external import)
+        pre_compile(&cfg).unwrap_or_else(|err| {
+            panic!("{:?}", err);
+        });
+        let copied = cfg
+            .build_dir()
+            .join(Target::Ethereum)
+            .join("contracts")
+            .join("EthUtil.sol");
+        assert_eq!(eth_util, fs::read_to_string(copied).unwrap());
+    }
+
+    #[test]
+    fn test_pre_compile_multicall() {
+        let tmp = tempdir().unwrap().into_path();
+        new::empty("my-app", ProjType::JavaScript, &tmp, false).unwrap();
+        let app_dir = tmp.join("my-app");
+
+        // EthUtil.sol and AvaReceiver.sol pin different compiler versions
+        let eth_util = ETH_UTIL_CONTRACT.replace(">=0.8.16", "^0.8.17");
+        fs::create_dir_all(app_dir.join("contracts")).unwrap();
+        fs::write(app_dir.join("contracts").join("EthUtil.sol"), &eth_util).unwrap();
+        fs::write(
+            app_dir.join("contracts").join("AvaReceiver.sol"),
+            AVA_RECEIVER_CONTRACT,
+        )
+        .unwrap();
+        fs::write(
+            app_dir.join("cubist-config.json"),
+            serde_json::json!({
+                "type": "JavaScript",
+                "build_dir": "build",
+                "deploy_dir": "deploy",
+                "contracts": {
+                    "root_dir": "contracts",
+                    "targets": {
+                        "ethereum": {
+                            "files": [ "./contracts/EthUtil.sol" ],
+                        },
+                        "avalanche": {
+                            "files": [ "./contracts/AvaReceiver.sol" ],
+                        }
+                    }
+                },
+                "network_profiles": {
+                    "default": {
+                        "avalanche": {
+                            "url": "http://localhost:9560",
+                            "multicall": "0xcA11bde05977b3631167028862bE2a173976CA11"
+                        }
+                    }
+                },
+                "current_network_profile": "default",
+                "bridge_provider": "Cubist"
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let cfg = Config::from_file(app_dir.join("cubist-config.json")).unwrap();
+        pre_compile(&cfg).unwrap_or_else(|err| {
+            panic!("{:?}", err);
+        });
+
+        let multicall_file = |target: Target| {
+            cfg.build_dir()
+                .join(target)
+                .join("contracts")
+                .join(MULTICALL_FILE)
+        };
+        let in_manifest = |target: Target| {
+            PreCompileManifest::from_file(&Paths::new(&cfg).for_target(target).manifest)
+                .unwrap()
+                .files
+                .iter()
+                .any(|f| f.rel_path == PathBuf::from(MULTICALL_FILE))
+        };
+
+        // generated with the pragma of the contracts on Ethereum
+        let multicall = fs::read_to_string(multicall_file(Target::Ethereum)).unwrap();
+        assert!(multicall.contains("pragma solidity ^0.8.17;"));
+        assert!(!multicall.contains("^0.8.0"));
+        assert!(in_manifest(Target::Ethereum));
+
+        // not generated on Avalanche, which uses an existing Multicall3 contract
+        assert!(!multicall_file(Target::Avalanche).exists());
+        assert!(!in_manifest(Target::Avalanche));
+    }
+
+    #[test]
+    fn test_import_foundry_project_and_compile() {
+        let tmp = tempdir().unwrap().into_path();
+        fs::write(
+            tmp.join("foundry.toml"),
+            r#"
+            [profile.default]
+            src = "src"
+            libs = ["lib"]
+            remappings = ["@openzeppelin/=lib/openzeppelin-contracts/"]
+            "#,
+        )
+        .unwrap();
+        // remappings without the '@' prefix (auto-detected by 'forge') come from remappings.txt
+        fs::write(tmp.join("remappings.txt"), "solmate/=lib/solmate/src/\n").unwrap();
+        let oz = tmp
+            .join("lib")
+            .join("openzeppelin-contracts")
+            .join("contracts")
+            .join("access");
+        fs::create_dir_all(&oz).unwrap();
+        fs::write(
+            oz.join("Ownable.sol"),
+            r#"
+            // SPDX-License-Identifier: UNLICENSED
+            pragma solidity >=0.8.16;
+            contract Ownable {
+                address public owner;
+                constructor() { owner = msg.sender; }
+            }"#,
+        )
+        .unwrap();
+        let solmate = tmp.join("lib").join("solmate").join("src");
+        fs::create_dir_all(&solmate).unwrap();
+        fs::write(
+            solmate.join("Math.sol"),
+            r#"
+            // SPDX-License-Identifier: UNLICENSED
+            pragma solidity >=0.8.16;
+            library Math {
+                function double(uint256 x) internal pure returns (uint256) { return 2 * x; }
+            }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(tmp.join("src")).unwrap();
+        let counter = r#"
+            // SPDX-License-Identifier: UNLICENSED
+            pragma solidity >=0.8.16;
+            import "@openzeppelin/contracts/access/Ownable.sol";
+            import "solmate/Math.sol";
+            contract Counter is Ownable {
+                uint256 public number;
+                function inc() public { number = Math.double(number) + 1; }
+            }"#;
+        fs::write(tmp.join("src").join("Counter.sol"), counter).unwrap();
+
+        // import (without relying on a locally installed 'forge'), then reload the generated config
+        let project = ImportedProject::from_foundry_with(&tmp, None).unwrap();
+        let cfg = new::write_imported(&project, ProjType::JavaScript, true).unwrap();
+        let cfg = Config::from_file(&cfg.config_path).unwrap();
+
+        pre_compile(&cfg).unwrap_or_else(|err| {
+            panic!("{:?}", err);
+        });
+        let copied = cfg
+            .build_dir()
+            .join(Target::Ethereum)
+            .join("contracts")
+            .join("Counter.sol");
+        assert_eq!(counter, fs::read_to_string(copied).unwrap());
+        compile(&cfg).unwrap_or_else(|err| {
+            panic!("{:?}", err);
+        });
+    }
+
+    #[test]
+    fn test_artifacts_export() {
+        let tmp = tempdir().unwrap().into_path();
+        let eth_sender = format!(
+            "import './AvaReceiver.sol'; import './EthUtil.sol'; {}",
+            ETH_SENDER_CONTRACT
+        );
+        let poly_sender = format!("import './EthUtil.sol'; {}", POLY_SENDER_CONTRACT);
+        let cfg = new_simple_solc_project(
+            &tmp,
+            &eth_sender,
+            ETH_UTIL_CONTRACT,
+            &poly_sender,
+            AVA_RECEIVER_CONTRACT,
+        );
+        pre_compile(&cfg).unwrap();
+        compile(&cfg).unwrap();
+
+        let out_dir = tmp.join("export");
+        artifacts::export(cfg, Some(out_dir.clone())).unwrap();
+
+        let eth_dir = out_dir.join(Target::Ethereum);
+        let bundle: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(eth_dir.join(BUNDLE_FILE_NAME)).unwrap())
+                .unwrap();
+        assert_eq!("ethereum", bundle["target"]);
+        let contracts = bundle["contracts"].as_array().unwrap();
+        let find = |name: &str| {
+            contracts
+                .iter()
+                .find(|c| c["name"] == name)
+                .unwrap_or_else(|| panic!("Contract {name} not exported"))
+        };
+
+        let eth_util = find("EthUtil");
+        assert_eq!(false, eth_util["isShim"]);
+        assert_eq!("EthUtil.sol", eth_util["source"]);
+        assert!(eth_util["deployedBytecode"].is_string());
+        assert!(eth_util["sourceMap"].is_string());
+        assert!(eth_util["storageLayout"].is_object());
+        assert!(eth_util["methodIdentifiers"]["baz()"].is_string());
+        // the referenced build info is copied next to the bundle
+        let build_info = eth_util["buildInfo"].as_str().unwrap();
+        assert!(eth_dir.join(build_info).is_file());
+
+        // shims of contracts on other targets are exported as well
+        assert_eq!(true, find("AvaReceiver")["isShim"]);
+    }
 }
@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use console::style;
+use cubist_config::paths::hex;
+use cubist_config::Config;
+use cubist_sdk::core::{Cubist, TxBundle};
+use cubist_sdk::Http;
+use eyre::{bail, Result};
+
+/// Command that broadcasts a signed transaction bundle (see [`TxBundle`]), as written by
+/// `cubist deploy --unsigned` and then signed offline, and completes the deployments it makes
+/// (saving their deployment manifests).
+///
+/// # Arguments
+///
+/// * `cfg`         - Cubist config
+/// * `bundle_file` - Signed transaction bundle
+pub async fn broadcast(cfg: Config, bundle_file: &Path) -> Result<()> {
+    let bundle = TxBundle::from_file(bundle_file)?;
+    let unsigned = bundle
+        .transactions
+        .iter()
+        .filter(|utx| utx.signed.is_none())
+        .count();
+    if unsigned > 0 {
+        bail!(
+            "{unsigned} transaction(s) in {} are not signed",
+            bundle_file.display()
+        );
+    }
+
+    let cubist = Cubist::<Http>::new(cfg).await?;
+    println!(
+        "{} {} transaction(s)",
+        style("Broadcasting").bold().green(),
+        bundle.transactions.len()
+    );
+    for utx in &bundle.transactions {
+        println!(
+            " - {} (nonce {} on {})",
+            utx.description,
+            utx.nonce(),
+            utx.target
+        );
+    }
+    let receipts = cubist.broadcast(&bundle).await?;
+    println!("Confirmed {} transaction(s)", receipts.len());
+    for manifest in &bundle.deployments {
+        println!(
+            "{} {} on {} at 0x{}",
+            style("Deployed").bold().green(),
+            manifest.contract.name,
+            manifest.deployment.target,
+            hex(&manifest.deployment.address)
+        );
+    }
+    Ok(())
+}
@@ -9,6 +9,7 @@ use std::path::Path;
 use self::{js::JavaScript, rs::Rust, ts::TypeScript};
 
 pub mod git;
+pub mod import;
 pub mod js;
 pub mod rs;
 pub mod template;
@@ -56,7 +57,21 @@ pub fn new_base_cube(proj_ty: ProjType, proj_dir: &Path, force: bool) -> Result<
     fs::create_dir_all(proj_dir).context("Failed to create project directory")?;
     // create config file
     let cfg: Config = Config::new(proj_ty, proj_dir)?;
+    if !write_config(&cfg, force)? {
+        return Ok(());
+    }
+    // Create contracts directory if it doesn't exist
+    fs::create_dir_all(&cfg.contracts().root_dir).context("Failed to create context directory")
+}
 
+/// Save config to its file.  Returns `false` if the file already exists and the user chose not
+/// to overwrite it.
+///
+/// # Arguments
+///
+/// * `cfg`   - Config to save
+/// * `force` - Force overwrite existing config file if it exists
+pub fn write_config(cfg: &Config, force: bool) -> Result<bool> {
     // If config file already exists and no overwrite, check with user
     let mut proceed = false;
     if cfg.config_path.is_file() && !force {
@@ -67,7 +82,7 @@ pub fn new_base_cube(proj_ty: ProjType, proj_dir: &Path, force: bool) -> Result<
             ))
             .interact()?;
         if !proceed {
-            return Ok(());
+            return Ok(false);
         }
     }
     cfg.to_file(force || proceed).with_context(|| {
@@ -76,6 +91,5 @@ pub fn new_base_cube(proj_ty: ProjType, proj_dir: &Path, force: bool) -> Result<
             cfg.config_path.display()
         )
     })?;
-    // Create contracts directory if it doesn't exist
-    fs::create_dir_all(&cfg.contracts().root_dir).context("Failed to create context directory")
+    Ok(true)
 }
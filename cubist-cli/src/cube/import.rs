@@ -0,0 +1,526 @@
+//! Support for importing existing Foundry and Hardhat projects.
+use console::user_attended;
+use cubist_config::{
+    Config, ContractsConfig, GlobsOrPaths, OptimizerSettings, ProjType, SolcSettings, Target,
+    TargetConfig, TargetConfigs,
+};
+use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
+use eyre::{bail, eyre, Result, WrapErr};
+use scopeguard::defer;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use tracing::{debug, warn};
+
+/// Targets contracts from an imported project can be assigned to.
+const EVM_TARGETS: [Target; 4] = [
+    Target::Ethereum,
+    Target::Polygon,
+    Target::Avalanche,
+    Target::AvaSubnet,
+];
+
+/// Prefix of the line our Hardhat script prints the resolved config on.
+const HARDHAT_OUTPUT_PREFIX: &str = "CUBIST_HARDHAT_CONFIG:";
+
+/// Script run with `npx hardhat run` to dump the (resolved) Hardhat config.
+const HARDHAT_SCRIPT: &str = r#"
+const hre = require("hardhat");
+console.log("CUBIST_HARDHAT_CONFIG:" + JSON.stringify({
+  sources: hre.config.paths.sources,
+  compilers: hre.config.solidity.compilers,
+  overrides: hre.config.solidity.overrides,
+}));
+"#;
+
+/// Build tool of an imported project.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportSource {
+    /// Foundry project (configured via `foundry.toml`)
+    Foundry,
+    /// Hardhat project (configured via `hardhat.config.{js,ts}`)
+    Hardhat,
+}
+
+/// Contract-related settings extracted from an existing project.
+#[derive(Debug)]
+pub struct ImportedProject {
+    /// Project directory
+    pub dir: PathBuf,
+    /// Contracts root directory (relative to [`Self::dir`])
+    pub root_dir: PathBuf,
+    /// Paths to search for imports (relative to [`Self::dir`])
+    pub import_dirs: Vec<PathBuf>,
+    /// Compiler settings
+    pub solc: SolcSettings,
+}
+
+impl ImportedProject {
+    /// Extract contract settings from a project.
+    ///
+    /// # Arguments
+    /// * `source` - build tool the project uses
+    /// * `dir`    - project directory
+    pub fn load(source: ImportSource, dir: &Path) -> Result<Self> {
+        match source {
+            ImportSource::Foundry => Self::from_foundry(dir),
+            ImportSource::Hardhat => Self::from_hardhat(dir),
+        }
+    }
+
+    /// Extract contract settings from the default profile of a Foundry project.
+    pub fn from_foundry(dir: &Path) -> Result<Self> {
+        Self::from_foundry_with(dir, forge_remappings(dir))
+    }
+
+    /// Same as [`Self::from_foundry`], given the output of `forge remappings` (or [`None`] if
+    /// `forge` could not be run).
+    pub(crate) fn from_foundry_with(dir: &Path, forge_remappings: Option<String>) -> Result<Self> {
+        let foundry_toml = dir.join("foundry.toml");
+        let contents = fs::read_to_string(&foundry_toml)
+            .wrap_err_with(|| format!("Could not read {}", foundry_toml.display()))?;
+        let tml: toml::Table = toml::from_str(&contents)
+            .wrap_err_with(|| format!("Malformed {}", foundry_toml.display()))?;
+        let profile = tml
+            .get("profile")
+            .and_then(|p| p.get("default"))
+            .and_then(|p| p.as_table())
+            .cloned()
+            .unwrap_or_default();
+
+        let str_list = |key: &str| -> Option<Vec<String>> {
+            profile.get(key).and_then(|v| v.as_array()).map(|vs| {
+                vs.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+        };
+
+        let root_dir = profile
+            .get("src")
+            .and_then(|v| v.as_str())
+            .unwrap_or("src")
+            .into();
+        let mut import_dirs: Vec<PathBuf> = str_list("libs")
+            .unwrap_or_else(|| vec!["lib".to_string()])
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        if dir.join("node_modules").is_dir() {
+            import_dirs.push("node_modules".into());
+        }
+
+        let optimizer_runs = profile
+            .get("optimizer_runs")
+            .and_then(|v| v.as_integer())
+            .map_or(200, |runs| runs as usize);
+        let solc = SolcSettings {
+            optimizer: profile
+                .get("optimizer")
+                .and_then(|v| v.as_bool())
+                .map(|enabled| OptimizerSettings {
+                    enabled,
+                    runs: optimizer_runs,
+                }),
+            via_ir: profile.get("via_ir").and_then(|v| v.as_bool()),
+            evm_version: parse_setting(profile.get("evm_version").and_then(|v| v.as_str()))?,
+            metadata_hash: parse_setting(profile.get("bytecode_hash").and_then(|v| v.as_str()))?,
+            remappings: foundry_remappings(
+                dir,
+                str_list("remappings").unwrap_or_default(),
+                forge_remappings,
+            ),
+        };
+
+        Ok(ImportedProject {
+            dir: dir.to_path_buf(),
+            root_dir,
+            import_dirs,
+            solc,
+        })
+    }
+
+    /// Extract contract settings from a Hardhat project.  The Hardhat config is resolved by
+    /// running a script through `npx hardhat run`, so the project dependencies must be installed.
+    pub fn from_hardhat(dir: &Path) -> Result<Self> {
+        if !["js", "cjs", "ts"]
+            .iter()
+            .any(|ext| dir.join(format!("hardhat.config.{ext}")).is_file())
+        {
+            bail!("No Hardhat config found in {}", dir.display());
+        }
+        // Hardhat reports canonical paths
+        let dir = &fs::canonicalize(dir)?;
+
+        let script = dir.join(".cubist-import-hardhat.cjs");
+        fs::write(&script, HARDHAT_SCRIPT)
+            .wrap_err_with(|| format!("Could not write {}", script.display()))?;
+        defer! {
+            if let Err(e) = fs::remove_file(&script) {
+                warn!("Could not remove {}: {e}", script.display());
+            }
+        }
+
+        let output = Command::new("npx")
+            .args(["--no-install", "hardhat", "run"])
+            .arg(&script)
+            .current_dir(dir)
+            .output()
+            .wrap_err("Failed to run 'npx hardhat'")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to load Hardhat config (did you install the project's dependencies?): {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json = stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(HARDHAT_OUTPUT_PREFIX))
+            .ok_or_else(|| eyre!("Hardhat did not print its config"))?;
+        let hh_config: Value = serde_json::from_str(json).wrap_err("Malformed Hardhat config")?;
+        debug!("Resolved Hardhat config: {hh_config}");
+
+        let root_dir = hh_config["sources"]
+            .as_str()
+            .map(PathBuf::from)
+            .and_then(|src| src.strip_prefix(dir).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| "contracts".into());
+        Ok(ImportedProject {
+            dir: dir.to_path_buf(),
+            root_dir,
+            import_dirs: vec!["node_modules".into()],
+            solc: solc_settings_from_hardhat(&hh_config)?,
+        })
+    }
+
+    /// Return all contract files (relative to [`Self::dir`]) of the project.  Foundry tests and
+    /// scripts (`*.t.sol` and `*.s.sol`) are skipped.
+    pub fn contract_files(&self) -> Result<Vec<PathBuf>> {
+        let pattern = self.dir.join(&self.root_dir).join("**").join("*.sol");
+        let mut files = glob::glob(&pattern.to_string_lossy())?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|f| {
+                let name = f.to_string_lossy();
+                !name.ends_with(".t.sol") && !name.ends_with(".s.sol")
+            })
+            .filter_map(|f| f.strip_prefix(&self.dir).ok().map(Path::to_path_buf))
+            .collect::<Vec<_>>();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Create a Cubist config (in [`Self::dir`]) with the contract files assigned to targets.
+    ///
+    /// # Arguments
+    /// * `type_`   - Project type
+    /// * `targets` - Contract files assigned to each target
+    pub fn to_config(
+        &self,
+        type_: ProjType,
+        targets: HashMap<Target, Vec<PathBuf>>,
+    ) -> Result<Config> {
+        let mut cfg = Config::new(type_, &self.dir)?;
+        cfg.set_contracts(ContractsConfig {
+            root_dir: self.root_dir.clone(),
+            targets: targets
+                .into_iter()
+                .map(|(target, files)| {
+                    let files = files.into_iter().map(Into::into).collect();
+                    let target_config = TargetConfig::EvmTargetConfig {
+                        files: GlobsOrPaths::Globs(files),
+                        compiler: Default::default(),
+                        solc: Default::default(),
+                    };
+                    (target, target_config)
+                })
+                .collect::<TargetConfigs>(),
+            import_dirs: self.import_dirs.clone(),
+            solidity_dependencies: HashMap::new(),
+            vendor: None,
+            solc: self.solc.clone(),
+        })?;
+        Ok(cfg)
+    }
+}
+
+/// Ask the user to assign contract files to targets.  If the user isn't attended (e.g., when
+/// running in CI), all files are assigned to Ethereum.
+///
+/// # Arguments
+/// * `files` - Contract files to assign
+pub fn assign_targets(files: &[PathBuf]) -> Result<HashMap<Target, Vec<PathBuf>>> {
+    if files.is_empty() {
+        bail!("No contract files found");
+    }
+    if !user_attended() {
+        return Ok(HashMap::from([(Target::Ethereum, files.to_vec())]));
+    }
+
+    let theme = ColorfulTheme::default();
+    let targets = loop {
+        let selected = MultiSelect::with_theme(&theme)
+            .with_prompt("Which chains do you want to deploy to? (space to select)")
+            .items(&EVM_TARGETS)
+            .defaults(&[true])
+            .interact()?;
+        if !selected.is_empty() {
+            break selected
+                .into_iter()
+                .map(|i| EVM_TARGETS[i])
+                .collect::<Vec<_>>();
+        }
+    };
+
+    let mut result: HashMap<Target, Vec<PathBuf>> = HashMap::new();
+    for file in files {
+        let target = match targets.as_slice() {
+            [target] => *target,
+            _ => {
+                let i = Select::with_theme(&theme)
+                    .with_prompt(format!("Target chain for {}", file.display()))
+                    .items(&targets)
+                    .default(0)
+                    .interact()?;
+                targets[i]
+            }
+        };
+        result.entry(target).or_default().push(file.clone());
+    }
+    Ok(result)
+}
+
+/// Run `forge remappings` in a Foundry project, returning its output (or [`None`] if `forge`
+/// is not installed or fails).
+fn forge_remappings(dir: &Path) -> Option<String> {
+    let output = Command::new("forge")
+        .arg("remappings")
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into())
+}
+
+/// Collect the remappings of a Foundry project.  We prefer the output of `forge remappings`
+/// (which also auto-detects remappings for libraries) and otherwise fall back to the remappings
+/// from `foundry.toml` and `remappings.txt`.
+fn foundry_remappings(
+    dir: &Path,
+    mut configured: Vec<String>,
+    forge_remappings: Option<String>,
+) -> Vec<String> {
+    let lines = forge_remappings.unwrap_or_else(|| {
+        debug!("Could not run 'forge remappings'; reading remappings.txt instead");
+        fs::read_to_string(dir.join("remappings.txt")).unwrap_or_default()
+    });
+    for remapping in lines.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if !configured.iter().any(|r| r == remapping) {
+            configured.push(remapping.to_string());
+        }
+    }
+    configured
+}
+
+/// Translate the settings of all compilers (including the per-file overrides) from a (resolved)
+/// Hardhat config.  Cubist compiles all contracts (of a target) with the same settings, so the
+/// settings of all compilers must agree.
+fn solc_settings_from_hardhat(hh_config: &Value) -> Result<SolcSettings> {
+    let overrides = hh_config["overrides"]
+        .as_object()
+        .into_iter()
+        .flat_map(|o| o.values());
+    let compilers = hh_config["compilers"].as_array().into_iter().flatten();
+    let mut result: Option<(SolcSettings, &Value)> = None;
+    for compiler in compilers.chain(overrides) {
+        let settings = solc_settings_from_hardhat_compiler(&compiler["settings"])?;
+        match &result {
+            None => result = Some((settings, &compiler["version"])),
+            Some((first, _)) if *first == settings => {}
+            Some((_, version)) => bail!(
+                "Hardhat compilers {version} and {} have different settings, but Cubist compiles all contracts with the same settings",
+                compiler["version"]
+            ),
+        }
+    }
+    Ok(result.map(|(settings, _)| settings).unwrap_or_default())
+}
+
+/// Translate the `settings` of a single compiler from a (resolved) Hardhat config.
+fn solc_settings_from_hardhat_compiler(settings: &Value) -> Result<SolcSettings> {
+    let optimizer = &settings["optimizer"];
+    Ok(SolcSettings {
+        optimizer: optimizer["enabled"]
+            .as_bool()
+            .map(|enabled| OptimizerSettings {
+                enabled,
+                runs: optimizer["runs"].as_u64().map_or(200, |runs| runs as usize),
+            }),
+        via_ir: settings["viaIR"].as_bool(),
+        evm_version: parse_setting(settings["evmVersion"].as_str())?,
+        metadata_hash: parse_setting(settings["metadata"]["bytecodeHash"].as_str())?,
+        remappings: settings["remappings"]
+            .as_array()
+            .map(|rs| {
+                rs.iter()
+                    .filter_map(|r| r.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Parse an optional setting (e.g., [`EvmVersion`] or [`MetadataHash`]) from its string value.
+fn parse_setting<T: FromStr>(value: Option<&str>) -> Result<Option<T>> {
+    value
+        .map(|v| T::from_str(v).map_err(|_| eyre!("Unsupported compiler setting '{v}'")))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cubist_config::{EvmVersion, MetadataHash};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_from_foundry() {
+        let tmp = tempdir().unwrap();
+        fs::write(
+            tmp.path().join("foundry.toml"),
+            r#"
+            [profile.default]
+            src = "src"
+            libs = ["lib"]
+            optimizer = true
+            optimizer_runs = 10000
+            evm_version = "paris"
+            bytecode_hash = "none"
+            remappings = ["@oz/=lib/openzeppelin-contracts/"]
+            "#,
+        )
+        .unwrap();
+        fs::create_dir_all(tmp.path().join("src").join("test")).unwrap();
+        fs::write(tmp.path().join("src").join("Counter.sol"), "").unwrap();
+        fs::write(
+            tmp.path().join("src").join("test").join("Counter.t.sol"),
+            "",
+        )
+        .unwrap();
+
+        fs::write(
+            tmp.path().join("remappings.txt"),
+            "ds-test/=lib/forge-std/lib/ds-test/src/\n",
+        )
+        .unwrap();
+
+        let proj = ImportedProject::from_foundry_with(tmp.path(), None).unwrap();
+        assert_eq!(proj.root_dir, PathBuf::from("src"));
+        assert_eq!(proj.import_dirs, vec![PathBuf::from("lib")]);
+        assert_eq!(
+            proj.solc.optimizer,
+            Some(OptimizerSettings {
+                enabled: true,
+                runs: 10000
+            })
+        );
+        assert_eq!(proj.solc.evm_version, Some(EvmVersion::Paris));
+        assert_eq!(proj.solc.metadata_hash, Some(MetadataHash::None));
+        assert_eq!(
+            proj.solc.remappings,
+            vec![
+                "@oz/=lib/openzeppelin-contracts/".to_string(),
+                "ds-test/=lib/forge-std/lib/ds-test/src/".to_string()
+            ]
+        );
+        // 'forge remappings' (if available) takes precedence over remappings.txt
+        let forge = "@oz/=lib/openzeppelin-contracts/\nforge-std/=lib/forge-std/src/\n";
+        let with_forge =
+            ImportedProject::from_foundry_with(tmp.path(), Some(forge.to_string())).unwrap();
+        assert_eq!(
+            with_forge.solc.remappings,
+            vec![
+                "@oz/=lib/openzeppelin-contracts/".to_string(),
+                "forge-std/=lib/forge-std/src/".to_string()
+            ]
+        );
+
+        let files = proj.contract_files().unwrap();
+        assert_eq!(files, vec![PathBuf::from("src/Counter.sol")]);
+
+        let targets = HashMap::from([(Target::Polygon, files)]);
+        let cfg = proj.to_config(ProjType::TypeScript, targets).unwrap();
+        cfg.to_file(false).unwrap();
+        let cfg = Config::from_file(&cfg.config_path).unwrap();
+        assert_eq!(cfg.targets().collect::<Vec<_>>(), vec![Target::Polygon]);
+        assert_eq!(cfg.contracts().solc.evm_version, Some(EvmVersion::Paris));
+    }
+
+    #[test]
+    fn test_solc_settings_from_hardhat() {
+        let hh_config = json!({ "compilers": [{
+            "version": "0.8.17",
+            "settings": {
+                "optimizer": { "enabled": true, "runs": 1 },
+                "viaIR": true,
+                "evmVersion": "london",
+                "metadata": { "bytecodeHash": "ipfs" }
+            }
+        }]});
+        let solc = solc_settings_from_hardhat(&hh_config).unwrap();
+        assert_eq!(
+            solc.optimizer,
+            Some(OptimizerSettings {
+                enabled: true,
+                runs: 1
+            })
+        );
+        assert_eq!(solc.via_ir, Some(true));
+        assert_eq!(solc.evm_version, Some(EvmVersion::London));
+        assert_eq!(solc.metadata_hash, Some(MetadataHash::Ipfs));
+        assert!(solc.remappings.is_empty());
+    }
+
+    #[test]
+    fn test_solc_settings_from_hardhat_compilers() {
+        let settings =
+            json!({ "optimizer": { "enabled": false, "runs": 200 }, "evmVersion": "cancun" });
+        // compilers (and overrides) with the same settings are fine, whatever their versions
+        let hh_config = json!({
+            "compilers": [
+                { "version": "0.8.24", "settings": settings },
+                { "version": "0.8.25", "settings": settings },
+            ],
+            "overrides": { "contracts/Foo.sol": { "version": "0.8.26", "settings": settings } },
+        });
+        let solc = solc_settings_from_hardhat(&hh_config).unwrap();
+        assert_eq!(solc.evm_version, Some(EvmVersion::Cancun));
+
+        // but ones with different settings are not
+        let hh_config = json!({
+            "compilers": [
+                { "version": "0.8.24", "settings": settings },
+                { "version": "0.8.17", "settings": { "evmVersion": "london" } },
+            ],
+        });
+        let err = solc_settings_from_hardhat(&hh_config).unwrap_err();
+        assert!(err.to_string().contains("different settings"));
+        let hh_config = json!({
+            "compilers": [{ "version": "0.8.24", "settings": settings }],
+            "overrides": {
+                "contracts/Foo.sol": { "version": "0.8.24", "settings": { "viaIR": true } }
+            },
+        });
+        assert!(solc_settings_from_hardhat(&hh_config).is_err());
+
+        // no compilers at all means default settings
+        assert!(solc_settings_from_hardhat(&json!({})).unwrap().is_empty());
+    }
+}
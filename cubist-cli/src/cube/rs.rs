@@ -1,6 +1,7 @@
 //! Support for creating Rust projects.
 use color_eyre::owo_colors::OwoColorize;
 use console::style;
+use convert_case::{Case, Casing};
 use cubist_config::Target;
 use cubist_sdk::core::{ContractData, ContractInfo, CubistInfo};
 use cubist_util::tera::TeraEmbed;
@@ -44,6 +45,7 @@ pub fn cargo_toml(name: &str) -> Table {
         cubist-sdk = { git = cubist_git_url, package = "cubist-sdk" }
         cubist-config = { git = cubist_git_url, package = "cubist-config" }
         ethers = { version = "1.0.2", features = ["abigen"] }
+        futures = "0.3"
         tokio = "1.21.2"
         lazy_static = "1.4.0"
     }
@@ -164,6 +166,23 @@ struct TeraContract {
     pub static_name: String,
     pub target: String,
     pub shim_targets: Vec<String>,
+    pub events_type: Option<String>,
+}
+
+/// Returns the name of the type that `abigen` generates for decoding any event of contract
+/// `info`: an enum named after the contract if it declares several events, the event's filter
+/// struct if it declares exactly one, and none if it declares none (or is not a Solidity
+/// contract).
+fn events_type(info: &ContractInfo) -> Option<String> {
+    let ContractData::SolidityData { abi, .. } = &info.data else {
+        return None;
+    };
+    let mut events = abi.events();
+    match (events.next(), events.next()) {
+        (None, _) => None,
+        (Some(event), None) => Some(format!("{}Filter", event.name.to_case(Case::UpperCamel))),
+        (Some(_), Some(_)) => Some(format!("{}Events", info.fqn.name)),
+    }
 }
 
 impl Rust {
@@ -202,6 +221,7 @@ impl Rust {
                         .shim_targets(&c.fqn)
                         .map(|t| format!("{:?}", t))
                         .collect::<Vec<_>>(),
+                    events_type: events_type(c),
                 })
                 .collect::<Vec<_>>(),
         );
@@ -219,6 +239,7 @@ impl Rust {
                         .shim_targets(&c.fqn)
                         .map(|t| format!("{:?}", t))
                         .collect::<Vec<_>>(),
+                    events_type: None,
                 })
                 .collect::<Vec<_>>(),
         );
@@ -516,7 +516,11 @@ impl DaemonManager {
             defer! { manifest.delete() }
 
             let servers = match cmd {
-                StartCommand::Chains => chain_manager::start(cfg.network_profile()).await?,
+                StartCommand::Chains => {
+                    let servers = chain_manager::start(cfg.network_profile()).await?;
+                    chain_manager::deploy_multicall(&cfg).await;
+                    servers
+                }
                 StartCommand::Axelar => {
                     let relayer: Relayers = axelar::start(&cfg).await?.into();
                     relayer.send_to_background();
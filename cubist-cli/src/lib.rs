@@ -23,6 +23,14 @@
 //! cubist new --type Rust --template Storage my_storage_dapp
 //! ```
 //!
+//! If you already have a Foundry or Hardhat project, you can generate a `cubist-config.json` for
+//! it (with the contracts directory, import paths, remappings, and compiler settings carried
+//! over) instead; you'll be asked which chain each contract should be deployed to:
+//! ```bash
+//! cubist new --from-foundry path/to/foundry_project
+//! cubist new --from-hardhat path/to/hardhat_project
+//! ```
+//!
 //! Next, let's see how to build the `my_storage_dapp` with Cubist.
 //!
 //! # Working with an Existing Cubist dApp
@@ -41,6 +49,10 @@
 //! 1. `compile`: invokes a contract compiler (e.g., `solc`) to compile all relevant contracts
 //! 1. `gen`: generates strongly typed bindings for all contracts for the target language.
 //!
+//! To hand compiled contracts off to other tools (debuggers, verifiers, upgrade-safety checkers),
+//! run `cubist artifacts export`; it writes a chain-agnostic bundle per target (abi, bytecode,
+//! source maps, storage layout, method identifiers, and build info) to `{build_dir}/export`.
+//!
 //! Check out the [Cubist SDK](cubist_sdk) docs, in particular the [Background](cubist_sdk#background)
 //! and [Example](cubist_sdk#example) sections, for more information about shim contracts, why they
 //! are needed, and how Cubist breaks up a multi-chain dApp into multiple single-chain projects
@@ -86,9 +98,6 @@ pub mod cube;
 /// Management of background cubist services.
 pub mod daemon;
 
-mod deployment_watcher;
-mod stylist;
-
 #[derive(RustEmbed)]
 #[folder = "templates"]
 struct CubeTemplates;
@@ -1,9 +1,15 @@
 use clap::Parser;
 use color_eyre::eyre::{eyre, Result, WrapErr};
 use console::style;
-use cubist_cli::cli::{Cli, Commands};
-use cubist_cli::commands::{compile::compile, gen, new, pre_compile::pre_compile};
+use cubist_cli::cli::{
+    ArtifactsCommand, Cli, Commands, DeploymentsCommand, ShimsCommand, TxCommand,
+};
+use cubist_cli::commands::{
+    artifacts, compile::compile, deploy, deployments, gen, new, pre_compile::pre_compile, shims, tx,
+};
+use cubist_cli::cube::import::ImportSource;
 use cubist_cli::daemon::{DaemonManager, StartCommand};
+use cubist_config::util::OrBug;
 use cubist_config::Config;
 
 use std::env;
@@ -29,17 +35,26 @@ async fn main() -> Result<()> {
             type_,
             template,
             from_repo,
+            from_foundry,
+            from_hardhat,
             dir,
             force,
             branch,
         } => {
             let dir = dir.or_else(|| env::current_dir().ok()).unwrap();
-            if let Some(url) = from_repo {
-                new::from_git_repo(&name, &url, &dir, force)?;
-            } else if let Some(template) = template {
-                new::from_template(&name, type_, template, &dir, force, branch)?
+            if let Some(project_dir) = from_foundry {
+                new::from_existing(ImportSource::Foundry, type_, &project_dir, force)?;
+            } else if let Some(project_dir) = from_hardhat {
+                new::from_existing(ImportSource::Hardhat, type_, &project_dir, force)?;
             } else {
-                new::empty(&name, type_, &dir, force)?;
+                let name = name.or_bug("Project name is required");
+                if let Some(url) = from_repo {
+                    new::from_git_repo(&name, &url, &dir, force)?;
+                } else if let Some(template) = template {
+                    new::from_template(&name, type_, template, &dir, force, branch)?
+                } else {
+                    new::empty(&name, type_, &dir, force)?;
+                }
             }
         }
         Commands::PreCompile { config } => {
@@ -60,6 +75,64 @@ async fn main() -> Result<()> {
             let cfg = load_config(&config)?;
             gen::gen_orm(cfg)?;
         }
+        Commands::Deploy {
+            config,
+            plan,
+            dry_run,
+            estimate,
+            resume,
+            unsigned,
+        } => {
+            let cfg = load_config(&config)?;
+            deploy::deploy(cfg, plan, dry_run, estimate, resume, unsigned).await?;
+        }
+        Commands::Tx { command } => match command {
+            TxCommand::Broadcast { config, bundle } => {
+                let cfg = load_config(&config)?;
+                tx::broadcast(cfg, &bundle).await?;
+            }
+        },
+        Commands::Pause { config, contract } => {
+            shims::pause(load_config(&config)?, &contract, false).await?;
+        }
+        Commands::Unpause { config, contract } => {
+            shims::pause(load_config(&config)?, &contract, true).await?;
+        }
+        Commands::Shims { command } => {
+            match command {
+                ShimsCommand::Roles { config, contract } => {
+                    shims::roles(load_config(&config)?, contract).await?
+                }
+            }
+            // don't print "Done!" after listings
+            return Ok(());
+        }
+        Commands::Deployments { command } => {
+            match command {
+                DeploymentsCommand::List {
+                    config,
+                    contract,
+                    json,
+                } => deployments::list(load_config(&config)?, contract, json)?,
+                DeploymentsCommand::Show { config, contract } => {
+                    deployments::show(load_config(&config)?, &contract)?
+                }
+                DeploymentsCommand::Prune {
+                    config,
+                    keep,
+                    chain_id,
+                    dry_run,
+                } => deployments::prune(load_config(&config)?, keep, chain_id, dry_run)?,
+            }
+            // don't print "Done!" after listings
+            return Ok(());
+        }
+        Commands::Artifacts { command } => match command {
+            ArtifactsCommand::Export { config, out_dir } => {
+                let cfg = load_config(&config)?;
+                artifacts::export(cfg, out_dir)?;
+            }
+        },
         Commands::Start {
             config,
             args,
@@ -10,7 +10,7 @@ use ethers::{abi::{Tokenize, Address}, providers::Middleware};
 use tokio::sync::OnceCell;
 
 use cubist_config::{Config, Target};
-use cubist_sdk::{core::{Cubist, Contract, ContractAddress, TargetProject}, Http, Ws, CubistSdkError};
+use cubist_sdk::{core::{Cubist, Contract, ContractAddress, EventStream, TargetProject}, Http, Ws, CubistSdkError};
 
 pub type Result<T, E = CubistSdkError> = std::result::Result<T, E>;
 
@@ -209,13 +209,24 @@ impl<M: Middleware> {{c.struct_name}}<M> {
     }
 
     /// Returns the deployment address of this contract on chain '{{target}}'
-    /// if the contract has been deployed or `None` otherwise.
+    /// if the contract has been deployed (or its CREATE2 address can be
+    /// predicted) or `None` otherwise.
     pub fn try_get_{{target | lower}}_address(&self) -> Option<ContractAddress> {
         self.1.try_address_on(Target::{{target}})
     }
     {% endfor %}
 }
 
+{%- if c.events_type %}
+impl<M: Middleware + 'static> {{c.struct_name}}<M> {
+    /// Streams the (strongly typed) events emitted by this contract, starting with block
+    /// `from_block` (to backfill past events) or else with the next block.
+    pub fn event_stream(&self, from_block: Option<u64>) -> Result<EventStream<{{c.rs_mod_name}}::{{c.events_type}}>> {
+        self.1.typed_events(from_block)
+    }
+}
+{% endif %}
+
 impl<M: Middleware> std::ops::Deref for {{c.struct_name}}<M> {
     type Target = {{c.rs_mod_name}}::{{c.struct_name}}<M>;
     fn deref(&self) -> &Self::Target {
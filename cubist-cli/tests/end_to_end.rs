@@ -1,16 +1,18 @@
 use assert_matches::assert_matches;
 use color_eyre::owo_colors::OwoColorize;
 use cubist_cli::{
-    commands::relayer::{Relayer, RelayerConfig},
     cube::template::Template,
     daemon::{CubistServerKind, DaemonFilter, DaemonManager, StartArgs, StartCommand, StartMode},
 };
 use cubist_config::{paths, util::OrBug, Config, ProjType, Target};
 use cubist_sdk::{
     core::{
-        Contract, Cubist, DeploymentInfo, DeploymentManifest, TargetProject, TargetProjectInfo,
+        AdminTx, Contract, Cubist, DeploymentEvent, DeploymentHistory, DeploymentInfo,
+        DeploymentManifest, TargetProject, TargetProjectInfo,
     },
     gen::APPROVE_CALLER_METHOD_NAME,
+    relayer::{Relayer, RelayerConfig},
+    testing::TestNetwork,
     CubistSdkError, Http, Ws,
 };
 use cubist_util::proc::SIGINT;
@@ -217,7 +219,7 @@ async fn deploy_and_test_api_mid(cfg: Config) -> Result<()> {
     println!("Deploying");
     let val = U256::from(42);
     receiver.deploy(val).await?;
-    assert_deployment_manifest(&receiver)?;
+    assert_deployment_manifest(&receiver).await?;
 
     // deploy StorageSender
     sender
@@ -226,7 +228,7 @@ async fn deploy_and_test_api_mid(cfg: Config) -> Result<()> {
             Address::from_slice(&receiver.address_on(sender.target())),
         ))
         .await?;
-    assert_deployment_manifest(&sender)?;
+    assert_deployment_manifest(&sender).await?;
 
     // start the bridge right away (and tell it to process up to 3 events, so that we can wait for it to finish)
     let mut relayer = create_relayer(&proj, 3);
@@ -270,10 +272,12 @@ async fn deploy_and_test_api_mid(cfg: Config) -> Result<()> {
     Ok(())
 }
 
-fn assert_receipt(proj: &TargetProject, file: &str, name: &str, addr: &[u8]) {
+async fn assert_receipt(proj: &TargetProject, file: &str, name: &str, addr: &[u8]) {
+    let chain_id = proj.chain_id().await.unwrap();
     let path = proj
         .target_paths
         .deploy_root
+        .join(chain_id.to_string())
         .join(file)
         .join(name)
         .join(paths::hex(addr))
@@ -281,7 +285,7 @@ fn assert_receipt(proj: &TargetProject, file: &str, name: &str, addr: &[u8]) {
     assert!(path.is_file(), "Receipt not found at {}", path.display());
 }
 
-fn assert_deployment_manifest(cnt: &Contract) -> Result<()> {
+async fn assert_deployment_manifest(cnt: &Contract) -> Result<()> {
     // check deployment manifest
     let manifest_path = cnt
         .project
@@ -295,14 +299,15 @@ fn assert_deployment_manifest(cnt: &Contract) -> Result<()> {
     let contents = std::fs::read_to_string(manifest_path)?;
     let manifest: DeploymentManifest = serde_json::from_str(contents.as_str())?;
 
-    let check_deployment_info = |c: &Contract, d: &DeploymentInfo| {
+    let check_deployment_info = |c: &Contract, d: &DeploymentInfo, chain_id: u64| {
         assert_eq!(c.address(), Some(d.address.clone()));
         assert_eq!(c.target(), d.target);
+        assert_eq!(Some(chain_id), d.chain_id);
     };
 
     // check info for self
     assert_eq!(cnt.meta.fqn, manifest.contract);
-    check_deployment_info(cnt, &manifest.deployment);
+    check_deployment_info(cnt, &manifest.deployment, cnt.project.chain_id().await?);
 
     // check infos for shims
     assert_eq!(cnt.shims.len(), manifest.shims.len());
@@ -317,16 +322,25 @@ fn assert_deployment_manifest(cnt: &Contract) -> Result<()> {
                     shim.full_name_with_target()
                 )
             });
-        check_deployment_info(shim, deployment_info);
+        check_deployment_info(shim, deployment_info, shim.project.chain_id().await?);
         // check regular deployment receipts too
         assert_receipt(
             &shim.project,
             shim.meta.fqn.file.to_str().unwrap(),
             &shim.meta.fqn.name,
             &shim.address().or_bug("Address not set after deployment"),
-        );
+        )
+        .await;
     }
 
+    // check that the deployment got recorded in the history
+    let history = DeploymentHistory::new(cnt.project.paths.deployment_history());
+    assert!(history.records()?.iter().any(|r| {
+        r.event == DeploymentEvent::Deployed
+            && r.contract == cnt.meta.fqn
+            && cnt.address() == Some(r.deployment.address.clone())
+    }));
+
     Ok(())
 }
 
@@ -374,7 +388,8 @@ async fn deploy_and_test_api_low(cfg: &Config) -> Result<()> {
             "StorageReceiver.sol",
             "StorageReceiver",
             &receiver.address().to_fixed_bytes(),
-        );
+        )
+        .await;
 
         // test loading the contract by its address
         let receiver_2 = ethereum.at(&receiver_meta, receiver.address());
@@ -443,13 +458,15 @@ async fn deploy_and_test_api_low(cfg: &Config) -> Result<()> {
             "StorageReceiver.sol",
             "StorageReceiver",
             &receiver.address().to_fixed_bytes(),
-        );
+        )
+        .await;
         assert_receipt(
             &polygon,
             "StorageSender.sol",
             "StorageSender",
             &sender.address().to_fixed_bytes(),
-        );
+        )
+        .await;
 
         // test loading the contract by its address
         let sender_2 = polygon.at(&sender_meta, sender.address());
@@ -627,6 +644,165 @@ async fn do_circular_foo_bar<M: Middleware + 'static>(proj: Cubist<M>) -> Result
     Ok(())
 }
 
+#[tokio::test]
+#[serial]
+#[allow(clippy::disallowed_names)]
+async fn pause_shims() -> Result<()> {
+    let tmp = tempdir()?;
+    let src_app_dir = project_fixture_dir("circular_imports");
+    let app_dir = tmp.path().join("circular_imports");
+    let mut opts = fs_extra::dir::CopyOptions::new();
+    opts.copy_inside = true;
+    fs_extra::dir::copy(&src_app_dir, &app_dir, &opts)?;
+    let cfg_path = app_dir.join("cubist-config.json");
+    let mut new_cfg: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(&cfg_path)?
+            .replace(r#""__FOO_TARGET__""#, &json!(Target::Ethereum).to_string())
+            .replace(r#""__BAR_TARGET__""#, &json!(Target::Polygon).to_string()),
+    )?;
+    // a second account on Foo's chain, to guard Bar's shim there
+    new_cfg["network_profiles"]["default"]["ethereum"]["bootstrap_mnemonic"] = json!({
+        "seed": { "secret": "test test test test test test test test test test test junk" },
+        "account_count": 2
+    });
+    fs::write(&cfg_path, serde_json::to_string_pretty(&new_cfg)?)?;
+    let cfg = Config::from_dir(&app_dir)?;
+
+    println!("Building");
+    clean(&app_dir);
+    run_cubist_cmd(app_dir, "build").await?;
+
+    defer! { stop_chains(cfg_path) }
+    println!("Starting chains");
+    start_chains(cfg.clone()).await?;
+
+    let proj = Cubist::<Http>::new(cfg).await?;
+    let foo = proj.contract("Foo").unwrap();
+    let bar = proj.contract("Bar").unwrap();
+
+    println!("Deploying");
+    foo.deploy_shims().await?;
+    bar.deploy_shims().await?;
+    foo.deploy(Address::from_slice(&bar.address_on(foo.target())))
+        .await?;
+    bar.deploy(Address::from_slice(&foo.address_on(bar.target())))
+        .await?;
+
+    let accounts = proj.accounts_on(foo.target()).await?;
+    let (owner, guardian) = (accounts[0], accounts[1]);
+
+    println!("Granting the GUARDIAN role");
+    for (target, outcome) in bar.set_shim_guardian(guardian, true).await? {
+        assert_eq!(foo.target(), target);
+        assert_matches!(outcome, AdminTx::Sent(receipt) if receipt.from == owner);
+    }
+    let roles = bar.shim_roles().await?;
+    assert_eq!(1, roles.len());
+    assert_eq!(vec![guardian], roles[0].guardians);
+    assert!(!roles[0].paused);
+
+    // The relayer (over HTTP) polls for events every 7 seconds, so it only sees the event of
+    // the first call once the shim is paused
+    let mut relayer = create_relayer(&proj, 1);
+    relayer.start().await?;
+    let dropped_val = U256::from(1);
+    foo.send("call_bar", dropped_val).await?;
+
+    println!("Pausing as the guardian");
+    let as_guardian = proj.with_sender(foo.target(), guardian)?;
+    let bar_as_guardian = as_guardian.contract("Bar").unwrap();
+    for (_, outcome) in bar_as_guardian.pause_shims().await? {
+        assert_matches!(outcome?, AdminTx::Sent(receipt) if receipt.from == guardian);
+    }
+    assert!(bar.shim_roles().await?[0].paused);
+
+    // paused shims reject calls
+    assert!(foo.send("call_bar", U256::from(3)).await.is_err());
+
+    println!("Waiting for the relayer to skip the event of the paused shim");
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    println!("Unpausing as the owner (not a guardian)");
+    bar.set_shim_guardian(guardian, false).await?;
+    for (_, outcome) in bar.unpause_shims().await? {
+        assert_matches!(outcome?, AdminTx::Sent(receipt) if receipt.from == owner);
+    }
+    let roles = bar.shim_roles().await?;
+    assert!(roles[0].guardians.is_empty());
+    assert!(!roles[0].paused);
+
+    println!("Relaying once unpaused");
+    let bar_val = U256::from(2);
+    foo.send("call_bar", bar_val).await?;
+    // the skipped event is not relayed (it would have been the only event to relay)
+    relayer.run_to_completion().await?;
+    assert_eq!(bar_val, bar.call("retrieve", ()).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+#[allow(clippy::disallowed_names)]
+async fn test_networks_in_parallel() -> Result<()> {
+    let tmp = tempdir()?;
+    let src_app_dir = project_fixture_dir("circular_imports");
+    let app_dir = tmp.path().join("circular_imports");
+    let mut opts = fs_extra::dir::CopyOptions::new();
+    opts.copy_inside = true;
+    fs_extra::dir::copy(&src_app_dir, &app_dir, &opts)?;
+    let cfg_path = app_dir.join("cubist-config.json");
+    let new_cfg_content = fs::read_to_string(&cfg_path)?
+        .replace(r#""__FOO_TARGET__""#, &json!(Target::Ethereum).to_string())
+        .replace(r#""__BAR_TARGET__""#, &json!(Target::Polygon).to_string());
+    fs::write(&cfg_path, new_cfg_content)?;
+    let cfg = Config::from_dir(&app_dir)?;
+
+    println!("Building");
+    clean(&app_dir);
+    run_cubist_cmd(app_dir, "build").await?;
+
+    println!("Starting two test networks");
+    let start = || {
+        TestNetwork::builder()
+            .config(cfg.clone())
+            .relayer(false)
+            .start()
+    };
+    let (a, b) = try_join!(start(), start())?;
+
+    // each network uses its own ports and deploy directory
+    for target in [Target::Ethereum, Target::Polygon] {
+        let url = |net: &TestNetwork| {
+            let network = net.config().network_for_target(target).unwrap();
+            network.common().url.expose_url().unwrap()
+        };
+        assert_ne!(url(&a).port(), url(&b).port());
+    }
+    assert_ne!(a.config().relayer_endpoint, b.config().relayer_endpoint);
+    assert_ne!(a.config().deploy_dir(), b.config().deploy_dir());
+
+    println!("Deploying to one of them");
+    let bar = a.cubist().contract("Bar").unwrap();
+    bar.deploy_shims().await?;
+    bar.deploy(Address::zero()).await?;
+    let address = Address::from_slice(&bar.address_on(bar.target()));
+
+    // the other network's chains (and deployments) are unaffected
+    let other_bar = b.cubist().contract("Bar").unwrap();
+    assert!(!other_bar.is_deployed());
+    let code = b
+        .cubist()
+        .project(bar.target())
+        .unwrap()
+        .provider()
+        .get_code(address, None)
+        .await?;
+    assert!(code.is_empty());
+
+    Ok(())
+}
+
 const CUBIST_TESTNET_MNEMONIC_ENV_VAR: &str = "CUBIST_TESTNET_MNEMONIC";
 
 #[rstest]
@@ -7,9 +7,12 @@
 //! - [`build_dir`](Config::build_dir): where Cubist will write build output (can be overridden via `CUBIST_BUILD_DIR` env var),
 //! - [`deploy_dir`](Config::deploy_dir): where Cubist will generate deployment scripts and information (can be overridden via `CUBIST_DEPLOY_DIR` env var),
 //! - [`contracts`](Config::contracts): assignment of contracts to chains (currently per contract source file, not per contract)
+//!   and which contracts are [`upgradeable`](ContractsConfig::upgradeable) (see [`ProxyKind`]),
 //! - [`network_profiles`](Config::network_profiles): named profiles containing network/chain configuration (see [network configuration](network)),
 //! - [`current_network_profile`](Config::current_network_profile): currently selected network profile (can be overridden via `CUBIST_NETWORK_PROFILE` env var).
 //! - [`bridge_provider`](Config::bridge_provider): currently selected bridge provider (can be overridden via `CUBIST_BRIDGE_PROVIDER` env var).
+//! - [`relayer_endpoint`](Config::relayer_endpoint): opt-in endpoint on which the relayer publishes which contracts it has bridged.
+//! - [`create2`](Config::create2): opt-in deterministic (CREATE2) deployments (see [`Create2Config`]).
 //!
 //! Example JSON file:
 //! ```
@@ -87,8 +90,8 @@
 
 use glob::glob;
 pub use network::{
-    AvalancheConfig, CommonConfig, CredConfig, EndpointConfig, EthereumConfig, NetworkProfile,
-    PolygonConfig, ProxyConfig,
+    AvalancheConfig, CommonConfig, CredConfig, EndpointConfig, EthereumConfig, FeeStrategy,
+    NetworkProfile, PolygonConfig, ProxyConfig,
 };
 use parse_display::{Display, FromStr};
 use path_clean::PathClean;
@@ -103,6 +106,8 @@ use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::warn;
+use url::Url;
+use util::OrBug;
 
 use thiserror::Error;
 
@@ -110,6 +115,8 @@ use thiserror::Error;
 pub mod axelar_manifest;
 /// Bridge metadata
 pub mod bridge;
+/// Lock file produced by the native dependency resolver
+pub mod lockfile;
 /// Well-known paths
 pub mod paths;
 /// Manifest produced by the 'pre-compile' step
@@ -126,6 +133,15 @@ pub mod pre_compile_manifest;
 ///   (applies only if `url` is a localhost address)
 /// - [`proxy`][CCproxy]: whether and how to start a Cubist Proxy in front or `url`
 ///   (applies only if `autostart` is false)
+/// - [`fees`][CCFees]: how to set fees for transactions sent to the chain
+/// - [`simulate`][CCSimulate]: whether to simulate transactions (and decode why they would
+///   revert) before sending them
+/// - [`confirmations`][CCConfirmations], [`receipt_timeout`][CCReceiptTimeout], and
+///   [`poll_interval`][CCPollInterval]: how many confirmations to wait for before considering a
+///   transaction final, for how long (in seconds), and how often to poll (in milliseconds)
+/// - [`multicall`][CCMulticall]: an existing Multicall3 contract to batch calls through
+/// - [`safe`][CCSafe]: a Safe (multisig) to propose admin operations to, rather than sending
+///   them from the sender
 ///
 /// # Autostart Local Networks
 ///
@@ -199,6 +215,50 @@ pub mod pre_compile_manifest;
 /// # assert_eq!(2, pc.local_accounts.len());
 /// ```
 ///
+/// # Transaction Fees
+///
+/// By default, Cubist sends legacy transactions (see [`FeeStrategy::Legacy`]), since some chains
+/// don't support EIP-1559.  To send EIP-1559 (type-2) transactions instead, with fees estimated
+/// from the chain's fee history, set `fees` to `eip1559`; to use fixed fee caps, set it to
+/// `fixed`:
+///
+/// ```
+/// # use cubist_config::*;
+/// # use serde_json::json;
+/// # let np: NetworkProfile = serde_json::from_value(json!(
+/// {
+///   "ethereum": { "url": "http://localhost:8545", "fees": "eip1559" },
+///   "polygon": {
+///     "url": "http://localhost:9545",
+///     "fees": {
+///       "fixed": { "max_fee_per_gas": 50000000000, "max_priority_fee_per_gas": 2000000000 }
+///     }
+///   }
+/// }
+/// # )).unwrap();
+/// # assert_eq!(FeeStrategy::Eip1559, np.ethereum.unwrap().common.fees);
+/// ```
+///
+/// # Sender Accounts
+///
+/// Transactions are sent from the first account reported by the endpoint (or its credentials
+/// proxy), unless a `default_sender` is configured for that chain (see
+/// [`CommonConfig::default_sender`]):
+///
+/// ```
+/// # use cubist_config::*;
+/// # use serde_json::json;
+/// # let np: NetworkProfile = serde_json::from_value(json!(
+/// {
+///   "ethereum": {
+///     "url": "http://localhost:8545",
+///     "default_sender": "0x8ba1f109551bd432803012645ac136ddd64dba72"
+///   }
+/// }
+/// # )).unwrap();
+/// # assert!(np.ethereum.unwrap().common.default_sender.is_some());
+/// ```
+///
 /// # Connect to a Public Network
 ///
 /// Before discussing how to configure Cubist to connect to public
@@ -280,6 +340,13 @@ pub mod pre_compile_manifest;
 /// [CCUrl]: crate::network::CommonConfig::url
 /// [CCAutostart]: crate::network::CommonConfig::autostart
 /// [CCProxy]: crate::network::CommonConfig::proxy
+/// [CCFees]: crate::network::CommonConfig::fees
+/// [CCSimulate]: crate::network::CommonConfig::simulate
+/// [CCConfirmations]: crate::network::CommonConfig::confirmations
+/// [CCReceiptTimeout]: crate::network::CommonConfig::receipt_timeout
+/// [CCPollInterval]: crate::network::CommonConfig::poll_interval
+/// [CCMulticall]: crate::network::CommonConfig::multicall
+/// [CCSafe]: crate::network::CommonConfig::safe
 /// [eth_sendTransaction]: https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_sendtransaction
 /// [eth_sendRawTransaction]: https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_sendrawtransaction
 ///
@@ -300,6 +367,7 @@ pub const DEFAULT_FILENAME: &str = "cubist-config.json";
 /// Various utilities
 pub mod util;
 
+pub use lockfile::{LockFile, LockedPackage};
 pub use pre_compile_manifest::FileArtifact;
 pub use pre_compile_manifest::PreCompileManifest;
 
@@ -343,6 +411,9 @@ pub enum ConfigError {
     /// Error raised when interpolation produces an invalid URL    
     #[error("Invalid URL after applying interpolation.  Original URL: {0}")]
     UrlInterpolate(String),
+    /// Error raised when a solc remapping is not of the form `prefix=target`
+    #[error("Invalid remapping '{0}'.  Remappings must be of the form 'prefix=target'")]
+    InvalidRemapping(String),
     /// Error raised when provided URL scheme is invalid
     #[error("Invalid URL scheme: {0}.  Supported schemes are: ws, wss, http, https")]
     UrlInvalidScheme(String),
@@ -411,7 +482,9 @@ pub enum Compiler {
 }
 
 /// Target chains (e.g., Avalanche, Polygon, Ethereum) for which we can deploy contracts.
-#[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, Hash, Display)]
+#[derive(
+    PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, Hash, Display, FromStr,
+)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
 #[display(style = "snake_case")]
@@ -453,6 +526,10 @@ pub enum TargetConfig {
         /// Compiler to compile the contract with.
         #[serde(default)]
         compiler: Compiler,
+        /// Target-specific solc settings; these override the settings in
+        /// [`ContractsConfig::solc`].
+        #[serde(default, skip_serializing_if = "SolcSettings::is_empty")]
+        solc: SolcSettings,
     },
     /// Target configuration for Stellar
     StellarTargetConfig {
@@ -548,6 +625,26 @@ impl TargetConfig {
         Ok(())
     }
 
+    /// Make resolved paths relative to the config project directory (the inverse of
+    /// [`Self::resolve_paths`]).
+    pub(crate) fn relativize_paths(&mut self, cfg: &Config) -> Result<()> {
+        match self {
+            Self::EvmTargetConfig { files, .. } => {
+                if let GlobsOrPaths::Paths(paths) = files {
+                    let globs = paths
+                        .iter()
+                        .map(|p| cfg.relative_to_project_dir(p).map(Glob::from))
+                        .collect::<Result<_>>()?;
+                    *files = GlobsOrPaths::Globs(globs);
+                }
+            }
+            Self::StellarTargetConfig { root, .. } => {
+                *root = cfg.relative_to_project_dir(root.as_path())?;
+            }
+        }
+        Ok(())
+    }
+
     fn resolve_globs_pure(globs: &[Glob], cfg: &Config) -> Result<Vec<PathBuf>> {
         let root_dir = cfg.project_dir();
         let mut resolved_files = vec![];
@@ -608,9 +705,25 @@ pub struct ContractsConfig {
     /// Paths to search for imports.
     #[serde(default = "default_import_dirs")]
     pub import_dirs: Vec<PathBuf>,
-    /// Solidity dependencies.
+    /// Solidity dependencies, mapping package names to version specs.
+    ///
+    /// By default these are installed with the project's JavaScript package manager. When
+    /// [`vendor`](Self::vendor) is set, a spec can be an npm version or version range (e.g.,
+    /// `^4.8.0`), a dist-tag (e.g., `latest`), a tarball URL, or a git URL with a ref (e.g.,
+    /// `git+https://github.com/OpenZeppelin/openzeppelin-contracts.git#v4.8.0`).
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
     pub solidity_dependencies: HashMap<String, String>,
+    /// Resolve dependencies natively (without a JavaScript package manager) by vendoring them
+    /// into a local directory and pinning them in a `cubist.lock` file.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub vendor: Option<VendorConfig>,
+    /// Solc settings applied to all EVM targets (unless overridden in the target's config).
+    #[serde(skip_serializing_if = "SolcSettings::is_empty", default)]
+    pub solc: SolcSettings,
+    /// Contracts that should be deployed behind an ERC-1967 proxy (so that they can later be
+    /// upgraded), mapped to the kind of proxy to use.
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub upgradeable: HashMap<ContractName, ProxyKind>,
 }
 
 impl Default for ContractsConfig {
@@ -620,6 +733,9 @@ impl Default for ContractsConfig {
             targets: HashMap::new(),
             import_dirs: default_import_dirs(),
             solidity_dependencies: HashMap::new(),
+            vendor: None,
+            solc: SolcSettings::default(),
+            upgradeable: HashMap::new(),
         }
     }
 }
@@ -637,6 +753,79 @@ impl ContractsConfig {
     }
 }
 
+/// Settings for the native dependency resolver.
+///
+/// Dependencies are downloaded (from an npm-compatible registry, a tarball URL, or a git
+/// repository) into [`dir`](Self::dir), which is automatically added to the import directories.
+/// The exact version, source, and content hash of every dependency is recorded in a `cubist.lock`
+/// file next to the config file and verified on every build.
+#[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VendorConfig {
+    /// Directory (relative to the project root) where dependencies are vendored.
+    #[serde(default = "default_vendor_dir")]
+    pub dir: PathBuf,
+    /// npm-compatible registry to resolve package versions against.
+    #[serde(default = "default_vendor_registry")]
+    pub registry: Url,
+}
+
+impl Default for VendorConfig {
+    fn default() -> Self {
+        VendorConfig {
+            dir: default_vendor_dir(),
+            registry: default_vendor_registry(),
+        }
+    }
+}
+
+/// Address of the canonical CREATE2 factory (the "deterministic deployment proxy"), which is
+/// deployed at the same address on every chain by a pre-signed, chain-agnostic transaction.
+pub const CREATE2_FACTORY: ethers_core::types::Address = ethers_core::types::H160([
+    0x4e, 0x59, 0xb4, 0x48, 0x47, 0xb3, 0x79, 0x57, 0x85, 0x88, 0x92, 0x0c, 0xa7, 0x8f, 0xbf, 0x26,
+    0xc0, 0xb4, 0x95, 0x6c,
+]);
+
+/// Settings for deterministic deployments.
+///
+/// When enabled, contracts (and their shims) are deployed via the canonical CREATE2 factory (see
+/// [`CREATE2_FACTORY`]), so their addresses depend only on their bytecode, constructor arguments,
+/// and salt, rather than on the deployer account and its nonce.  Deploying the same contract
+/// with the same salt therefore yields the same address on every chain and across re-runs.
+///
+/// Shims take their owner (the deploying account) as a constructor argument, so their addresses
+/// also depend on the deploying account.  Proxies of upgradeable contracts are deployed with
+/// regular transactions, since their constructors call initializers (which must see the
+/// deploying account, not the factory, as the caller).
+#[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Create2Config {
+    /// Salt used for contracts that don't have one in [`salts`](Self::salts).
+    #[serde(default)]
+    pub salt: String,
+    /// Per-contract salts, mapping contract names to salts.  A contract's shims use the same salt
+    /// as the contract itself.
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub salts: HashMap<ContractName, String>,
+}
+
+impl Create2Config {
+    /// Returns the CREATE2 salt for a given contract, i.e., the keccak256 hash of its configured
+    /// salt.
+    pub fn salt_for(&self, contract: &str) -> [u8; 32] {
+        let salt = self.salts.get(contract).unwrap_or(&self.salt);
+        ethers_core::utils::keccak256(salt.as_bytes())
+    }
+}
+
+fn default_vendor_dir() -> PathBuf {
+    "lib".into()
+}
+
+fn default_vendor_registry() -> Url {
+    Url::parse("https://registry.npmjs.org/").or_bug("Default registry URL is valid")
+}
+
 /// Bridge provider options Cubist supports
 #[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -647,16 +836,171 @@ pub enum BridgeProvider {
     Axelar,
 }
 
+/// Kinds of ERC-1967 proxies upgradeable contracts can be deployed behind
+#[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyKind {
+    /// Transparent proxy, upgraded through a separately deployed proxy admin contract
+    Transparent,
+    /// UUPS proxy, upgraded by calling `upgradeTo` on the implementation (which therefore must
+    /// implement the upgrade logic itself)
+    Uups,
+}
+
+/// Solc settings that affect the produced bytecode.
+///
+/// These can be set for all targets (in [`ContractsConfig::solc`]) and overridden for individual
+/// EVM targets (e.g., to select an EVM version supported by that particular chain).
+#[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SolcSettings {
+    /// Optimizer settings.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub optimizer: Option<OptimizerSettings>,
+    /// Whether to compile via the Yul intermediate representation.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub via_ir: Option<bool>,
+    /// EVM version to compile for.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub evm_version: Option<EvmVersion>,
+    /// Hash method used for the metadata hash appended to the bytecode.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub metadata_hash: Option<MetadataHash>,
+    /// Import remappings of the form `prefix=target` (e.g., `@openzeppelin/=lib/openzeppelin/`).
+    /// Relative targets are resolved against the project directory.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[schemars(inner(regex(pattern = r"^[^=]+=.*$")))]
+    pub remappings: Vec<String>,
+}
+
+impl SolcSettings {
+    /// Whether no setting is specified.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns these settings with every setting specified in `overrides` taking precedence.
+    /// Remappings are concatenated, so that remappings in `overrides` come last.
+    pub fn merge(&self, overrides: &SolcSettings) -> SolcSettings {
+        SolcSettings {
+            optimizer: overrides
+                .optimizer
+                .clone()
+                .or_else(|| self.optimizer.clone()),
+            via_ir: overrides.via_ir.or(self.via_ir),
+            evm_version: overrides.evm_version.or(self.evm_version),
+            metadata_hash: overrides.metadata_hash.or(self.metadata_hash),
+            remappings: self
+                .remappings
+                .iter()
+                .chain(overrides.remappings.iter())
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Solc optimizer settings.
+#[derive(PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OptimizerSettings {
+    /// Whether to enable the optimizer.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Number of times each opcode is expected to be executed.
+    #[serde(default = "default_optimizer_runs")]
+    pub runs: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_optimizer_runs() -> usize {
+    200
+}
+
+/// EVM versions solc can compile for.
+///
+/// Versions after London (i.e., Paris and later) are accepted here (e.g., so that imported
+/// projects validate), but compiling for them is not supported yet: the bundled solc integration
+/// (ethers-solc 1.0.2) only knows versions up to London.
+#[derive(
+    PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, Display, FromStr,
+)]
+#[serde(rename_all = "camelCase")]
+#[display(style = "camelCase")]
+pub enum EvmVersion {
+    /// Homestead
+    Homestead,
+    /// Tangerine Whistle
+    TangerineWhistle,
+    /// Spurious Dragon
+    SpuriousDragon,
+    /// Byzantium
+    Byzantium,
+    /// Constantinople
+    Constantinople,
+    /// Petersburg
+    Petersburg,
+    /// Istanbul
+    Istanbul,
+    /// Berlin
+    Berlin,
+    /// London
+    London,
+    /// Paris
+    Paris,
+    /// Shanghai (introduces `PUSH0`)
+    Shanghai,
+    /// Cancun (introduces transient storage and `MCOPY`)
+    Cancun,
+}
+
+/// Hash method used for the metadata hash appended to the bytecode.
+#[derive(
+    PartialEq, Eq, Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, Display, FromStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[display(style = "lowercase")]
+pub enum MetadataHash {
+    /// IPFS hash (solc's default)
+    Ipfs,
+    /// Swarm hash
+    Bzzr1,
+    /// Do not append a metadata hash
+    None,
+}
+
 /// Compiler configuration, i.e., configurations that result in compiler flags
 #[derive(Clone, Default)]
 pub struct CompilerConfig {
     /// Paths to search for imports.
     pub import_dirs: Vec<PathBuf>,
+    /// Solc settings (with remapping targets resolved to absolute paths).
+    pub solc: SolcSettings,
 }
 
 impl CompilerConfig {
-    /// Search for `path` in this config's import dirs ([`Self::import_dirs`]).
+    /// Applies the remapping with the longest matching prefix (like solc does) to import `path`,
+    /// if any remapping matches.
+    pub fn remap(&self, path: &str) -> Option<PathBuf> {
+        self.solc
+            .remappings
+            .iter()
+            .filter_map(|remapping| remapping.split_once('='))
+            .filter(|(prefix, _)| path.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, target)| PathBuf::from(format!("{target}{}", &path[prefix.len()..])))
+    }
+
+    /// Search for `path`, first through this config's remappings (see [`Self::remap`]) and then
+    /// in its import dirs ([`Self::import_dirs`]).
     pub fn search<P: AsRef<Path>>(&self, path: P) -> Option<PathBuf> {
+        let remapped = path.as_ref().to_str().and_then(|p| self.remap(p));
+        if let Some(remapped) = remapped.filter(|remapped| remapped.exists()) {
+            return Some(remapped);
+        }
         self.import_dirs.iter().find_map(|dir| {
             let entry = dir.join(&path);
             if entry.exists() {
@@ -699,9 +1043,16 @@ pub struct Config {
     /// The bridge provider to use for cross-chain invocations.
     #[serde(default = "default_bridge_provider")]
     pub bridge_provider: BridgeProvider,
+    /// Address (`host:port`) on which the relayer publishes which contracts it has bridged, so
+    /// that SDKs don't have to poll the deploy directory to find out (disabled by default).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub relayer_endpoint: Option<String>,
     /// Allows or disables imports from external sources (GitHub and npm/Yarn).
     #[serde(default = "default_allow_import_from_external")]
     allow_import_from_external: bool,
+    /// Deploy contracts deterministically via CREATE2 (disabled by default).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub create2: Option<Create2Config>,
 }
 
 fn default_build_dir() -> PathBuf {
@@ -739,7 +1090,9 @@ impl Default for Config {
             network_profiles: default_network_profiles(),
             current_network_profile: default_profile_name(),
             bridge_provider: default_bridge_provider(),
+            relayer_endpoint: None,
             allow_import_from_external: default_allow_import_from_external(),
+            create2: None,
         }
     }
 }
@@ -924,9 +1277,9 @@ impl Config {
     /// Writing to an existing file is generally discouraged (but can be done with by passing true
     /// fo the `force` argument). When we read configs from file, we resolve some paths (e.g., we
     /// turn contract globs into paths) and don't preserve the original. We also make the build,
-    /// deploy, and contracts root dir relative to the project root. We do this largely because we
-    /// use `to_file` when we create new projects. This does mean: `to_file(from_file(_))` is not
-    /// the identiy function!
+    /// deploy, contracts root dir, and contract files relative to the project root. We do this
+    /// largely because we use `to_file` when we create new projects. This does mean:
+    /// `to_file(from_file(_))` is not the identiy function!
     pub fn to_file(&self, force: bool) -> Result<()> {
         if !force && self.config_path.is_file() {
             return Err(ConfigError::PathError(
@@ -945,6 +1298,12 @@ impl Config {
             .iter()
             .map(|d| cfg.relative_to_project_dir(d))
             .collect::<Result<_>>()?;
+        if let Some(vendor) = cfg.contracts.vendor.as_mut() {
+            vendor.dir = self.relative_to_project_dir(&vendor.dir)?;
+        }
+        for t_config in cfg.contracts.targets.values_mut() {
+            t_config.relativize_paths(self)?;
+        }
 
         let pretty = match serde_json::to_string_pretty(&cfg) {
             Ok(j) => Ok(j),
@@ -970,6 +1329,11 @@ impl Config {
         self.absolute_path_in_project(&self.deploy_dir)
     }
 
+    /// Set the deploy directory (relative paths are relative to the project directory).
+    pub fn set_deploy_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.deploy_dir = dir.into();
+    }
+
     /// Get the absolute build directory
     pub fn build_dir(&self) -> PathBuf {
         self.absolute_path_in_project(&self.build_dir)
@@ -1003,6 +1367,14 @@ impl Config {
         &self.contracts
     }
 
+    /// Replace contracts configurations.  Relative paths (and globs) in `contracts` are resolved
+    /// relative to the project directory, and the resulting config is validated.
+    pub fn set_contracts(&mut self, contracts: ContractsConfig) -> Result<()> {
+        self.contracts = contracts;
+        self.resolve_paths()?;
+        self.validate()
+    }
+
     /// Return all targets.
     pub fn targets(&self) -> impl Iterator<Item = Target> + '_ {
         self.contracts.targets.keys().copied()
@@ -1033,6 +1405,8 @@ impl Config {
     fn validate(&self) -> Result<()> {
         // Validate contract paths
         self.validate_contract_paths()?;
+        // Validate solc settings
+        self.validate_solc_settings()?;
         // Validate network profiles
         self.validate_network_profiles()
     }
@@ -1049,6 +1423,9 @@ impl Config {
             .iter()
             .map(|d| self.absolute_path_in_project(d))
             .collect();
+        if let Some(vendor) = contracts.vendor.as_mut() {
+            vendor.dir = self.absolute_path_in_project(&vendor.dir);
+        }
         // Resolve file globs
         for (_, t_config) in contracts.targets.iter_mut() {
             t_config.resolve_paths(self)?;
@@ -1081,6 +1458,24 @@ impl Config {
         Ok(())
     }
 
+    /// Check that all remappings (global and per-target) are of the form `prefix=target`.
+    fn validate_solc_settings(&self) -> Result<()> {
+        let target_settings = self.contracts.targets.values().filter_map(|t| match t {
+            TargetConfig::EvmTargetConfig { solc, .. } => Some(solc),
+            TargetConfig::StellarTargetConfig { .. } => None,
+        });
+        for settings in std::iter::once(&self.contracts.solc).chain(target_settings) {
+            if let Some(bad) = settings
+                .remappings
+                .iter()
+                .find(|r| !matches!(r.split_once('='), Some((prefix, _)) if !prefix.is_empty()))
+            {
+                return Err(ConfigError::InvalidRemapping(bad.clone()));
+            }
+        }
+        Ok(())
+    }
+
     /// Check if targets point to valid (defined) networks.
     fn validate_network_profiles(&self) -> Result<()> {
         // network profile is valid
@@ -1102,16 +1497,56 @@ impl Config {
         self.allow_import_from_external
     }
 
+    /// Returns the native dependency resolver settings (if enabled).
+    pub fn vendor(&self) -> Option<&VendorConfig> {
+        self.contracts.vendor.as_ref()
+    }
+
     /// Returns the compiler configuration for this Cubist configuration.
     pub fn get_compiler_config(&self) -> CompilerConfig {
+        self.compiler_config_with(&self.contracts.solc)
+    }
+
+    /// Returns the compiler configuration for a given target, i.e., the global compiler
+    /// configuration with the target-specific settings applied on top.
+    pub fn get_target_compiler_config(&self, target: Target) -> CompilerConfig {
+        match self.contracts.targets.get(&target) {
+            Some(TargetConfig::EvmTargetConfig { solc, .. }) => {
+                self.compiler_config_with(&self.contracts.solc.merge(solc))
+            }
+            _ => self.get_compiler_config(),
+        }
+    }
+
+    fn compiler_config_with(&self, solc: &SolcSettings) -> CompilerConfig {
         let abs_import_dirs = self
             .contracts
             .import_dirs
             .iter()
+            .chain(self.contracts.vendor.iter().map(|vendor| &vendor.dir))
             .map(|import_dir| self.absolute_path_in_project(import_dir))
             .collect::<Vec<_>>();
+        let mut solc = solc.clone();
+        solc.remappings = solc
+            .remappings
+            .iter()
+            .map(|remapping| self.resolve_remapping(remapping))
+            .collect();
         CompilerConfig {
             import_dirs: abs_import_dirs,
+            solc,
+        }
+    }
+
+    /// Make the target of a `prefix=target` remapping absolute (keeping a trailing slash, if any).
+    fn resolve_remapping(&self, remapping: &str) -> String {
+        match remapping.split_once('=') {
+            Some((prefix, target)) if !target.is_empty() => {
+                let abs_target = self.absolute_path_in_project(target);
+                let slash = if target.ends_with('/') { "/" } else { "" };
+                format!("{prefix}={}{slash}", abs_target.display())
+            }
+            _ => remapping.to_string(),
         }
     }
 }
@@ -1143,6 +1578,7 @@ fn find_file(file: impl AsRef<Path>, dir: impl AsRef<Path>) -> Result<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ethers_core::utils::keccak256;
     use pretty_assertions::assert_eq;
 
     use std::fs;
@@ -1168,6 +1604,35 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_search_remapped_import() {
+        let tmp = tempdir().unwrap();
+        let lib = tmp.path().join("lib").join("oz").join("token");
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(lib.join("ERC20.sol"), "contract ERC20 {}").unwrap();
+        let vendored = tmp.path().join("vendor").join("@openzeppelin");
+        fs::create_dir_all(&vendored).unwrap();
+
+        let cc = CompilerConfig {
+            import_dirs: vec![tmp.path().join("vendor")],
+            solc: SolcSettings {
+                remappings: vec![
+                    format!("@openzeppelin/={}/lib/", tmp.path().display()),
+                    format!("@openzeppelin/contracts/={}/lib/oz/", tmp.path().display()),
+                ],
+                ..Default::default()
+            },
+        };
+        // the longest prefix wins
+        assert_eq!(
+            Some(lib.join("ERC20.sol")),
+            cc.search("@openzeppelin/contracts/token/ERC20.sol")
+        );
+        assert_eq!(None, cc.search("@openzeppelin/contracts/token/Missing.sol"));
+        // unmapped imports are searched for in the import dirs
+        assert_eq!(Some(vendored), cc.search("@openzeppelin"));
+    }
+
     #[test]
     fn test_new_config() {
         let tmp = tempdir().unwrap();
@@ -1482,4 +1947,56 @@ mod tests {
         assert_eq!(2000, profile2.avalanche.unwrap().common.url.port().unwrap());
         assert_eq!(3000, profile2.polygon.unwrap().common.url.port().unwrap());
     }
+
+    #[test]
+    fn test_relayer_endpoint() {
+        let cfg: Config = serde_json::from_value(json!({ "type": "TypeScript" })).unwrap();
+        assert_eq!(None, cfg.relayer_endpoint);
+
+        let cfg: Config = serde_json::from_value(json!({
+            "type": "TypeScript",
+            "relayer_endpoint": "127.0.0.1:9599"
+        }))
+        .unwrap();
+        assert_eq!(Some("127.0.0.1:9599".to_owned()), cfg.relayer_endpoint);
+    }
+
+    #[test]
+    fn test_create2_salts() {
+        let cfg: Config = serde_json::from_value(json!({
+            "type": "TypeScript",
+            "create2": { "salt": "v1", "salts": { "Token": "token-v2" } }
+        }))
+        .unwrap();
+        let create2 = cfg.create2.unwrap();
+        assert_eq!(keccak256("v1"), create2.salt_for("Counter"));
+        assert_eq!(keccak256("token-v2"), create2.salt_for("Token"));
+
+        // disabled by default
+        let cfg: Config = serde_json::from_value(json!({ "type": "TypeScript" })).unwrap();
+        assert_eq!(None, cfg.create2);
+        assert_eq!(keccak256(""), Create2Config::default().salt_for("Counter"));
+    }
+
+    #[test]
+    fn test_upgradeable_contracts() {
+        let cfg: Config = serde_json::from_value(json!({
+            "type": "TypeScript",
+            "contracts": {
+                "root_dir": "contracts",
+                "targets": {},
+                "upgradeable": { "Token": "transparent", "Vault": "uups" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            Some(&ProxyKind::Transparent),
+            cfg.contracts.upgradeable.get("Token")
+        );
+        assert_eq!(
+            Some(&ProxyKind::Uups),
+            cfg.contracts.upgradeable.get("Vault")
+        );
+        assert_eq!(None, cfg.contracts.upgradeable.get("Counter"));
+    }
 }
@@ -0,0 +1,145 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap as Map;
+use std::{fs, path::Path};
+
+use crate::{util::OrBug, ConfigError, Result};
+
+/// Default name of the lock file (stored next to the config file).
+pub const LOCK_FILE_NAME: &str = "cubist.lock";
+
+/// Current version of the lock file format.
+const LOCK_FILE_VERSION: u32 = 1;
+
+/// Lock file generated by the native dependency resolver (see [`crate::VendorConfig`]).  Pins
+/// every vendored Solidity dependency to an exact version, source, and content hash.
+///
+/// Path: {project_dir}/cubist.lock
+///
+/// Example JSON file:
+///
+/// ```
+/// # use cubist_config::LockFile;
+/// # use serde_json::{from_str, json};
+/// # let lock_json = json!(
+///   {
+///      "version": 1,
+///      "packages": {
+///          "@openzeppelin/contracts": {
+///              "spec": "^4.8.0",
+///              "version": "4.9.6",
+///              "resolved": "https://registry.npmjs.org/@openzeppelin/contracts/-/contracts-4.9.6.tgz",
+///              "integrity": "blake3-8a3ec6c0c4a2c3a3f30d5b5e2cd4fd3cbbf7e5e7a0c7b42d66e8e0ed22a2dcf6"
+///          }
+///      }
+///   }
+/// # );
+/// # let lock: LockFile = from_str(&lock_json.to_string()).unwrap();
+/// ```
+///
+/// The lock file should be checked into version control; builds fail if a vendored dependency
+/// no longer matches its recorded hash.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LockFile {
+    /// Lock file format version.
+    pub version: u32,
+    /// Locked packages, keyed by package name.
+    pub packages: Map<String, LockedPackage>,
+}
+
+/// A single dependency pinned in a [`LockFile`].
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LockedPackage {
+    /// Version spec the package was resolved from (as requested in the config).
+    pub spec: String,
+    /// Exact resolved version (for registry packages and tarballs with a `package.json`) or
+    /// commit (for git dependencies).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    /// Exact location the package was downloaded from.
+    pub resolved: String,
+    /// Hash of the vendored package contents.
+    pub integrity: String,
+}
+
+impl Default for LockFile {
+    fn default() -> Self {
+        LockFile {
+            version: LOCK_FILE_VERSION,
+            packages: Map::new(),
+        }
+    }
+}
+
+impl LockFile {
+    /// Load lock file from JSON file, or return an empty lock file if `file` does not exist.
+    pub fn from_file_or_default(file: &Path) -> Result<Self> {
+        if !file.is_file() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(file)
+            .map_err(|e| ConfigError::FsError("Failed to read lock file", file.into(), e))?;
+        let lock: LockFile = serde_json::from_str(&json)
+            .map_err(|e| ConfigError::MalformedConfig(file.into(), e))?;
+        if lock.version != LOCK_FILE_VERSION {
+            return Err(ConfigError::PathError(
+                "Unsupported lock file version",
+                file.into(),
+            ));
+        }
+        Ok(lock)
+    }
+
+    /// Save lock file to JSON file.
+    pub fn to_file(&self, destination: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self).or_bug("Serializing LockFile to json");
+        fs::write(destination, json).map_err(|e| {
+            ConfigError::FsError("Failed to write lock file", destination.into(), e)
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_lock_file_roundtrip() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join(LOCK_FILE_NAME);
+        assert_eq!(
+            LockFile::default(),
+            LockFile::from_file_or_default(&file).unwrap()
+        );
+
+        let mut lock = LockFile::default();
+        lock.packages.insert(
+            "@openzeppelin/contracts".into(),
+            LockedPackage {
+                spec: "^4.8.0".into(),
+                version: Some("4.9.6".into()),
+                resolved:
+                    "https://registry.npmjs.org/@openzeppelin/contracts/-/contracts-4.9.6.tgz"
+                        .into(),
+                integrity: "blake3-00".into(),
+            },
+        );
+        lock.to_file(&file).unwrap();
+        assert_eq!(lock, LockFile::from_file_or_default(&file).unwrap());
+    }
+
+    #[test]
+    fn test_lock_file_bad_version() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join(LOCK_FILE_NAME);
+        fs::write(&file, r#"{ "version": 42, "packages": {} }"#).unwrap();
+        assert!(matches!(
+            LockFile::from_file_or_default(&file),
+            Err(ConfigError::PathError(..))
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use coins_bip39::{English, Mnemonic};
+use ethers_core::types::Address;
 use k256::SecretKey;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -77,6 +78,18 @@ pub enum EndpointConfig {
     Stellar(StellarConfig),
 }
 
+impl EndpointConfig {
+    /// Returns the config options common to all providers
+    pub fn common(&self) -> &CommonConfig {
+        match self {
+            EndpointConfig::Eth(c) => &c.common,
+            EndpointConfig::Ava(c) | EndpointConfig::AvaSub(c) => &c.common,
+            EndpointConfig::Poly(c) => &c.common,
+            EndpointConfig::Stellar(c) => &c.common,
+        }
+    }
+}
+
 /// Configuration for mnemonic-based credentials
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -162,6 +175,85 @@ pub struct CommonConfig {
     /// Whether to run a local credentials proxy in front of the endpoint
     /// (applies only if `url` is a remote address).
     pub proxy: Option<ProxyConfig>,
+
+    /// How to set transaction fees for transactions sent to this chain.
+    #[serde(default)]
+    pub fees: FeeStrategy,
+
+    /// Account to send transactions from by default (must be one of the accounts managed by the
+    /// endpoint or its credentials proxy).  When omitted, the first account reported by the
+    /// endpoint is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub default_sender: Option<Address>,
+
+    /// Whether to simulate each transaction (with `eth_call`) before sending it, so that
+    /// transactions that would revert fail early, with a decoded revert reason.
+    #[serde(default)]
+    pub simulate: bool,
+
+    /// Number of confirmations (i.e., blocks including and on top of the one that includes a
+    /// transaction) to wait for before considering a transaction final.
+    #[serde(default = "default_confirmations")]
+    pub confirmations: usize,
+
+    /// How long (in seconds) to wait for a transaction to be confirmed before giving up.
+    #[serde(default = "default_receipt_timeout")]
+    pub receipt_timeout: u64,
+
+    /// How often (in milliseconds) to poll for transaction receipts.
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64,
+
+    /// Address of an already deployed Multicall3 contract (e.g., the canonical one at
+    /// `0xcA11bde05977b3631167028862bE2a173976CA11`) to batch calls through.  When omitted,
+    /// Cubist deploys its own Multicall3-compatible contract (via the CREATE2 factory) on chains
+    /// it starts locally, and uses the canonical one (if deployed) everywhere else.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub multicall: Option<Address>,
+
+    /// Address of a Safe (multisig) that administers the contracts deployed to this chain.  When
+    /// set, admin operations (granting the CALLER role on shims, updating Axelar receiver shims,
+    /// and transferring ownership) on contracts that the Safe owns are not sent from the sender;
+    /// instead, they are proposed to the Safe as transaction batches to import into Safe tooling
+    /// (see [`Paths::for_safe_batch`](crate::paths::Paths::for_safe_batch)).  Shims deployed to
+    /// this chain are owned by the Safe from the start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub safe: Option<Address>,
+}
+
+/// Default for [`CommonConfig::confirmations`]
+pub const DEFAULT_CONFIRMATIONS: usize = 1;
+
+/// Default for [`CommonConfig::receipt_timeout`] (in seconds)
+pub const DEFAULT_RECEIPT_TIMEOUT: u64 = 60;
+
+/// Default for [`CommonConfig::poll_interval`] (in milliseconds)
+pub const DEFAULT_POLL_INTERVAL: u64 = 250;
+
+/// Strategy for setting transaction fees.  Fees are computed for each transaction right before
+/// it's sent (rather than once, when connecting to the chain).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeStrategy {
+    /// Send legacy transactions, with the gas price set to the greater of the current gas price
+    /// and the estimated EIP-1559 max fee per gas.  Use this for chains that don't support
+    /// EIP-1559 (type-2) transactions.
+    #[default]
+    Legacy,
+    /// Send EIP-1559 (type-2) transactions, with the max fee per gas and max priority fee per gas
+    /// set from the chain's fee history.
+    Eip1559,
+    /// Send EIP-1559 (type-2) transactions with fixed fee caps.
+    Fixed {
+        /// Max fee per gas (in wei)
+        max_fee_per_gas: u64,
+        /// Max priority fee per gas (in wei)
+        max_priority_fee_per_gas: u64,
+    },
 }
 
 /// Subnet information.
@@ -292,6 +384,18 @@ fn default_true() -> bool {
     true
 }
 
+fn default_confirmations() -> usize {
+    DEFAULT_CONFIRMATIONS
+}
+
+fn default_receipt_timeout() -> u64 {
+    DEFAULT_RECEIPT_TIMEOUT
+}
+
+fn default_poll_interval() -> u64 {
+    DEFAULT_POLL_INTERVAL
+}
+
 fn default_mnemonic_config() -> MnemonicConfig {
     MnemonicConfig {
         seed: default_mnemonic().into(),
@@ -313,7 +417,9 @@ mod test {
         SecretKind, INVALID_MNEMONIC_ERR, INVALID_PRIVATE_KEY_ERR, INVALID_PRIVATE_KEY_HEX_ERR,
     };
 
-    use super::{MnemonicConfig, PolygonConfig, PrivateKeyConfig, ProxyConfig};
+    use super::{
+        CommonConfig, FeeStrategy, MnemonicConfig, PolygonConfig, PrivateKeyConfig, ProxyConfig,
+    };
     use secrecy::ExposeSecret;
 
     #[test]
@@ -401,4 +507,110 @@ mod test {
         let loaded = pk.hex.load().unwrap();
         assert_eq!(key, loaded.expose_secret());
     }
+
+    #[test]
+    fn serde_fee_strategy() {
+        let cc: CommonConfig =
+            serde_json::from_value(json!({ "url": "http://localhost:8545" })).unwrap();
+        assert_eq!(FeeStrategy::Legacy, cc.fees);
+
+        let json = json!({ "url": "http://localhost:8545", "fees": "eip1559" });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(FeeStrategy::Eip1559, cc.fees);
+
+        let json = json!({
+            "url": "http://localhost:8545",
+            "fees": { "fixed": { "max_fee_per_gas": 100, "max_priority_fee_per_gas": 2 } }
+        });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            FeeStrategy::Fixed {
+                max_fee_per_gas: 100,
+                max_priority_fee_per_gas: 2
+            },
+            cc.fees
+        );
+
+        let json = json!({ "url": "http://localhost:8545", "fees": "cheap" });
+        assert!(serde_json::from_value::<CommonConfig>(json).is_err());
+    }
+
+    #[test]
+    fn serde_default_sender() {
+        let cc: CommonConfig =
+            serde_json::from_value(json!({ "url": "http://localhost:8545" })).unwrap();
+        assert_eq!(None, cc.default_sender);
+
+        let sender = "0x8ba1f109551bd432803012645ac136ddd64dba72";
+        let json = json!({ "url": "http://localhost:8545", "default_sender": sender });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(Some(sender.parse().unwrap()), cc.default_sender);
+        assert_eq!(
+            json!(sender),
+            serde_json::to_value(&cc).unwrap()["default_sender"]
+        );
+
+        let json = json!({ "url": "http://localhost:8545", "default_sender": "alice" });
+        assert!(serde_json::from_value::<CommonConfig>(json).is_err());
+    }
+
+    #[test]
+    fn serde_simulate() {
+        let cc: CommonConfig =
+            serde_json::from_value(json!({ "url": "http://localhost:8545" })).unwrap();
+        assert!(!cc.simulate);
+
+        let json = json!({ "url": "http://localhost:8545", "simulate": true });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert!(cc.simulate);
+    }
+
+    #[test]
+    fn serde_receipt_settings() {
+        let cc: CommonConfig =
+            serde_json::from_value(json!({ "url": "http://localhost:8545" })).unwrap();
+        assert_eq!(DEFAULT_CONFIRMATIONS, cc.confirmations);
+        assert_eq!(DEFAULT_RECEIPT_TIMEOUT, cc.receipt_timeout);
+        assert_eq!(DEFAULT_POLL_INTERVAL, cc.poll_interval);
+
+        let json = json!({
+            "url": "http://localhost:8545",
+            "confirmations": 12,
+            "receipt_timeout": 600,
+            "poll_interval": 5000,
+        });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(12, cc.confirmations);
+        assert_eq!(600, cc.receipt_timeout);
+        assert_eq!(5000, cc.poll_interval);
+    }
+
+    #[test]
+    fn serde_multicall() {
+        let cc: CommonConfig =
+            serde_json::from_value(json!({ "url": "http://localhost:8545" })).unwrap();
+        assert_eq!(None, cc.multicall);
+        assert!(serde_json::to_value(&cc)
+            .unwrap()
+            .get("multicall")
+            .is_none());
+
+        let multicall = "0xca11bde05977b3631167028862be2a173976ca11";
+        let json = json!({ "url": "http://localhost:8545", "multicall": multicall });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(Some(multicall.parse().unwrap()), cc.multicall);
+    }
+
+    #[test]
+    fn serde_safe() {
+        let cc: CommonConfig =
+            serde_json::from_value(json!({ "url": "http://localhost:8545" })).unwrap();
+        assert_eq!(None, cc.safe);
+        assert!(serde_json::to_value(&cc).unwrap().get("safe").is_none());
+
+        let safe = "0x5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe";
+        let json = json!({ "url": "http://localhost:8545", "safe": safe });
+        let cc: CommonConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(Some(safe.parse().unwrap()), cc.safe);
+    }
 }
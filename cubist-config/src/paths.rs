@@ -6,7 +6,7 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::{Config, Target};
+use crate::{lockfile::LOCK_FILE_NAME, Config, Target};
 
 /// Hex representation of a byte array (as used by functions in this
 /// module that construct a path from an address)
@@ -62,8 +62,11 @@ pub struct Paths {
     pub project_dir: PathBuf,
     /// Build directory: where Cubist generates per-chain projects.
     pub build_dir: PathBuf,
-    /// Deploy directory: where Cubist saves deployment receipts.
+    /// Deploy directory of the current network profile: where Cubist saves deployment receipts,
+    /// manifests, and history.
     pub deploy_dir: PathBuf,
+    /// Lock file pinning natively resolved Solidity dependencies.
+    pub lock_file: PathBuf,
     /// Per target chain well-known paths.
     pub per_target: HashMap<Target, TargetPaths>,
 }
@@ -103,6 +106,7 @@ impl Paths {
             .map(|t| (t, TargetPaths::new(build_dir.join(t), deploy_dir.join(t))))
             .collect();
         Paths {
+            lock_file: project_dir.join(LOCK_FILE_NAME),
             project_dir,
             build_dir,
             deploy_dir,
@@ -123,16 +127,59 @@ impl Paths {
 
     /// Directory where cubist deployment manifests are written.
     ///
-    /// Path: {deploy_dir}/cubist-deploy
+    /// Path: {deploy_dir}/{network_profile}/cubist-deploy
     pub fn deployment_manifest_dir(&self) -> PathBuf {
-        self.deploy_dir.with_file_name("cubist-deploy")
+        self.deploy_dir.join("cubist-deploy")
+    }
+
+    /// Directory where journals of deployments in progress are kept (so that interrupted
+    /// deployments can be resumed).
+    ///
+    /// Path: {deploy_dir}/{network_profile}/cubist-deploy-journal
+    pub fn deployment_journal_dir(&self) -> PathBuf {
+        self.deploy_dir.join("cubist-deploy-journal")
+    }
+
+    /// Journal of the (in-progress) deployment of a contract to a given target.
+    ///
+    /// Path: {deploy_dir}/{network_profile}/cubist-deploy-journal/{target}/{contract_name}.json
+    pub fn for_deployment_journal(&self, target: Target, contract: &ContractFQN) -> PathBuf {
+        self.deployment_journal_dir()
+            .join(target.to_string())
+            .join(format!("{}.json", contract.name))
+    }
+
+    /// Directory where Safe transaction batches (of admin operations proposed to Safes, see
+    /// [`CommonConfig::safe`](crate::network::CommonConfig::safe)) are written.
+    ///
+    /// Path: {deploy_dir}/{network_profile}/cubist-safe-batches
+    pub fn safe_batch_dir(&self) -> PathBuf {
+        self.deploy_dir.join("cubist-safe-batches")
+    }
+
+    /// Safe transaction batch of the admin operations concerning a contract that are proposed
+    /// to the Safe of a given target.
+    ///
+    /// Path: {deploy_dir}/{network_profile}/cubist-safe-batches/{target}/{contract_name}.json
+    pub fn for_safe_batch(&self, target: Target, contract: &ContractFQN) -> PathBuf {
+        self.safe_batch_dir()
+            .join(target.to_string())
+            .join(format!("{}.json", contract.name))
+    }
+
+    /// Append-only history of the deployments (and upgrades) made with the current network
+    /// profile (one JSON record per line).
+    ///
+    /// Path: {deploy_dir}/{network_profile}/deployments.jsonl
+    pub fn deployment_history(&self) -> PathBuf {
+        self.deploy_dir.join("deployments.jsonl")
     }
 
     /// Destination for a contract deployment manifest file generated
     /// by Cubist.  This manifest file contains contract's deployed
     /// address as well as the deployed addresses of all of its shims.
     ///
-    /// Path: {deploy_dir}/cubist-deploy/{contract_name}-{address}.json
+    /// Path: {deploy_dir}/{network_profile}/cubist-deploy/{contract_name}-{address}.json
     pub fn for_deployment_manifest(&self, contract: &ContractFQN, address: &[u8]) -> PathBuf {
         self.deployment_manifest_dir()
             .join(format!("{}-{}.json", contract.name, hex(address)))
@@ -142,7 +189,7 @@ impl Paths {
     /// created for a given contract (as specified by its deployment
     /// manifest file)
     ///
-    /// Path: {deploy_dir}/cubist-deploy/{contract_name}-{address}.bridged
+    /// Path: {deploy_dir}/{network_profile}/cubist-deploy/{contract_name}-{address}.bridged
     pub fn notify_contract_bridged(&self, contract: &ContractFQN, address: &[u8]) -> PathBuf {
         Self::bridged_signal_for_manifest_file(&self.for_deployment_manifest(contract, address))
     }
@@ -150,7 +197,7 @@ impl Paths {
     /// Full path to the file indicating that a bridge has been
     /// created for a given deployment manifest file.
     ///
-    /// Path: {deploy_dir}/cubist-deploy/{contract_name}-{address}.bridged
+    /// Path: {deploy_dir}/{network_profile}/cubist-deploy/{contract_name}-{address}.bridged
     pub fn bridged_signal_for_manifest_file(deployment_manifest: &Path) -> PathBuf {
         dbg!(deployment_manifest.with_extension("bridged"))
     }
@@ -176,18 +223,32 @@ impl TargetPaths {
         }
     }
 
-    /// Directory where deployment receipts are stored for a given contract.
+    /// Directory where deployment receipts are stored for a given chain.
+    ///
+    /// Path: {deploy_dir}/{network_profile}/{target}/{chain_id}
+    pub fn deployment_receipts_root(&self, chain_id: u64) -> PathBuf {
+        self.deploy_root.join(chain_id.to_string())
+    }
+
+    /// Directory where deployment receipts are stored for a given contract on a given chain.
     ///
-    /// Path: {deploy_dir}/{network_profile}/{target}/{contract_file}/{contract_name}
-    pub fn deployment_receipts_dir(&self, contract: &ContractFQN) -> PathBuf {
-        self.deploy_root.join(&contract.file).join(&contract.name)
+    /// Path: {deploy_dir}/{network_profile}/{target}/{chain_id}/{contract_file}/{contract_name}
+    pub fn deployment_receipts_dir(&self, chain_id: u64, contract: &ContractFQN) -> PathBuf {
+        self.deployment_receipts_root(chain_id)
+            .join(&contract.file)
+            .join(&contract.name)
     }
 
     /// Destination for a contract deployment receipt file.
     ///
-    /// Path: {deploy_dir}/{network_profile}/{target}/{contract_file}/{contract_name}/{address}.json
-    pub fn for_deployment_receipt(&self, contract: &ContractFQN, address: &[u8; 20]) -> PathBuf {
-        self.deployment_receipts_dir(contract)
+    /// Path: {deploy_dir}/{network_profile}/{target}/{chain_id}/{contract_file}/{contract_name}/{address}.json
+    pub fn for_deployment_receipt(
+        &self,
+        chain_id: u64,
+        contract: &ContractFQN,
+        address: &[u8; 20],
+    ) -> PathBuf {
+        self.deployment_receipts_dir(chain_id, contract)
             .join(hex(address))
             .with_extension("json")
     }
@@ -127,6 +127,77 @@ fn test_bad_config_compiler() {
     }
 }
 
+#[test]
+fn test_solc_settings() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+
+    let cfg = Config::from_file(dir.join("solc-settings-config.json")).unwrap();
+
+    // avalanche only gets the global settings
+    let ava = cfg.get_target_compiler_config(Target::Avalanche).solc;
+    assert_eq!(
+        ava.optimizer,
+        Some(OptimizerSettings {
+            enabled: true,
+            runs: 1000
+        })
+    );
+    assert_eq!(ava.via_ir, Some(true));
+    assert_eq!(ava.evm_version, None);
+    assert_eq!(ava.metadata_hash, Some(MetadataHash::None));
+    assert_eq!(
+        ava.remappings,
+        vec![format!("@oz/={}/", dir.join("lib/oz").display())]
+    );
+
+    // polygon overrides some of them
+    let poly = cfg.get_target_compiler_config(Target::Polygon).solc;
+    assert_eq!(poly.optimizer, ava.optimizer);
+    assert_eq!(poly.via_ir, Some(false));
+    assert_eq!(poly.evm_version, Some(EvmVersion::Paris));
+    assert_eq!(poly.metadata_hash, Some(MetadataHash::None));
+    assert_eq!(
+        poly.remappings,
+        vec![
+            format!("@oz/={}/", dir.join("lib/oz").display()),
+            format!("@poly/={}/", dir.join("lib/poly").display()),
+        ]
+    );
+}
+
+#[test]
+fn test_vendor_config() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+
+    let cfg = Config::from_file(dir.join("vendor-config.json")).unwrap();
+    let vendor = cfg.vendor().unwrap();
+    assert_eq!(vendor.dir, dir.join("vendor"));
+    assert_eq!(vendor.registry.as_str(), "https://registry.npmjs.org/");
+    assert_eq!(cfg.paths().lock_file, dir.join("cubist.lock"));
+
+    // the vendor directory is searched for imports
+    assert_eq!(
+        cfg.get_compiler_config().import_dirs,
+        vec![dir.join("vendor")]
+    );
+}
+
+#[test]
+fn test_bad_config_remapping() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+
+    match Config::from_file(dir.join("bad-config-remapping.json")) {
+        Err(ConfigError::InvalidRemapping(r)) => assert_eq!(r, "@oz/"),
+        c => panic!("Expected error, got {:?}", c),
+    }
+}
+
 #[test]
 fn test_bad_config_project() {
     let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
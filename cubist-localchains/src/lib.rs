@@ -24,7 +24,10 @@ mod proxy;
 pub mod resource;
 mod tracing;
 
-trait UrlExt {
+/// Extension of URLs of chains.
+pub trait UrlExt {
+    /// Whether the host of the URL resolves to this machine (e.g., a chain that Cubist can start
+    /// locally).
     fn is_loopback(&self) -> Result<bool>;
 }
 
@@ -5,12 +5,12 @@ use std::{iter::repeat, net::SocketAddr, time::Duration};
 /// These traits allow us to go from `Config -> Provider -> Server`
 /// while supporting things like on-demand binary downloading
 use crate::{
-    error::{Error, Result},
+    error::{Error, ProviderError, Result},
     proxy::Proxy,
     to_uri,
 };
 use async_trait::async_trait;
-use cubist_config::{network::CommonConfig, secret::SecretUrl, CredConfig};
+use cubist_config::{network::CommonConfig, secret::SecretUrl, CredConfig, CREATE2_FACTORY};
 use cubist_proxy::transformer::eth_creds::{build_wallets, EthProxyConfig};
 use cubist_util::tasks::retry;
 use futures::{
@@ -18,9 +18,10 @@ use futures::{
     select, Future,
 };
 use reqwest::Client;
+use secrecy::ExposeSecret;
 use serde_json::{json, Value};
 use tokio::process::Child;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use ethers::prelude::*;
 use ethers::providers;
@@ -250,6 +251,54 @@ async fn eth_fund_wallets(
     Ok(())
 }
 
+/// Pre-signed (chain-agnostic) transaction that deploys the canonical CREATE2 factory (see
+/// [`CREATE2_FACTORY`]); see <https://github.com/Arachnid/deterministic-deployment-proxy>.
+const CREATE2_FACTORY_DEPLOYMENT_TX: &str = "0xf8a58085174876e800830186a08080b853604580600e600039806000f350fe7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf31ba02222222222222222222222222222222222222222222222222222222222222222a02222222222222222222222222222222222222222222222222222222222222222";
+
+/// Account that signed [`CREATE2_FACTORY_DEPLOYMENT_TX`]
+const CREATE2_FACTORY_DEPLOYER: &str = "3fab184622dc19b6109349b94811493bf2a45362";
+
+/// What [`CREATE2_FACTORY_DEPLOYER`] pays for the deployment (100k gas at 100 gwei)
+const CREATE2_FACTORY_DEPLOYMENT_COST: u64 = 10_000_000_000_000_000;
+
+/// Deploys the canonical CREATE2 factory (unless it's already deployed), using the first account
+/// of the endpoint at `url` to pay for the deployment.
+///
+/// Failures are only logged, since the factory is needed only for CREATE2 deployments (which are
+/// opt-in) and some chains reject the (chain-agnostic) deployment transaction.
+async fn eth_deploy_create2_factory(url: &SecretUrl, name: &str) {
+    if let Err(e) = try_eth_deploy_create2_factory(url).await {
+        warn!("Failed to deploy CREATE2 factory on {name}: {e}");
+    }
+}
+
+async fn try_eth_deploy_create2_factory(url: &SecretUrl) -> Result<()> {
+    let url = url.load()?;
+    let provider = providers::Provider::<Http>::try_from(url.expose_secret())
+        .map_err(ProviderError::UrlParseError)?
+        .interval(Duration::from_millis(10));
+    if !provider.get_code(CREATE2_FACTORY, None).await?.is_empty() {
+        return Ok(());
+    }
+
+    debug!("Deploying CREATE2 factory at {CREATE2_FACTORY:?}");
+    let deployer: H160 = CREATE2_FACTORY_DEPLOYER.parse().unwrap();
+    let funder = *provider
+        .get_accounts()
+        .await?
+        .first()
+        .ok_or_else(|| ProviderError::SetupError("No account to fund deployment".to_owned()))?;
+    let tx = TransactionRequest::new()
+        .from(funder)
+        .to(deployer)
+        .value(CREATE2_FACTORY_DEPLOYMENT_COST);
+    provider.send_transaction(tx, None).await?.await?;
+
+    let deployment: Bytes = CREATE2_FACTORY_DEPLOYMENT_TX.parse().unwrap();
+    provider.send_raw_transaction(deployment).await?.await?;
+    Ok(())
+}
+
 /// Adds a method to run a dependent future in parallel to a given process.
 #[async_trait]
 pub trait WhileRunning<TR> {
@@ -28,7 +28,7 @@ use crate::{
     start_error, UrlExt,
 };
 
-use super::{eth_available, Provider, Server, WhileRunning};
+use super::{eth_available, eth_deploy_create2_factory, Provider, Server, WhileRunning};
 use crate::tracing::{child_stdio, trace_stdout};
 
 const DEFAULT_AVALANCHE_PORT: u16 = 8545;
@@ -530,6 +530,9 @@ impl Server for AvalancheNetworkRunnerServer {
     }
 
     async fn initialize(&mut self) -> Result<()> {
+        // go through the proxy, which manages the pre-funded account
+        let config = &self.config.config;
+        eth_deploy_create2_factory(&config.common.url, config.name()).await;
         Ok(())
     }
 }
@@ -19,7 +19,7 @@ use crate::{
     start_error, to_uri, UrlExt,
 };
 
-use super::{eth_available, Provider, Server, WhileRunning};
+use super::{eth_available, eth_deploy_create2_factory, Provider, Server, WhileRunning};
 use crate::tracing::{child_stdio, trace_stdout};
 
 const DEFAULT_ETHEREUM_PORT: u16 = 8545;
@@ -165,6 +165,7 @@ impl Server for AnvilServer {
     }
 
     async fn initialize(&mut self) -> Result<()> {
+        eth_deploy_create2_factory(&self.anvil_url, "anvil").await;
         Ok(())
     }
 }
@@ -21,7 +21,9 @@ use crate::{start_error, to_uri, UrlExt};
 
 use tokio::process::{Child, Command};
 
-use super::{eth_available, eth_fund_wallets, Provider, Server, WhileRunning};
+use super::{
+    eth_available, eth_deploy_create2_factory, eth_fund_wallets, Provider, Server, WhileRunning,
+};
 use crate::tracing::{child_stdio, trace_stdout};
 
 use ethers::prelude::*;
@@ -131,6 +133,7 @@ impl Server for BorServer {
         let provider = self.create_provider()?;
         let dev_account = self.get_dev_account(&provider).await?;
         eth_fund_wallets(&provider, dev_account, &self.to_fund).await?;
+        eth_deploy_create2_factory(&self.bor_url, PROVIDER_NAME).await;
         Ok(())
     }
 }
@@ -196,6 +199,9 @@ impl Provider for BorProvider {
                 &bor_port.to_string(),
             ])
             .args(["--http.api", "eth,net,web3,txpool"])
+            // needed for deploying the CREATE2 factory (whose deployment transaction is not
+            // replay-protected)
+            .arg("--rpc.allow-unprotected-txs")
             .arg("--networkid")
             .arg(BOR_CHAIN_ID.to_string())
             .args(["--miner.gasprice", "0"])
@@ -37,8 +37,43 @@ export interface CommonConfig {
     /** Whether to run a credentials proxy in front of the endpoint
      * (applies only if `url` is a remote address). */
     proxy: ProxyConfig,
+    /** How to set transaction fees for transactions sent to this chain. */
+    fees?: FeeStrategy,
+    /** Account to send transactions from by default (must be one of the
+     * accounts managed by the endpoint or its credentials proxy). When
+     * omitted, the first account reported by the endpoint is used. */
+    default_sender?: string,
+    /** Whether to simulate each transaction (with `eth_call`) before sending
+     * it, so that transactions that would revert fail early, with a decoded
+     * revert reason. */
+    simulate?: boolean,
+    /** Number of confirmations to wait for before considering a transaction
+     * final (default: 1). */
+    confirmations?: number,
+    /** How long (in seconds) to wait for a transaction to be confirmed
+     * before giving up (default: 60). */
+    receipt_timeout?: number,
+    /** How often (in milliseconds) to poll for transaction receipts
+     * (default: 250). */
+    poll_interval?: number,
+    /** Address of an already deployed Multicall3 contract to batch calls
+     * through. When omitted, Cubist deploys its own Multicall3-compatible
+     * contract (via the CREATE2 factory) on first use. */
+    multicall?: string,
 }
 
+/** Strategy for setting transaction fees: `legacy` (default), `eip1559`, or
+ * fixed EIP-1559 fee caps (in wei).
+ *
+ * @group Internal
+ * */
+export type FeeStrategy = 'legacy' | 'eip1559' | {
+    fixed: {
+        max_fee_per_gas: number,
+        max_priority_fee_per_gas: number,
+    },
+};
+
 /** Proxy configuration.
  *
  * @group Internal
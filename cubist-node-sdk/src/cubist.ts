@@ -905,7 +905,7 @@ export class Contract<T extends internal.Contract> {
       }),
     };
 
-    // Manifest path: deploy/cubist-deploy/{contract_name}-{address}.json
+    // Manifest path: deploy/{network_profile}/cubist-deploy/{contract_name}-{address}.json
     const manifestPath = this.deploymentManifestPath();
     await writeAtomic(dm, manifestPath);
   }
@@ -914,7 +914,8 @@ export class Contract<T extends internal.Contract> {
    * @return {PathBuf} - Directory where deployment manifest files are to be written.
    * */
   deploymentManifestDir(): PathBuf {
-    return path.join(this.project.config.deploy_dir(), 'cubist-deploy');
+    const config = this.project.config;
+    return path.join(config.deploy_dir(), config.current_network_profile, 'cubist-deploy');
   }
 
   /** @internal
@@ -1,6 +1,19 @@
+mod batch;
+mod bundle;
 mod contract;
 mod cubist;
+mod estimate;
+mod events;
+mod fees;
+mod history;
+mod journal;
 mod project;
-mod transformer;
-pub(crate) use self::transformer::LegacyTransformer;
-pub use self::{contract::*, cubist::*, project::*};
+mod revert;
+mod safe;
+mod snapshot;
+mod storage_layout;
+pub(crate) use self::fees::FeeMiddleware;
+pub use self::{
+    batch::*, bundle::*, contract::*, cubist::*, estimate::*, events::*, history::*, journal::*,
+    project::*, revert::*, safe::*, storage_layout::*,
+};
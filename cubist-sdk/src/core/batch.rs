@@ -0,0 +1,432 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use cubist_config::{paths::ContractFQN, util::OrBug, Target};
+use ethers::abi::{decode, encode, Detokenize, Function, ParamType, Token, Tokenize};
+use ethers::providers::Middleware;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, TransactionReceipt, TransactionRequest, U64};
+use futures::future::try_join_all;
+
+use crate::core::{Contract, ContractData, HttpStack, RevertReason};
+use crate::{CubistSdkError, Result, WrapperError};
+
+/// Signature of the Multicall3 function that calls are batched into.
+const AGGREGATE3: &str = "aggregate3((address,bool,bytes)[])";
+
+/// Encodes a call to Multicall3's `aggregate3`, given the address, whether failure is allowed,
+/// and the call data of each call.
+fn encode_aggregate3(calls: impl IntoIterator<Item = (Address, bool, Bytes)>) -> Bytes {
+    let calls = calls
+        .into_iter()
+        .map(|(address, allow_failure, data)| {
+            Token::Tuple(vec![
+                Token::Address(address),
+                Token::Bool(allow_failure),
+                Token::Bytes(data.to_vec()),
+            ])
+        })
+        .collect();
+    let args = encode(&[Token::Array(calls)]);
+    [&ethers::utils::id(AGGREGATE3)[..], &args].concat().into()
+}
+
+/// Decodes the output of Multicall3's `aggregate3`, i.e., whether each call succeeded and what
+/// it returned (or reverted with).
+fn decode_aggregate3(output: &[u8]) -> Result<Vec<(bool, Bytes)>, WrapperError> {
+    let result_type = ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes]);
+    let tokens = decode(&[ParamType::Array(Box::new(result_type))], output)?;
+    let unexpected = || WrapperError::ContractError("Unexpected output of 'aggregate3'".to_owned());
+    let results = match tokens.into_iter().next() {
+        Some(Token::Array(results)) => results,
+        _ => return Err(unexpected()),
+    };
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(success), Token::Bytes(data)] => {
+                    Ok((*success, Bytes::from(data.clone())))
+                }
+                _ => Err(unexpected()),
+            },
+            _ => Err(unexpected()),
+        })
+        .collect()
+}
+
+/// Handle to a call added to a [`Batch`], used to get the call's result (decoded into `T`) from
+/// [`BatchResults`].
+pub struct CallHandle<T> {
+    index: usize,
+    _ret: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for CallHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CallHandle<T> {}
+
+/// A call added to a [`Batch`].
+struct BatchCall<M: Middleware> {
+    /// Called contract
+    contract: Arc<Contract<M>>,
+    /// Called method
+    method_name: String,
+    /// Abi of the called method (to decode its output)
+    function: Function,
+    /// Address of the called contract
+    address: Address,
+    /// Encoded call
+    data: Bytes,
+}
+
+impl<M: Middleware> BatchCall<M> {
+    /// Decodes why this call reverted, given its revert data.
+    fn revert_reason(&self, data: &[u8]) -> RevertReason {
+        let abi = match &self.contract.meta.data {
+            ContractData::SolidityData { abi, .. } => Some(abi),
+            ContractData::SorobanData { .. } => None,
+        };
+        RevertReason::decode(data, abi)
+    }
+}
+
+/// Builder of a batch of calls to deployed EVM contracts (on one or several targets), which are
+/// grouped per target into calls to a Multicall3 contract (see
+/// [`TargetProject::multicall`](crate::core::TargetProject::multicall)), so that reading many
+/// values takes a single round trip per target (see [`Self::call`]), and sending many
+/// transactions takes a single, atomic, transaction per target (see [`Self::send_atomic`]).
+///
+/// Note that the batched calls are made by the Multicall3 contract, i.e., `msg.sender` is the
+/// address of that contract (rather than the sender of the transaction).
+pub struct Batch<M: Middleware = HttpStack> {
+    calls: Vec<BatchCall<M>>,
+}
+
+impl<M: Middleware> Default for Batch<M> {
+    fn default() -> Self {
+        Batch { calls: vec![] }
+    }
+}
+
+impl<M: Middleware> Batch<M> {
+    /// Constructor (of an empty batch).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a call of method `name` (with `args`) on (deployed) `contract` to this batch.  Returns
+    /// the handle to get the call's result, decoded into `TRet`, with (see [`BatchResults::get`]).
+    pub fn add<TArgs, TRet>(
+        &mut self,
+        contract: &Arc<Contract<M>>,
+        name: &str,
+        args: TArgs,
+    ) -> Result<CallHandle<TRet>>
+    where
+        TArgs: Tokenize,
+        TRet: Detokenize,
+    {
+        let call = contract.method::<_, TRet>(name, args)?;
+        let data = call.tx.data().cloned().unwrap_or_default();
+        self.calls.push(BatchCall {
+            contract: Arc::clone(contract),
+            method_name: name.to_owned(),
+            function: call.function,
+            address: Address::from_slice(&contract.address_unsafe()),
+            data,
+        });
+        Ok(CallHandle {
+            index: self.calls.len() - 1,
+            _ret: PhantomData,
+        })
+    }
+
+    /// Number of calls in this batch.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Whether this batch has no calls.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Makes all calls of this batch (with one `eth_call` per target, concurrently across
+    /// targets) and returns their results.  A call that reverts doesn't fail the others; its
+    /// revert reason is returned when getting its result instead.
+    ///
+    /// The batch can be called again (e.g., to refresh the results).
+    pub async fn call(&self) -> Result<BatchResults> {
+        let groups = self.by_target();
+        let outputs = try_join_all(groups.iter().map(|indices| self.aggregate(indices))).await?;
+        let mut outcomes: Vec<Option<CallOutcome>> = self.calls.iter().map(|_| None).collect();
+        for (indices, results) in groups.iter().zip(outputs) {
+            for (index, (success, data)) in indices.iter().zip(results) {
+                let call = &self.calls[*index];
+                outcomes[*index] = Some(CallOutcome {
+                    contract: call.contract.meta.fqn.clone(),
+                    target: call.contract.target(),
+                    method_name: call.method_name.clone(),
+                    function: call.function.clone(),
+                    result: if success {
+                        Ok(data)
+                    } else {
+                        Err(call.revert_reason(&data))
+                    },
+                });
+            }
+        }
+        Ok(BatchResults {
+            outcomes: outcomes
+                .into_iter()
+                .map(|o| o.or_bug("Every call belongs to a target group"))
+                .collect(),
+        })
+    }
+
+    /// Sends all calls of this batch as transactions, with a single transaction per target (sent
+    /// concurrently across targets) in which either all calls on that target succeed or none do.
+    /// Each transaction is sent from the sender of the first contract added for its target (see
+    /// [`Contract::as_signer`]).  Returns the receipts, in the order the targets first appear in
+    /// the batch.
+    ///
+    /// If [`simulate`](cubist_config::network::CommonConfig::simulate) is set for a target, the
+    /// calls on that target are simulated first, and the first one that would revert is reported
+    /// (as [`CubistSdkError::Reverted`]).
+    pub async fn send_atomic(&self) -> Result<Vec<TransactionReceipt>> {
+        let groups = self.by_target();
+        try_join_all(groups.iter().map(|indices| self.send_aggregate(indices))).await
+    }
+
+    /// Indices of the calls of this batch grouped by target (in the order the targets first
+    /// appear in the batch).
+    fn by_target(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<(Target, Vec<usize>)> = vec![];
+        for (index, call) in self.calls.iter().enumerate() {
+            let target = call.contract.target();
+            match groups.iter_mut().find(|(t, _)| *t == target) {
+                Some((_, indices)) => indices.push(index),
+                None => groups.push((target, vec![index])),
+            }
+        }
+        groups.into_iter().map(|(_, indices)| indices).collect()
+    }
+
+    /// Encodes a call to `aggregate3` of the calls at `indices` on the multicall contract of
+    /// their target (which is deployed first if needed).
+    async fn aggregate3_tx(
+        &self,
+        indices: &[usize],
+        allow_failure: bool,
+    ) -> Result<TypedTransaction> {
+        let project = &self.calls[indices[0]].contract.project;
+        let multicall = project.multicall().await?;
+        let data = encode_aggregate3(indices.iter().map(|index| {
+            let call = &self.calls[*index];
+            (call.address, allow_failure, call.data.clone())
+        }));
+        Ok(TransactionRequest::new().to(multicall).data(data).into())
+    }
+
+    /// Makes the calls at `indices` (all on the same target) with a single `eth_call`, allowing
+    /// each of them to fail, and returns whether each succeeded along with its output.
+    async fn aggregate(&self, indices: &[usize]) -> Result<Vec<(bool, Bytes)>> {
+        let project = &self.calls[indices[0]].contract.project;
+        let batch_err = |e: WrapperError| CubistSdkError::BatchError(project.target, Box::new(e));
+        let tx = self.aggregate3_tx(indices, true).await?;
+        let output = project.provider().call(&tx, None).await.map_err(|e| {
+            batch_err(WrapperError::ProviderError(
+                "eth_call".to_owned(),
+                e.to_string(),
+            ))
+        })?;
+        let results = decode_aggregate3(&output).map_err(batch_err)?;
+        if results.len() != indices.len() {
+            return Err(batch_err(WrapperError::ContractError(format!(
+                "Expected {} results from 'aggregate3' but got {}",
+                indices.len(),
+                results.len()
+            ))));
+        }
+        Ok(results)
+    }
+
+    /// Sends the calls at `indices` (all on the same target) in a single transaction that
+    /// reverts if any of them does.
+    async fn send_aggregate(&self, indices: &[usize]) -> Result<TransactionReceipt> {
+        let project = &self.calls[indices[0]].contract.project;
+        let batch_err = |e: WrapperError| CubistSdkError::BatchError(project.target, Box::new(e));
+        if project.simulate() {
+            let results = self.aggregate(indices).await?;
+            let failed = indices
+                .iter()
+                .zip(results)
+                .find(|(_, (success, _))| !success);
+            if let Some((index, (_, data))) = failed {
+                let call = &self.calls[*index];
+                return Err(CubistSdkError::Reverted {
+                    contract: call.contract.meta.fqn.clone(),
+                    target: project.target,
+                    method_name: call.method_name.clone(),
+                    reason: call.revert_reason(&data),
+                });
+            }
+        }
+        let tx = self.aggregate3_tx(indices, false).await?;
+        let receipt = project.send_tx(tx).await.map_err(batch_err)?;
+        if receipt.status == Some(U64::zero()) {
+            return Err(batch_err(WrapperError::ContractError(format!(
+                "Batch transaction {:?} reverted",
+                receipt.transaction_hash
+            ))));
+        }
+        Ok(receipt)
+    }
+}
+
+/// Result of a call made as part of a batch.
+struct CallOutcome {
+    /// Called contract
+    contract: ContractFQN,
+    /// Target of the called contract
+    target: Target,
+    /// Called method
+    method_name: String,
+    /// Abi of the called method
+    function: Function,
+    /// Returned data, or why the call reverted
+    result: std::result::Result<Bytes, RevertReason>,
+}
+
+/// Results of the calls of a [`Batch`] (see [`Batch::call`]).
+pub struct BatchResults {
+    outcomes: Vec<CallOutcome>,
+}
+
+impl BatchResults {
+    /// Returns the result of the call added to the batch as `handle`, decoded into `T`, or
+    /// [`CubistSdkError::Reverted`] if the call reverted.
+    ///
+    /// # Panics
+    /// * if `handle` was not returned by the batch these are the results of
+    pub fn get<T: Detokenize>(&self, handle: CallHandle<T>) -> Result<T> {
+        let outcome = &self.outcomes[handle.index];
+        let data = match &outcome.result {
+            Ok(data) => data,
+            Err(reason) => {
+                return Err(CubistSdkError::Reverted {
+                    contract: outcome.contract.clone(),
+                    target: outcome.target,
+                    method_name: outcome.method_name.clone(),
+                    reason: reason.clone(),
+                })
+            }
+        };
+        outcome
+            .function
+            .decode_output(data)
+            .map_err(WrapperError::from)
+            .and_then(|tokens| {
+                T::from_tokens(tokens).map_err(|e| WrapperError::ContractAbiError(e.into()))
+            })
+            .map_err(|e| CubistSdkError::CallError {
+                contract: outcome.contract.clone(),
+                target: outcome.target,
+                method_name: outcome.method_name.clone(),
+                source: Box::new(e),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Abi;
+    use ethers::types::U256;
+    use serde_json::json;
+
+    #[test]
+    fn test_encode_aggregate3() {
+        let address = Address::repeat_byte(0x11);
+        let data = encode_aggregate3([(address, true, Bytes::from(vec![1, 2, 3]))]);
+        // same selector as Multicall3's 'aggregate3'
+        assert_eq!([0x82, 0xad, 0x56, 0xcb], data[..4]);
+        let tokens = decode(
+            &[ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::Bool,
+                ParamType::Bytes,
+            ])))],
+            &data[4..],
+        )
+        .unwrap();
+        assert_eq!(
+            vec![Token::Array(vec![Token::Tuple(vec![
+                Token::Address(address),
+                Token::Bool(true),
+                Token::Bytes(vec![1, 2, 3]),
+            ])])],
+            tokens
+        );
+    }
+
+    #[test]
+    fn test_decode_aggregate3() {
+        let output = encode(&[Token::Array(vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![0xab])]),
+            Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![])]),
+        ])]);
+        assert_eq!(
+            vec![(true, Bytes::from(vec![0xab])), (false, Bytes::default())],
+            decode_aggregate3(&output).unwrap()
+        );
+        assert!(decode_aggregate3(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_batch_results() {
+        let abi: Abi = serde_json::from_value(json!([{
+            "type": "function",
+            "name": "retrieve",
+            "inputs": [],
+            "outputs": [{ "name": "", "type": "uint256" }],
+            "stateMutability": "view"
+        }]))
+        .unwrap();
+        let outcome = |result| CallOutcome {
+            contract: ContractFQN::new("Storage.sol".into(), "Storage".to_owned()),
+            target: Target::Ethereum,
+            method_name: "retrieve".to_owned(),
+            function: abi.function("retrieve").unwrap().clone(),
+            result,
+        };
+        let results = BatchResults {
+            outcomes: vec![
+                outcome(Ok(encode(&[Token::Uint(U256::from(42))]).into())),
+                outcome(Err(RevertReason::Error("not stored".to_owned()))),
+                outcome(Ok(Bytes::default())),
+            ],
+        };
+        let handle = |index| CallHandle::<U256> {
+            index,
+            _ret: PhantomData,
+        };
+        assert_eq!(U256::from(42), results.get(handle(0)).unwrap());
+        assert!(matches!(
+            results.get(handle(1)),
+            Err(CubistSdkError::Reverted {
+                reason: RevertReason::Error(message),
+                ..
+            }) if message == "not stored"
+        ));
+        assert!(matches!(
+            results.get(handle(2)),
+            Err(CubistSdkError::CallError { .. })
+        ));
+    }
+}
@@ -0,0 +1,302 @@
+use std::fs;
+use std::path::Path;
+
+use cubist_config::paths::ContractFQN;
+use cubist_config::util::OrBug;
+use cubist_config::Target;
+use ethers::providers::Middleware;
+use ethers::types::{transaction::eip2718::TypedTransaction, Address, Bytes, U256};
+use ethers::utils::rlp::Rlp;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{DeploymentManifest, TargetProject};
+use crate::{CubistSdkError, Result, WrapperError};
+
+/// Transaction of a [`TxBundle`], to be signed offline.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct BundledTx {
+    /// What the transaction does (for whoever signs it)
+    pub description: String,
+    /// Chain to send the transaction to
+    pub target: Target,
+    /// Id of that chain (which signers must sign the transaction for)
+    pub chain_id: u64,
+    /// The unsigned transaction, with its sender, nonce, calldata, gas, and fees set
+    pub tx: TypedTransaction,
+    /// Contract created by the transaction (contract creations only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<ContractFQN>,
+    /// Address of the contract created by the transaction (contract creations only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creates: Option<Address>,
+    /// The signed transaction (RLP-encoded), filled in by the signer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed: Option<Bytes>,
+}
+
+impl BundledTx {
+    /// The transaction to sign (i.e., [`Self::tx`] for chain [`Self::chain_id`]).
+    pub fn to_sign(&self) -> TypedTransaction {
+        let mut tx = self.tx.clone();
+        tx.set_chain_id(self.chain_id);
+        tx
+    }
+
+    /// Nonce of the transaction.
+    pub fn nonce(&self) -> U256 {
+        self.tx.nonce().copied().unwrap_or_default()
+    }
+
+    /// Returns [`Self::signed`], after checking that it is a signed version of [`Self::tx`]
+    /// (i.e., that it has the same nonce, recipient, value, and calldata), signed by the sender
+    /// of [`Self::tx`] for chain [`Self::chain_id`].  Returns why not otherwise.
+    pub fn checked_signed(&self) -> std::result::Result<&Bytes, String> {
+        let signed = self.signed.as_ref().ok_or("transaction is not signed")?;
+        let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(signed))
+            .map_err(|e| format!("cannot decode signed transaction: {e}"))?;
+        let value = |tx: &TypedTransaction| tx.value().copied().unwrap_or_default();
+        if tx.nonce() != self.tx.nonce()
+            || tx.to() != self.tx.to()
+            || value(&tx) != value(&self.tx)
+            || tx.data() != self.tx.data()
+        {
+            return Err("signed transaction differs from the unsigned one".to_owned());
+        }
+        if tx.chain_id() != Some(self.chain_id.into()) {
+            return Err(format!(
+                "transaction is signed for chain {:?} rather than {}",
+                tx.chain_id(),
+                self.chain_id
+            ));
+        }
+        let signer = signature
+            .recover(tx.sighash())
+            .map_err(|e| format!("cannot recover signer: {e}"))?;
+        if Some(&signer) != self.tx.from() {
+            return Err(format!(
+                "transaction is signed by {signer:?} rather than its sender"
+            ));
+        }
+        Ok(signed)
+    }
+}
+
+/// Unsigned transactions produced instead of broadcasting them, for signing offline (e.g., with
+/// a hardware wallet or a multisig).  Stored as JSON (see [`Self::write_file`]); signers fill in
+/// [`BundledTx::signed`] for every transaction, after which the bundle can be broadcast (see
+/// [`Cubist::broadcast`](crate::Cubist::broadcast)).
+///
+/// Nonces are assigned in order, so the transactions of each sender on each chain must be
+/// broadcast in the order they appear in the bundle.  Since nonces (and thus the addresses of
+/// created contracts) are fixed upfront, transactions may refer to contracts created earlier in
+/// the same bundle.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TxBundle {
+    /// Transactions, in the order they must be broadcast
+    pub transactions: Vec<BundledTx>,
+    /// Deployment manifests to write once all transactions are confirmed
+    #[serde(default)]
+    pub deployments: Vec<DeploymentManifest>,
+}
+
+impl TxBundle {
+    /// Reads a bundle from a file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let bundle_err = |e| CubistSdkError::TxBundleError(path.to_path_buf(), Box::new(e));
+        let content = fs::read_to_string(path)
+            .map_err(|e| bundle_err(WrapperError::IOError(path.to_path_buf(), e)))?;
+        serde_json::from_str(&content).map_err(|e| {
+            bundle_err(WrapperError::JsonError(
+                path.to_path_buf(),
+                "TxBundle".to_owned(),
+                e,
+            ))
+        })
+    }
+
+    /// Writes the bundle to a file (as pretty-printed JSON).
+    pub fn write_file(&self, path: &Path) -> Result<()> {
+        let bundle_err = |e| CubistSdkError::TxBundleError(path.to_path_buf(), Box::new(e));
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            bundle_err(WrapperError::JsonError(
+                path.to_path_buf(),
+                "TxBundle".to_owned(),
+                e,
+            ))
+        })?;
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|e| bundle_err(WrapperError::IOError(parent_dir.to_path_buf(), e)))?;
+        }
+        fs::write(path, content)
+            .map_err(|e| bundle_err(WrapperError::IOError(path.to_path_buf(), e)))
+    }
+
+    /// Whether the bundle has no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Address of `contract` on `target` if the bundle creates it.
+    pub fn created(&self, target: Target, contract: &ContractFQN) -> Option<Address> {
+        self.transactions
+            .iter()
+            .filter(|t| t.target == target)
+            .find(|t| matches!(&t.contract, Some(c) if c.is_same_as(contract)))
+            .and_then(|t| t.creates)
+    }
+
+    /// Adds `tx` (to be sent to `project`) to the bundle, after filling in everything needed to
+    /// sign it offline (see [`TargetProject::fill_unsigned_tx`]).  The nonce follows the nonce of
+    /// the sender's last transaction in the bundle (or is the sender's next nonce on the chain).
+    pub(crate) async fn push<M: Middleware>(
+        &mut self,
+        project: &TargetProject<M>,
+        description: String,
+        mut tx: TypedTransaction,
+    ) -> Result<&mut BundledTx> {
+        let target = project.target;
+        let bundle_err = |e| CubistSdkError::BundleTxError(description.clone(), Box::new(e));
+        let from = match tx.from() {
+            Some(from) => *from,
+            None => project.sender().await?,
+        };
+        tx.set_from(from);
+        let last_nonce = self
+            .transactions
+            .iter()
+            .filter(|t| t.target == target && t.tx.from() == Some(&from))
+            .map(BundledTx::nonce)
+            .max();
+        let nonce = match last_nonce {
+            Some(nonce) => nonce + 1,
+            None => project.pending_nonce(from).await.map_err(bundle_err)?,
+        };
+        project
+            .fill_unsigned_tx(&mut tx, nonce)
+            .await
+            .map_err(bundle_err)?;
+        self.transactions.push(BundledTx {
+            description,
+            target,
+            chain_id: project.chain_id().await?,
+            tx,
+            contract: None,
+            creates: None,
+            signed: None,
+        });
+        Ok(self
+            .transactions
+            .last_mut()
+            .or_bug("Just pushed a transaction"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{TransactionRequest, U64};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_roundtrip() {
+        let fqn = ContractFQN::new(PathBuf::from("a.sol"), "Receiver".to_owned());
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(Address::repeat_byte(1))
+            .nonce(7u64)
+            .gas(100_000u64)
+            .gas_price(10u64)
+            .data(vec![1, 2, 3])
+            .into();
+        let bundle = TxBundle {
+            transactions: vec![BundledTx {
+                description: "Deploy Receiver".to_owned(),
+                target: Target::Ethereum,
+                chain_id: 31337,
+                tx,
+                contract: Some(fqn.clone()),
+                creates: Some(Address::repeat_byte(2)),
+                signed: None,
+            }],
+            deployments: vec![],
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.json");
+        bundle.write_file(&path).unwrap();
+        let read = TxBundle::from_file(&path).unwrap();
+        assert_eq!(bundle, read);
+        assert_eq!(U256::from(7), read.transactions[0].nonce());
+        assert_eq!(
+            Some(U64::from(31337)),
+            read.transactions[0].to_sign().chain_id()
+        );
+        assert_eq!(
+            Some(Address::repeat_byte(2)),
+            read.created(Target::Ethereum, &fqn)
+        );
+        assert_eq!(None, read.created(Target::Polygon, &fqn));
+    }
+
+    #[test]
+    fn test_checked_signed() {
+        let wallet: LocalWallet =
+            "0000000000000000000000000000000000000000000000000000000000000001"
+                .parse()
+                .unwrap();
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(wallet.address())
+            .to(Address::repeat_byte(2))
+            .nonce(3u64)
+            .gas(21_000u64)
+            .gas_price(10u64)
+            .into();
+        let mut utx = BundledTx {
+            description: "Transfer".to_owned(),
+            target: Target::Ethereum,
+            chain_id: 1,
+            tx,
+            contract: None,
+            creates: None,
+            signed: None,
+        };
+        assert!(utx.checked_signed().is_err());
+
+        let to_sign = utx.to_sign();
+        let signature = wallet.with_chain_id(1u64).sign_transaction_sync(&to_sign);
+        utx.signed = Some(to_sign.rlp_signed(&signature));
+        assert!(utx.checked_signed().is_ok());
+
+        // signed for another chain
+        let mut other_chain = to_sign.clone();
+        other_chain.set_chain_id(2u64);
+        let signature = wallet
+            .with_chain_id(2u64)
+            .sign_transaction_sync(&other_chain);
+        utx.signed = Some(other_chain.rlp_signed(&signature));
+        assert!(utx.checked_signed().unwrap_err().contains("chain"));
+
+        // signed by someone else than the sender
+        let other: LocalWallet = "0000000000000000000000000000000000000000000000000000000000000002"
+            .parse()
+            .unwrap();
+        let signature = other.with_chain_id(1u64).sign_transaction_sync(&to_sign);
+        utx.signed = Some(to_sign.rlp_signed(&signature));
+        assert!(utx.checked_signed().unwrap_err().contains("signed by"));
+
+        // the signed transaction sends a different value
+        let mut with_value = to_sign.clone();
+        with_value.set_value(1u64);
+        let signature = wallet
+            .with_chain_id(1u64)
+            .sign_transaction_sync(&with_value);
+        utx.signed = Some(with_value.rlp_signed(&signature));
+        assert!(utx.checked_signed().unwrap_err().contains("differs"));
+
+        let signature = wallet.with_chain_id(1u64).sign_transaction_sync(&to_sign);
+        utx.signed = Some(to_sign.rlp_signed(&signature));
+        utx.tx.set_nonce(4u64);
+        assert!(utx.checked_signed().is_err());
+    }
+}
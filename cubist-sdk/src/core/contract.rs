@@ -1,21 +1,41 @@
 use crate::core::DeploymentInfo;
 use crate::core::DeploymentManifest;
+use crate::core::ProxyDeployment;
+use crate::core::ReceiptOptions;
+use crate::core::RevertReason;
+use crate::core::TxBundle;
+use crate::core::{admin_safe, AdminTx, SafeProposal, SafeProposer};
+use crate::core::{decode_evm_event, evm_events, evm_filter, stellar_events, EventStream};
+use crate::core::{DeploymentEstimate, GasTally, CALL_GAS_ALLOWANCE, PROXY_GAS_ALLOWANCE};
+use crate::core::{DeploymentEvent, DeploymentHistory, DeploymentRecord};
+use crate::core::{DeploymentJournal, JournaledDep};
 use crate::gen::backend;
-use crate::gen::APPROVE_CALLER_METHOD_NAME;
+use crate::gen::proxy::{PROXY_ADMIN_CONTRACT, PROXY_ADMIN_TRANSFER_OWNERSHIP_METHOD_NAME};
+use crate::gen::{
+    ACCEPT_SHIM_OWNERSHIP_METHOD_NAME, APPROVED_CALLERS_METHOD_NAME, APPROVE_CALLER_METHOD_NAME,
+    PAUSE_SHIM_METHOD_NAME, PENDING_SHIM_OWNER_METHOD_NAME, REVOKE_CALLER_METHOD_NAME,
+    SET_SHIM_GUARDIAN_METHOD_NAME, SHIM_GUARDIANS_METHOD_NAME, SHIM_OWNER_METHOD_NAME,
+    SHIM_PAUSED_METHOD_NAME, TRANSFER_SHIM_OWNERSHIP_METHOD_NAME, UNPAUSE_SHIM_METHOD_NAME,
+};
+use crate::relayer;
 use crate::CubistSdkError;
 use crate::Result;
 use crate::WrapperError;
 use cubist_config::paths::{hex, ContractFQN};
 use cubist_config::util::OrBug;
-use cubist_config::Target;
-use ethers::abi::{Address, Detokenize, Tokenize};
+use cubist_config::{ProxyKind, Target};
+use ethers::abi::{Address, Detokenize, RawLog, Token, Tokenize};
+use ethers::contract::EthLogDecode;
 use ethers::core::abi::Abi;
 use ethers::prelude::builders::ContractCall;
 use ethers::providers::Middleware;
-use ethers::types::{Bytes, TransactionReceipt};
-use futures::FutureExt;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Bytes, TransactionReceipt, TransactionRequest};
+use ethers_solc::artifacts::StorageLayout;
+use futures::{FutureExt, StreamExt};
+use serde::Serialize;
 use soroban_env_host::xdr::ScSpecEntry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
@@ -25,11 +45,12 @@ use tracing::Level;
 use tracing::{debug, span, trace, warn};
 
 use super::project::BridgeInfo;
-use super::{HttpStack, TargetProject};
+use super::{HttpStack, TargetProject, WithSender};
 
 type EthersContract<M> = ethers::contract::Contract<M>;
 
 /// Data for the contract
+#[derive(Clone)]
 pub enum ContractData {
     /// Data related to a Solidity contract
     SolidityData {
@@ -37,6 +58,8 @@ pub enum ContractData {
         abi: Abi,
         /// Contract bytecode
         bytes: Bytes,
+        /// Additional compiler outputs (e.g., for debuggers and upgrade safety tools)
+        artifacts: Box<SolidityArtifacts>,
     },
     /// Data related to a Soroban contract
     SorobanData {
@@ -49,7 +72,84 @@ pub enum ContractData {
     },
 }
 
+/// Compiler outputs of a Solidity contract beyond its abi and bytecode.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolidityArtifacts {
+    /// Deployed (runtime) bytecode
+    pub deployed_bytes: Option<Bytes>,
+    /// Source map of the bytecode
+    pub source_map: Option<String>,
+    /// Source map of the deployed bytecode
+    pub deployed_source_map: Option<String>,
+    /// Storage layout
+    pub storage_layout: Option<StorageLayout>,
+    /// Function signatures mapped to their selectors (hex strings)
+    pub method_identifiers: BTreeMap<String, String>,
+    /// Standard-JSON build info (i.e., compiler input and output) the contract was compiled from
+    pub build_info: Option<PathBuf>,
+}
+
+/// Current role holders of a deployed shim (see [`Contract::shim_roles`]).
+#[derive(Clone, Debug, Serialize)]
+pub struct ShimRoles {
+    /// Chain the shim is deployed to
+    pub target: Target,
+    /// Address of the shim
+    pub address: Address,
+    /// Owner (who administers the shim)
+    pub owner: Address,
+    /// Account that ownership is being transferred to (until it accepts the transfer)
+    pub pending_owner: Option<Address>,
+    /// Approved callers (who hold the CALLER role)
+    pub callers: Vec<Address>,
+    /// Guardians (who hold the GUARDIAN role, which allows pausing the shim)
+    pub guardians: Vec<Address>,
+    /// Whether the shim is paused (see [`Contract::pause_shims`])
+    pub paused: bool,
+}
+
+/// Account that administers a shim for a given admin transaction (see
+/// [`Contract::send_to_shims`]).
+#[derive(Clone, Copy, Debug)]
+enum ShimAdmin {
+    /// The owner of the shim
+    Owner,
+    /// The account that ownership of the shim is being transferred to
+    PendingOwner,
+    /// The sender of the shim's chain, if it is a guardian of the shim, or else the owner
+    Guardian,
+}
+
+impl ShimAdmin {
+    /// Resolves the account administering a shim, given its `owner`, its `pending_owner` (zero if
+    /// no ownership transfer is pending), its `guardians`, and the `sender` of its chain (if
+    /// any).  Returns why there is no such account otherwise.
+    fn resolve(
+        self,
+        owner: Address,
+        pending_owner: Address,
+        guardians: &[Address],
+        sender: Option<Address>,
+    ) -> std::result::Result<Address, &'static str> {
+        let (admin, missing) = match self {
+            ShimAdmin::Owner => (owner, "shim has no owner"),
+            ShimAdmin::PendingOwner => (pending_owner, "no pending ownership transfer"),
+            ShimAdmin::Guardian => match sender.filter(|sender| guardians.contains(sender)) {
+                Some(guardian) => return Ok(guardian),
+                None => (owner, "sender is not a guardian and shim has no owner"),
+            },
+        };
+        if admin.is_zero() {
+            Err(missing)
+        } else {
+            Ok(admin)
+        }
+    }
+}
+
 /// Contract compiled into abi and bytecode.
+#[derive(Clone)]
 pub struct ContractInfo {
     /// Fully qualified name
     pub fqn: ContractFQN,
@@ -57,6 +157,16 @@ pub struct ContractInfo {
     pub data: ContractData,
 }
 
+impl ContractInfo {
+    /// Additional compiler outputs if this is a Solidity contract.
+    pub fn solidity_artifacts(&self) -> Option<&SolidityArtifacts> {
+        match &self.data {
+            ContractData::SolidityData { artifacts, .. } => Some(artifacts),
+            ContractData::SorobanData { .. } => None,
+        }
+    }
+}
+
 /// The address of a contract (blockchain independent)
 pub type ContractAddress = Vec<u8>;
 
@@ -98,9 +208,66 @@ pub struct Contract<M: Middleware = HttpStack> {
 
     /// Ethers contract implementation, initialized once this contract is deployed (or loaded from a deployment receipt).
     inner: OnceCell<DeployedContract<M>>,
+
+    /// Proxy info, initialized once this contract is deployed behind a proxy (see
+    /// [`Self::is_upgradeable`]).
+    proxy: OnceCell<ProxyDeployment>,
+}
+
+impl<M: WithSender> Contract<M> {
+    /// Returns a handle to this contract whose transactions are sent from `sender`.
+    ///
+    /// If this contract is already deployed (or loaded from its deployment receipt), the returned
+    /// handle refers to the same deployment; otherwise, the returned handle is deployable
+    /// independently of `self` (and it deploys the contract from `sender`).
+    pub fn as_signer(&self, sender: Address) -> Contract<M> {
+        self.with_project(Arc::new(self.project.with_sender(sender)))
+    }
 }
 
 impl<M: Middleware> Contract<M> {
+    /// Returns a handle to this contract that waits for the receipts of its transactions (e.g.,
+    /// for more confirmations or longer) according to `options`.
+    ///
+    /// Like [`Self::as_signer`], the returned handle refers to the same deployment if this
+    /// contract is already deployed, and is deployable independently of `self` otherwise.
+    pub fn with_receipt_options(&self, options: ReceiptOptions) -> Contract<M> {
+        self.with_project(Arc::new(self.project.with_receipt_options(options)))
+    }
+
+    /// Returns a copy of this contract (sharing its shims, dependencies, and deployment, if any)
+    /// that talks to its target chain via `project`.
+    pub(crate) fn with_project(&self, project: Arc<TargetProject<M>>) -> Contract<M> {
+        self.rebuild(project, self.shims.clone(), self.deps.clone())
+    }
+
+    /// Like [`Self::with_project`], but the returned copy uses the given `shims` and `deps`
+    /// (which must be copies of this contract's own shims and dependencies).
+    pub(crate) fn rebuild(
+        &self,
+        project: Arc<TargetProject<M>>,
+        shims: HashMap<Target, Arc<Contract<M>>>,
+        deps: Vec<Arc<Contract<M>>>,
+    ) -> Contract<M> {
+        let deployed = self.inner.get().map(|deployed| match deployed {
+            DeployedContract::Evm { inner } => DeployedContract::Evm {
+                inner: project.at(&self.meta, inner.address()),
+            },
+            DeployedContract::Stellar { address } => DeployedContract::Stellar {
+                address: address.clone(),
+            },
+        });
+        Contract {
+            is_shim: self.is_shim,
+            project,
+            meta: self.meta.clone(),
+            shims,
+            deps,
+            inner: OnceCell::new_with(deployed),
+            proxy: OnceCell::new_with(self.proxy.get().cloned()),
+        }
+    }
+
     /// Create new shim contract for a given chain project.
     ///
     /// # Arguments
@@ -114,6 +281,7 @@ impl<M: Middleware> Contract<M> {
             shims: HashMap::new(),
             deps: Vec::new(),
             inner: OnceCell::new(),
+            proxy: OnceCell::new(),
         }
     }
 
@@ -145,6 +313,7 @@ impl<M: Middleware> Contract<M> {
             shims,
             deps,
             inner: OnceCell::new(),
+            proxy: OnceCell::new(),
         }
     }
 
@@ -152,6 +321,10 @@ impl<M: Middleware> Contract<M> {
     /// bridged (in which case the result is `true`) or when the
     /// timeout expires (in which case the result is `false`).
     ///
+    /// If [`relayer_endpoint`](cubist_config::Config::relayer_endpoint) is configured, waits
+    /// (for up to the sum of `delays`) for the relayer to report that this contract is bridged;
+    /// otherwise, or if the relayer can't be reached, polls for the relayer's marker file.
+    ///
     /// # Arguments
     /// * `delays` - how long to wait between retries
     ///
@@ -169,24 +342,55 @@ impl<M: Middleware> Contract<M> {
         }
 
         let addr = self.address().or_bug("already checked that addr is set");
-        let path = self
-            .project
-            .paths
-            .notify_contract_bridged(&self.meta.fqn, &addr);
+        let total_delay: Duration = delays.iter().sum();
+        if let Some(endpoint) = &self.project.relayer_endpoint {
+            // the relayer only notifies subscribers when it bridges a contract, so one that got
+            // bridged already (e.g., before the relayer was restarted) is only known by its marker
+            if self.has_bridged_marker(&addr).await {
+                debug!("{} is bridged", self.name_with_target_and_address());
+                return true;
+            }
+            trace!(
+                "Waiting until {} is bridged (subscribed to relayer endpoint {endpoint})",
+                self.name_with_target_and_address(),
+            );
+            match relayer::wait_bridged(endpoint, &self.meta.fqn, &addr, total_delay).await {
+                Ok(true) => {
+                    debug!("{} is bridged", self.name_with_target_and_address());
+                    return true;
+                }
+                // the notice may have been missed; check the marker file before giving up
+                Ok(false) => {
+                    let bridged = self.has_bridged_marker(&addr).await;
+                    if bridged {
+                        debug!("{} is bridged", self.name_with_target_and_address());
+                    } else {
+                        warn!(
+                            "{} not bridged after {total_delay:?}",
+                            self.name_with_target_and_address()
+                        );
+                    }
+                    return bridged;
+                }
+                Err(e) => debug!("Cannot reach relayer endpoint {endpoint} ({e}), polling instead"),
+            }
+        }
+
         trace!(
             "Waiting until {} is bridged (looking for file {})",
             self.name_with_target_and_address(),
-            path.display()
+            self.project
+                .paths
+                .notify_contract_bridged(&self.meta.fqn, &addr)
+                .display()
         );
         for delay in delays {
-            let is_file = tokio::fs::metadata(&path).await.map(|m| m.is_file());
-            if let Ok(true) = is_file {
+            if self.has_bridged_marker(&addr).await {
                 debug!("{} is bridged", self.name_with_target_and_address());
                 return true;
             }
             tokio::time::sleep(*delay).await;
         }
-        let total_delay: Duration = delays.iter().sum();
         warn!(
             "{} not bridged after {total_delay:?}",
             self.name_with_target_and_address()
@@ -194,6 +398,16 @@ impl<M: Middleware> Contract<M> {
         false
     }
 
+    /// Whether the relayer has left its marker file for this contract (deployed at `addr`),
+    /// i.e., whether it has bridged the contract.
+    async fn has_bridged_marker(&self, addr: &[u8]) -> bool {
+        let path = self
+            .project
+            .paths
+            .notify_contract_bridged(&self.meta.fqn, addr);
+        matches!(tokio::fs::metadata(&path).await, Ok(m) if m.is_file())
+    }
+
     /// Full contract name in the format of {file_name}:{contract_name}
     pub fn full_name(&self) -> String {
         format!("{}:{}", self.meta.fqn.file.display(), self.meta.fqn.name)
@@ -246,14 +460,53 @@ impl<M: Middleware> Contract<M> {
         self.address().is_some()
     }
 
+    /// Whether this contract is deployed behind an ERC-1967 proxy (see
+    /// [`ContractsConfig::upgradeable`](cubist_config::ContractsConfig::upgradeable)), in which
+    /// case its address is the address of the proxy.
+    pub fn is_upgradeable(&self) -> bool {
+        self.proxy_kind().is_some()
+    }
+
+    fn proxy_kind(&self) -> Option<ProxyKind> {
+        if self.is_shim {
+            None
+        } else {
+            self.project.proxy_kind(&self.meta.fqn.name)
+        }
+    }
+
     /// Try to find deployed address of this contract or one of its
     /// shims corresponding to the given chain target.
+    ///
+    /// If CREATE2 deployments are enabled (see [`cubist_config::Create2Config`]), the address
+    /// of a contract that is not deployed yet is predicted, as long as its constructor takes no
+    /// arguments (since CREATE2 addresses depend on constructor arguments) and it is not
+    /// upgradeable.
     pub fn try_address_on(&self, target: Target) -> Option<ContractAddress> {
-        if target == self.target() {
-            self.address()
+        let contract: &Contract<M> = if target == self.target() {
+            self
         } else {
-            self.shims.get(&target).and_then(|c| c.address())
+            self.shims.get(&target)?
+        };
+        contract.address().or_else(|| contract.predicted_address())
+    }
+
+    /// Address this contract gets when deployed via CREATE2 without constructor arguments (or,
+    /// for shims bridged by Cubist, with just their owner), if CREATE2 deployments are enabled.
+    fn predicted_address(&self) -> Option<ContractAddress> {
+        if self.is_upgradeable() {
+            return None;
         }
+        let address = match (self.is_shim, &self.project.bridge) {
+            (false, _) => self.project.create2_address(&self.meta, ()),
+            // see `shim_constructor_args`
+            (true, BridgeInfo::Cubist) => {
+                let owner = self.project.provider().default_sender()?;
+                self.project.create2_address(&self.meta, owner)
+            }
+            (true, BridgeInfo::Axelar(_)) => return None,
+        };
+        Some(address.ok()??.as_fixed_bytes().to_vec())
     }
 
     /// Same as [`Self::try_address_on`] except that it panics instead
@@ -273,8 +526,185 @@ impl<M: Middleware> Contract<M> {
 
     /// First deploy all generated shims to their chains; then deploy this contract to its target chain.
     ///
+    /// If this contract is upgradeable, deploys its implementation (constructed with `args`) and
+    /// a proxy in front of it; shims and other contracts only ever see the address of the proxy.
+    ///
     /// May be called multiple times; once deployed, subsequent calls become no-op.
+    ///
+    /// Every step is journaled (see [`DeploymentJournal`]), so if a deployment gets interrupted
+    /// (e.g., the process dies after deploying the shims), calling this again (in any process)
+    /// resumes it, reusing the shims (and contract) it already deployed.
+    ///
+    /// Before sending any transaction, checks that the sender on each chain can afford the
+    /// deployment (see [`Self::estimate_deploy`]), so that a deployment doesn't fail halfway
+    /// (leaving orphaned shims behind) because some chain runs out of funds.
     pub async fn deploy<T>(&self, args: T) -> Result<(ContractAddress, Arc<M>)>
+    where
+        T: Tokenize,
+    {
+        self.deploy_and_init(args, Bytes::default()).await
+    }
+
+    /// Estimates the cost of [`Self::deploy`] (with `args`) on each chain it sends transactions
+    /// to: deploying this contract, its shims, and the shims it calls (unless already deployed),
+    /// and granting it the CALLER role on the shims it calls.  Calls to contracts that are not
+    /// deployed yet and proxies of upgradeable contracts are accounted for with fixed allowances
+    /// (see [`CALL_GAS_ALLOWANCE`] and [`PROXY_GAS_ALLOWANCE`]).
+    ///
+    /// Only EVM contracts are estimated (Stellar contracts and shims are skipped).  To estimate
+    /// deploying several contracts that share shims, see
+    /// [`Cubist::estimate_deploy`](crate::Cubist::estimate_deploy).
+    pub async fn estimate_deploy<T>(&self, args: T) -> Result<DeploymentEstimate>
+    where
+        T: Tokenize,
+    {
+        let mut tally = GasTally::default();
+        self.tally_deploy(&mut tally, args).await?;
+        tally.price().await
+    }
+
+    /// Adds the gas used by [`Self::deploy`] (with `args`) to `tally` (see
+    /// [`Self::estimate_deploy`]), except for contracts and shims it already accounts for.
+    pub(crate) async fn tally_deploy<T>(&self, tally: &mut GasTally<M>, args: T) -> Result<()>
+    where
+        T: Tokenize,
+    {
+        if self.is_deployed() {
+            return Ok(());
+        }
+        for shim in self.shims.values().filter(|s| !s.is_deployed()) {
+            let args = self.shim_constructor_args(shim, None).await?;
+            shim.estimate_deploy_self(tally, args).await?;
+        }
+        self.estimate_deploy_self(tally, args).await?;
+        if let Some(kind) = self.proxy_kind() {
+            // transparent proxies come with a proxy admin
+            let proxies = match kind {
+                ProxyKind::Uups => 1,
+                ProxyKind::Transparent => 2,
+            };
+            for _ in 0..proxies {
+                tally.add(&self.project, PROXY_GAS_ALLOWANCE.into());
+            }
+        }
+        if !self.is_shim {
+            // see `update_shims`
+            if let BridgeInfo::Axelar(..) = self.project.bridge {
+                if let Some(rec_shim) = self.shims.get(&self.target()) {
+                    tally.add(&rec_shim.project, CALL_GAS_ALLOWANCE.into());
+                }
+            }
+            // see `make_approved_caller_for_shims`
+            for dep in &self.deps {
+                if !dep.is_deployed() {
+                    dep.estimate_deploy_self(tally, dep.shim_owner().await?)
+                        .await?;
+                }
+                tally.add(&dep.project, CALL_GAS_ALLOWANCE.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the gas used by deploying just this contract (with `args`) to `tally`, unless
+    /// `tally` already accounts for it.
+    async fn estimate_deploy_self<T>(&self, tally: &mut GasTally<M>, args: T) -> Result<()>
+    where
+        T: Tokenize,
+    {
+        if let ContractData::SorobanData { .. } = self.meta.data {
+            return Ok(());
+        }
+        if !tally.first_deploy(self.target(), &self.meta.fqn, self.is_shim) {
+            return Ok(());
+        }
+        let tx = self.project.deployment_tx(&self.meta, args).await?;
+        let gas = self
+            .project
+            .estimate_gas(&tx)
+            .await
+            .map_err(|e| CubistSdkError::EstimateError(self.target(), Box::new(e)))?;
+        tally.add(&self.project, gas);
+        Ok(())
+    }
+
+    /// Constructor arguments of `shim` (one of this contract's shims), as passed by
+    /// [`Self::deploy_shims`]: its owner (see [`Self::shim_owner`]) followed by the arguments
+    /// of its bridge.  Axelar sender shims take the address of the Axelar receiver shim
+    /// (`receiver`); if that is not known yet, the zero address stands in for it.
+    async fn shim_constructor_args(
+        &self,
+        shim: &Contract<M>,
+        receiver: Option<&ContractAddress>,
+    ) -> Result<Token> {
+        let owner = Token::Address(shim.shim_owner().await?);
+        Ok(Token::Tuple(match &shim.project.bridge {
+            BridgeInfo::Cubist => vec![owner],
+            BridgeInfo::Axelar(m) if shim.target() == self.target() => {
+                vec![owner, Token::Address(m.gateway)]
+            }
+            BridgeInfo::Axelar(m) => vec![
+                owner,
+                Token::Address(m.gateway),
+                Token::Address(m.gas_receiver),
+                Token::String(hex(receiver.map_or(&[0; 20][..], |r| &r[..]))),
+            ],
+        }))
+    }
+
+    /// Account that owns this shim once deployed: the Safe administering the shim's chain (so
+    /// that admin operations on the shim are proposed to it), if any, or else the sender on the
+    /// shim's chain.  The owner is passed to the shim's constructor (rather than being the
+    /// deployer of the shim), since the deployer may be the CREATE2 factory (see
+    /// [`cubist_config::Create2Config`]).
+    async fn shim_owner(&self) -> Result<Address> {
+        match self.project.safe() {
+            Some(safe) => Ok(safe),
+            None => self.project.sender().await,
+        }
+    }
+
+    /// Same as [`Self::deploy`] for upgradeable contracts, except that the proxy is initialized
+    /// by calling `initializer` (with `init_args`) on the implementation, since constructors of
+    /// implementations only initialize their own storage (and not the proxy's).
+    pub async fn deploy_with_initializer<T, I>(
+        &self,
+        args: T,
+        initializer: &str,
+        init_args: I,
+    ) -> Result<(ContractAddress, Arc<M>)>
+    where
+        T: Tokenize,
+        I: Tokenize,
+    {
+        let not_upgradeable =
+            || CubistSdkError::NotUpgradeable(self.meta.fqn.clone(), self.target());
+        if !self.is_upgradeable() {
+            return Err(not_upgradeable());
+        }
+        let init_data = match &self.meta.data {
+            ContractData::SolidityData { abi, .. } => abi
+                .function(initializer)
+                .and_then(|f| f.encode_input(&init_args.into_tokens()))
+                .map_err(|e| {
+                    CubistSdkError::DeployError(
+                        self.meta.fqn.clone(),
+                        self.target(),
+                        Box::new(WrapperError::ContractError(e.to_string())),
+                    )
+                })?,
+            ContractData::SorobanData { .. } => return Err(not_upgradeable()),
+        };
+        self.deploy_and_init(args, init_data.into()).await
+    }
+
+    /// Implementation of [`Self::deploy`]; `init_data` is only used by upgradeable contracts (see
+    /// [`Self::deploy_with_initializer`]).
+    async fn deploy_and_init<T>(
+        &self,
+        args: T,
+        init_data: Bytes,
+    ) -> Result<(ContractAddress, Arc<M>)>
     where
         T: Tokenize,
     {
@@ -288,68 +718,399 @@ impl<M: Middleware> Contract<M> {
 
         let client = self.project.provider();
 
-        // no-op if already deployed
+        // no-op if already deployed (unless the deployment got interrupted)
         if let Some(address) = self.address() {
-            trace!("Contract already deployed at {address:?}");
-            return Ok((address, client));
+            if !self.is_deployment_interrupted() {
+                trace!("Contract already deployed at {address:?}");
+                return Ok((address, client));
+            }
+        }
+
+        // pick up where an interrupted deployment left off (shims are deployed via their native
+        // contracts, so they're not journaled themselves)
+        let mut journal = match self.is_shim {
+            true => None,
+            false => Some(self.resume_deployment().await?),
+        };
+
+        // refuse to start if some chain can't afford the deployment
+        let args = args.into_tokens();
+        if !self.is_shim {
+            match self.estimate_deploy(Token::Tuple(args.clone())).await {
+                Ok(estimate) => estimate.check()?,
+                Err(e) => warn!(
+                    "Cannot estimate cost of deploying {}: {e}",
+                    self.full_name_with_target()
+                ),
+            }
         }
 
         // deploy shims if they're not already deployed
-        self.deploy_shims().await?;
+        self.deploy_shims_journaled(journal.as_mut()).await?;
 
-        // deploy self
-        let address = self.deploy_self(args).await?;
+        // deploy self (unless resumed after that)
+        let address = match self.address() {
+            Some(address) => address,
+            None => {
+                let args = Token::Tuple(args);
+                let address = match self.proxy_kind() {
+                    Some(kind) => self.deploy_proxied(kind, args, init_data).await?,
+                    None => self.deploy_self(args).await?,
+                };
+                if let Some(journal) = journal.as_mut() {
+                    let info = self.deployment_info().await?;
+                    let proxy = self.proxy.get().cloned();
+                    journal.update(|p| {
+                        p.deployment = Some(info);
+                        p.proxy = proxy;
+                    })?;
+                }
+                address
+            }
+        };
 
         // If this contract is not a shim, grant it the CALLER role to
         // all its shim dependencies and save the deployment
         // manifest. (If it is a shim, it got/will get deployed via
         // (one of) its native contract(s) which will have the
         // manifest that involves the shim.)
-        if !self.is_shim {
-            self.update_shims(address.clone()).await?;
-            self.make_approved_caller_for_shims().await?;
-            self.save_deployment_manifest().await?;
+        //
+        // Where a Safe administers the chain of a shim, the admin transactions are proposed to
+        // the Safe instead of being sent (see `send_admin_tx`), and the proposals are recorded
+        // in the deployment manifest.
+        if let Some(mut journal) = journal {
+            let mut proposer = SafeProposer::new(
+                self.meta.fqn.clone(),
+                journal.progress().safe_proposals.clone(),
+            );
+            if !journal.progress().shims_updated {
+                self.update_shims(address.clone(), &mut proposer).await?;
+                journal.update(|p| {
+                    p.shims_updated = true;
+                    p.safe_proposals = proposer.proposals().to_vec();
+                })?;
+            }
+            self.make_approved_caller_for_shims(&mut journal, &mut proposer)
+                .await?;
+            self.write_deployment_manifest(Some(proposer.proposals().to_vec()))
+                .await?;
+            let implementation = self.proxy.get().map(|p| p.implementation);
+            self.record_deployment(DeploymentEvent::Deployed, implementation)
+                .await?;
+            journal.clear()?;
         }
 
         Ok((address, client))
     }
 
+    /// Same as [`Self::deploy`], except that rather than sending any transactions, adds them
+    /// (unsigned) to `bundle` for signing offline (see [`TxBundle`]).  Broadcasting the signed
+    /// bundle (see [`Cubist::broadcast`](crate::Cubist::broadcast)) completes the deployment
+    /// (including saving its deployment manifest).  Returns the address the contract gets.
+    ///
+    /// Shims (of this or other contracts) created earlier in `bundle` are reused.  Only
+    /// non-upgradeable EVM contracts bridged by Cubist can be deployed offline, and interrupted
+    /// deployments (see [`Self::is_deployment_interrupted`]) must be resumed with
+    /// [`Self::deploy`] instead.
+    pub async fn deploy_unsigned<T>(
+        &self,
+        bundle: &mut TxBundle,
+        args: T,
+    ) -> Result<ContractAddress>
+    where
+        T: Tokenize,
+    {
+        if self.is_deployment_interrupted() {
+            return Err(self
+                .offline_unsupported("its deployment got interrupted and must be resumed first"));
+        }
+        if let Some(address) = self.address() {
+            trace!("Contract already deployed at {address:?}");
+            return Ok(address);
+        }
+        if self.is_shim {
+            return Err(self.offline_unsupported("shims are deployed along with their contracts"));
+        }
+        if self.is_upgradeable() {
+            return Err(self.offline_unsupported("upgradeable contracts are not supported"));
+        }
+        if let BridgeInfo::Axelar(..) = self.project.bridge {
+            // see `update_shims`
+            return Err(self.offline_unsupported("shims bridged by Axelar are not supported"));
+        }
+
+        let mut shims = vec![];
+        for shim in self.shims.values() {
+            let args = self.shim_constructor_args(shim, None).await?;
+            let address = shim.create_unsigned(bundle, args).await?;
+            shims.push(DeploymentInfo {
+                target: shim.target(),
+                address: address.as_bytes().to_vec(),
+                chain_id: Some(shim.project.chain_id().await?),
+            });
+        }
+
+        let address = self
+            .create_unsigned(bundle, Token::Tuple(args.into_tokens()))
+            .await?;
+
+        // see `make_approved_caller_for_shims`
+        for dep in &self.deps {
+            let is_deployed = dep.is_deployed();
+            let dep_owner = Token::Tuple(vec![Token::Address(dep.shim_owner().await?)]);
+            let dep_address = dep.create_unsigned(bundle, dep_owner).await?;
+            let data = dep.encode_call(APPROVE_CALLER_METHOD_NAME, address)?;
+            let mut tx: TypedTransaction =
+                TransactionRequest::new().to(dep_address).data(data).into();
+            if !is_deployed {
+                // can't estimate calls to contracts that aren't deployed yet
+                tx.set_gas(CALL_GAS_ALLOWANCE);
+            }
+            let description = format!(
+                "Approve {} as a CALLER for shim contract {}",
+                self.full_name_with_target(),
+                dep.full_name_with_target()
+            );
+            bundle.push(&dep.project, description, tx).await?;
+        }
+
+        bundle.deployments.push(DeploymentManifest {
+            contract: self.meta.fqn.clone(),
+            deployment: DeploymentInfo {
+                target: self.target(),
+                address: address.as_bytes().to_vec(),
+                chain_id: Some(self.project.chain_id().await?),
+            },
+            shims,
+            proxy: None,
+            safe_proposals: vec![],
+        });
+        Ok(address.as_bytes().to_vec())
+    }
+
+    /// Adds a transaction deploying just this contract (with `args`) to `bundle`, unless this
+    /// contract is already deployed (or created earlier in `bundle`).  Returns the address of
+    /// the contract.
+    async fn create_unsigned(&self, bundle: &mut TxBundle, args: Token) -> Result<Address> {
+        if let Some(address) = self.address() {
+            return Ok(Address::from_slice(&address));
+        }
+        if let Some(address) = bundle.created(self.target(), &self.meta.fqn) {
+            return Ok(address);
+        }
+        if let ContractData::SorobanData { .. } = self.meta.data {
+            return Err(self.offline_unsupported("only EVM contracts are supported"));
+        }
+        let tx = self.project.deployment_tx(&self.meta, args.clone()).await?;
+        let create2_address = self.project.create2_address(&self.meta, args)?;
+        let description = format!("Deploy {}", self.full_name_with_target());
+        let utx = bundle.push(&self.project, description, tx).await?;
+        let address = match create2_address {
+            Some(address) => address,
+            None => ethers::utils::get_contract_address(
+                *utx.tx.from().or_bug("Sender set by TxBundle::push"),
+                utx.nonce(),
+            ),
+        };
+        utx.contract = Some(self.meta.fqn.clone());
+        utx.creates = Some(address);
+        Ok(address)
+    }
+
+    /// Completes a deployment made through a transaction bundle (see [`Self::deploy_unsigned`])
+    /// once all of its transactions are confirmed: loads this contract and its shims from
+    /// `manifest`, saves the deployment manifest, and records the deployment.
+    pub(crate) async fn complete_unsigned_deploy(
+        &self,
+        manifest: &DeploymentManifest,
+    ) -> Result<()> {
+        for info in &manifest.shims {
+            if let Some(shim) = self.shims.get(&info.target) {
+                shim.at(&info.address).await?;
+            }
+        }
+        self.at(&manifest.deployment.address).await?;
+        self.save_deployment_manifest().await?;
+        self.record_deployment(DeploymentEvent::Deployed, None)
+            .await
+    }
+
+    /// Error saying that this contract can't be deployed offline (see
+    /// [`Self::deploy_unsigned`]) for `reason`.
+    fn offline_unsupported(&self, reason: &str) -> CubistSdkError {
+        CubistSdkError::OfflineDeployUnsupported(
+            self.meta.fqn.clone(),
+            self.target(),
+            reason.to_owned(),
+        )
+    }
+
+    /// Whether a deployment of this contract got interrupted (e.g., because the process died or
+    /// a transaction failed) before completing, in which case [`Self::deploy`] resumes it.
+    pub fn is_deployment_interrupted(&self) -> bool {
+        !self.is_shim && self.deployment_journal_path().is_file()
+    }
+
+    /// Path of the journal of deploying this contract.
+    fn deployment_journal_path(&self) -> PathBuf {
+        self.project
+            .paths
+            .for_deployment_journal(self.target(), &self.meta.fqn)
+    }
+
+    /// Opens the journal of deploying this contract and, if it belongs to an interrupted
+    /// deployment, reuses everything that deployment already deployed (rather than deploying it
+    /// again).  Journals of contracts that are no longer deployed (e.g., because they were
+    /// recorded on other chains, or on local chains that got restarted since) are discarded, so
+    /// that everything is deployed again.
+    async fn resume_deployment(&self) -> Result<DeploymentJournal> {
+        let mut journal = DeploymentJournal::open(self.deployment_journal_path())?;
+        if !journal.has_progress() {
+            return Ok(journal);
+        }
+        let progress = journal.progress().clone();
+        let resume_err = |reason: String| {
+            CubistSdkError::ResumeDeploymentError(self.meta.fqn.clone(), self.target(), reason)
+        };
+
+        for info in progress.deployments() {
+            let contract = match self.shims.get(&info.target) {
+                Some(shim) => shim.as_ref(),
+                None if info.target == self.target() => self,
+                None => {
+                    return Err(resume_err(format!(
+                        "journal {} records a deployment to {}, which this contract has no shim on",
+                        journal.path().display(),
+                        info.target
+                    )))
+                }
+            };
+            if !contract.is_still_deployed(info).await? {
+                warn!(
+                    "Discarding journal {} of {} (its contracts are no longer deployed, e.g., because it was recorded on another chain)",
+                    journal.path().display(),
+                    self.full_name_with_target()
+                );
+                journal.clear()?;
+                return Ok(journal);
+            }
+        }
+
+        debug!(
+            "Resuming deployment of {} (journaled in {})",
+            self.full_name_with_target(),
+            journal.path().display()
+        );
+        for info in &progress.shims {
+            let shim = self.shims.get(&info.target).or_bug("Checked above");
+            shim.at(&info.address).await?;
+        }
+        for dep in &progress.deps {
+            let shim = self
+                .deps
+                .iter()
+                .find(|d| {
+                    d.meta.fqn.is_same_as(&dep.contract) && d.target() == dep.deployment.target
+                })
+                .ok_or_else(|| {
+                    resume_err(format!(
+                        "journal {} records shim '{}', which this contract doesn't call",
+                        journal.path().display(),
+                        dep.contract
+                    ))
+                })?;
+            shim.at(&dep.deployment.address).await?;
+        }
+        if let Some(info) = &progress.deployment {
+            self.at(&info.address).await?;
+            if let Some(proxy) = &progress.proxy {
+                let _ = self.proxy.set(proxy.clone());
+            }
+        }
+        Ok(journal)
+    }
+
+    /// Whether `info` (a deployment of this contract) is still deployed: it was made to the chain
+    /// that this contract's target currently uses (deployments recorded without a chain id are
+    /// assumed to be), and there is code at its address.  A local chain that got restarted keeps
+    /// its chain id, but not the contracts deployed to it.
+    async fn is_still_deployed(&self, info: &DeploymentInfo) -> Result<bool> {
+        let Some(chain_id) = info.chain_id else {
+            return Ok(true);
+        };
+        if chain_id != self.project.chain_id().await? {
+            return Ok(false);
+        }
+        let address = Address::from_slice(&info.address);
+        self.project.has_code(address).await.map_err(|e| {
+            CubistSdkError::ResumeDeploymentError(
+                self.meta.fqn.clone(),
+                self.target(),
+                format!("cannot check for code at {address:?}: {e}"),
+            )
+        })
+    }
+
+    /// Records in `journal` (if any) that this shim is deployed.
+    async fn journal_shim(&self, journal: Option<&mut DeploymentJournal>) -> Result<()> {
+        if let Some(journal) = journal {
+            let info = self.deployment_info().await?;
+            if !journal.progress().shims.contains(&info) {
+                journal.update(|p| p.shims.push(info))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Deploy only this contract's shims.
     /// May be called multiple times; once deployed, subsequent calls become no-op
     /// (this is true because deploy_self has this property).
     pub async fn deploy_shims(&self) -> Result<()> {
+        self.deploy_shims_journaled(None).await
+    }
+
+    /// Implementation of [`Self::deploy_shims`], which records every deployed shim in `journal`
+    /// (if any).
+    async fn deploy_shims_journaled(
+        &self,
+        mut journal: Option<&mut DeploymentJournal>,
+    ) -> Result<()> {
         // nothing to do if no shims
         if self.shims.is_empty() {
             return Ok(());
         }
 
+        // all shims take their owner first (see `shim_constructor_args`)
         match &self.project.bridge {
             BridgeInfo::Cubist => {
-                // all shims have no-arg constructors
+                // all shims take no other arguments
                 for shim in self.shims.values() {
-                    shim.deploy_self(()).await?;
+                    shim.deploy_self(self.shim_constructor_args(shim, None).await?)
+                        .await?;
+                    shim.journal_shim(journal.as_deref_mut()).await?;
                 }
             }
-            BridgeInfo::Axelar(m) => {
+            BridgeInfo::Axelar(_) => {
                 // "axelar_receiver" shim (which is on the same chain) takes only gateway address
                 let receiver_shim = self
                     .shims
                     .get(&self.target())
                     .or_bug("Same-target shim expected for Axelar bridge");
-                let rec_addr = receiver_shim.deploy_self(m.gateway).await?;
+                let rec_addr = receiver_shim
+                    .deploy_self(self.shim_constructor_args(receiver_shim, None).await?)
+                    .await?;
+                receiver_shim.journal_shim(journal.as_deref_mut()).await?;
 
                 // "axelar_sender" shims (which are on different chains) take
                 // (gateway, gas_receiver, axelar_receiver_shim_addr)
                 for sender_shim in self.shims.values() {
                     if sender_shim.target() != self.target() {
-                        if let BridgeInfo::Axelar(m) = &sender_shim.project.bridge {
-                            sender_shim
-                                .deploy_self((
-                                    m.gateway,
-                                    m.gas_receiver,
-                                    hex(&rec_addr).to_string(),
-                                ))
+                        if let BridgeInfo::Axelar(_) = &sender_shim.project.bridge {
+                            let args = self
+                                .shim_constructor_args(sender_shim, Some(&rec_addr))
                                 .await?;
+                            sender_shim.deploy_self(args).await?;
+                            sender_shim.journal_shim(journal.as_deref_mut()).await?;
                         } else {
                             panic!("[BUG] Expected: all target projects must use the same bridge provider; actual {} uses Axelar and {} doesn't", self.target(), sender_shim.target());
                         }
@@ -380,6 +1141,83 @@ impl<M: Middleware> Contract<M> {
         Ok(self.address_unsafe())
     }
 
+    /// Deploy just self (behind a proxy of the given `kind`), ignore shims.
+    async fn deploy_proxied<T>(
+        &self,
+        kind: ProxyKind,
+        args: T,
+        init_data: Bytes,
+    ) -> Result<ContractAddress>
+    where
+        T: Tokenize,
+    {
+        self.inner
+            .get_or_try_init(|| async {
+                let (inner, proxy) = self
+                    .project
+                    .deploy_proxied(&self.meta, kind, args, init_data)
+                    .await?;
+                let _ = self.proxy.set(proxy);
+                Ok::<_, CubistSdkError>(DeployedContract::Evm { inner })
+            })
+            .await?;
+        Ok(self.address_unsafe())
+    }
+
+    /// Upgrades this (upgradeable) contract to a new implementation constructed with `args`, and
+    /// returns the address of the new implementation.
+    ///
+    /// The contract keeps its address (i.e., the address of its proxy), so its shims need no
+    /// updates.  The upgrade is refused if the storage layout of the new implementation is
+    /// incompatible with the storage layout of the current one (as recorded in the contract's
+    /// deployment manifest).
+    pub async fn upgrade<T>(&self, args: T) -> Result<ContractAddress>
+    where
+        T: Tokenize,
+    {
+        let span = span!(
+            Level::DEBUG,
+            "upgrade",
+            contract = self.meta.fqn.name,
+            target = self.target().to_string()
+        );
+        let _enter = span.enter();
+
+        if !self.is_upgradeable() {
+            return Err(CubistSdkError::NotUpgradeable(
+                self.meta.fqn.clone(),
+                self.target(),
+            ));
+        }
+        self.inner()?;
+        let path = self.deployment_manifest_path();
+        let mut manifest = DeploymentManifest::from_file(&path)
+            .map_err(|e| CubistSdkError::LoadDeploymentManifestError(path.clone(), Box::new(e)))?;
+        let current = manifest
+            .proxy
+            .as_ref()
+            .ok_or_else(|| CubistSdkError::NotUpgradeable(self.meta.fqn.clone(), self.target()))?;
+        let upgraded = self
+            .project
+            .upgrade_proxy(
+                &self.meta,
+                Address::from_slice(&self.address_unsafe()),
+                current,
+                args,
+            )
+            .await?;
+        let implementation = upgraded.implementation;
+
+        manifest.proxy = Some(upgraded);
+        manifest
+            .write_atomic(&path)
+            .map_err(|e| CubistSdkError::SaveDeploymentManifestError(path.clone(), Box::new(e)))?;
+        debug!("Saved deployment manifest to {}", path.display());
+        self.record_deployment(DeploymentEvent::Upgraded, Some(implementation))
+            .await?;
+        Ok(implementation.as_fixed_bytes().to_vec())
+    }
+
     /// Set address of this contract.  Returns an error if address is already set and is different from `addr`.
     pub async fn at(&self, addr: &ContractAddress) -> Result<()> {
         if self.inner.get().is_some() {
@@ -482,6 +1320,9 @@ impl<M: Middleware> Contract<M> {
         let _enter = span.enter();
 
         let call = self.method::<_, ()>(name, args)?;
+        if self.project.simulate() {
+            self.simulate_tx(name, &call.tx).await?;
+        }
         let receipt = self
             .project
             .send_tx(call.tx)
@@ -490,6 +1331,92 @@ impl<M: Middleware> Contract<M> {
         Ok(Some(receipt))
     }
 
+    /// Same as [`Self::send`], except that rather than sending the transaction, adds it
+    /// (unsigned) to `bundle` for signing offline (see [`TxBundle`]).  This contract may be one
+    /// created earlier in `bundle` (in which case the gas of the call can't be estimated, and
+    /// [`CALL_GAS_ALLOWANCE`] is used instead).
+    pub async fn send_unsigned<TArgs>(
+        &self,
+        bundle: &mut TxBundle,
+        name: &str,
+        args: TArgs,
+    ) -> Result<()>
+    where
+        TArgs: Tokenize,
+    {
+        let to = match self.address() {
+            Some(address) => Address::from_slice(&address),
+            None => bundle
+                .created(self.target(), &self.meta.fqn)
+                .ok_or_else(|| CubistSdkError::ContractNotDeployed {
+                    contract: self.meta.fqn.clone(),
+                    target: self.target(),
+                })?,
+        };
+        let data = self.encode_call(name, args)?;
+        let mut tx: TypedTransaction = TransactionRequest::new().to(to).data(data).into();
+        if !self.is_deployed() {
+            tx.set_gas(CALL_GAS_ALLOWANCE);
+        }
+        let description = format!("Call '{name}' on {}", self.full_name_with_target());
+        bundle.push(&self.project, description, tx).await?;
+        Ok(())
+    }
+
+    /// Encodes the calldata of calling method `name` (with `args`) on this (EVM) contract.
+    fn encode_call<TArgs>(&self, name: &str, args: TArgs) -> Result<Bytes>
+    where
+        TArgs: Tokenize,
+    {
+        let abi = match &self.meta.data {
+            ContractData::SolidityData { abi, .. } => abi,
+            ContractData::SorobanData { .. } => {
+                let e = WrapperError::ContractError("only EVM contracts are supported".to_owned());
+                return Err(self.to_call_error(name, e));
+            }
+        };
+        abi.function(name)
+            .and_then(|f| f.encode_input(&args.into_tokens()))
+            .map(Bytes::from)
+            .map_err(|e| self.to_call_error(name, WrapperError::AbiError(e)))
+    }
+
+    /// Simulate sending a transaction to this contract (see [`Self::send`]), without sending it.
+    /// Returns [`CubistSdkError::Reverted`] (with the decoded revert reason) if the transaction
+    /// would revert.
+    pub async fn simulate<TArgs>(&self, name: &str, args: TArgs) -> Result<()>
+    where
+        TArgs: Tokenize,
+    {
+        let call = self.method::<_, ()>(name, args)?;
+        self.simulate_tx(name, &call.tx).await
+    }
+
+    /// Simulates transaction `tx` (calling method `name`) and decodes why it would revert, if it
+    /// would.
+    async fn simulate_tx(&self, name: &str, tx: &TypedTransaction) -> Result<()> {
+        let revert_data = self
+            .project
+            .simulate_tx(tx)
+            .await
+            .map_err(|e| self.to_call_error(name, e))?;
+        match revert_data {
+            None => Ok(()),
+            Some(data) => {
+                let abi = match &self.meta.data {
+                    ContractData::SolidityData { abi, .. } => Some(abi),
+                    ContractData::SorobanData { .. } => None,
+                };
+                Err(CubistSdkError::Reverted {
+                    contract: self.meta.fqn.clone(),
+                    target: self.target(),
+                    method_name: name.to_string(),
+                    reason: RevertReason::decode(&data, abi),
+                })
+            }
+        }
+    }
+
     /// Send a transaction to this contract.
     pub async fn send_soroban<TArgs>(&self, name: &str, args: TArgs) -> Result<()>
     where
@@ -523,54 +1450,84 @@ impl<M: Middleware> Contract<M> {
         }
     }
 
-    /// Performs any shim updates after this (native) contract has been deployed
-    async fn update_shims(&self, address: ContractAddress) -> Result<()> {
+    /// Performs any shim updates after this (native) contract has been deployed (proposing them
+    /// through `proposer` where the Safe administering the shim's chain owns the shim).
+    async fn update_shims(
+        &self,
+        address: ContractAddress,
+        proposer: &mut SafeProposer,
+    ) -> Result<()> {
         match self.project.bridge {
             // no updates necessary
             BridgeInfo::Cubist => {}
             // only the "axelar_receiver" shim needs to be updated with the address of this contract
             BridgeInfo::Axelar(..) => {
                 if let Some(rec_shim) = self.shims.get(&self.target()) {
-                    debug!(
-                        "Updating Axelar receiver shim's target by calling {} on {}",
-                        backend::AXELAR_SET_TARGET_ADDR_METHOD_NAME,
+                    let method = backend::AXELAR_SET_TARGET_ADDR_METHOD_NAME;
+                    let description = format!(
+                        "Update Axelar receiver shim's target by calling {method} on {}",
                         rec_shim.name_with_target_and_address(),
                     );
+                    let data = rec_shim.encode_call(method, Address::from_slice(&address))?;
                     rec_shim
-                        .send(
-                            backend::AXELAR_SET_TARGET_ADDR_METHOD_NAME,
-                            Address::from_slice(&address),
-                        )
-                        .await?
-                        .ok_or(CubistSdkError::AxelarSetTargetError {
-                            receiver_contract: rec_shim.meta.fqn.clone(),
-                            target: rec_shim.target(),
-                        })?;
+                        .send_shim_owner_tx(proposer, description, method, data)
+                        .await?;
                 }
             }
         };
         Ok(())
     }
 
-    /// Grants CALLER role to this contract for all of its shim dependencies.
-    async fn make_approved_caller_for_shims(&self) -> Result<()> {
+    /// Grants CALLER role to this contract for all of its shim dependencies (skipping the ones
+    /// `journal` records as done), recording every step in `journal`.  Grants on shims owned by
+    /// the Safe administering their chain are proposed through `proposer` instead (see
+    /// [`Self::send_shim_owner_tx`]).
+    async fn make_approved_caller_for_shims(
+        &self,
+        journal: &mut DeploymentJournal,
+        proposer: &mut SafeProposer,
+    ) -> Result<()> {
         let address = self
             .address()
             .or_bug("Must be deployed before granting CALLER role");
         for dep in &self.deps {
-            dep.deploy_self(()).await?;
-            debug!(
-                "Approving {} as a CALLER for shim contract {}",
+            let approved = journal
+                .progress()
+                .dep(&dep.meta.fqn, dep.target())
+                .map(|d| d.approved);
+            if approved == Some(true) {
+                continue;
+            }
+            dep.deploy_self(dep.shim_owner().await?).await?;
+            if approved.is_none() {
+                let deployment = dep.deployment_info().await?;
+                let contract = dep.meta.fqn.clone();
+                journal.update(|p| {
+                    p.deps.push(JournaledDep {
+                        contract,
+                        deployment,
+                        approved: false,
+                    })
+                })?;
+            }
+            let description = format!(
+                "Approve {} as a CALLER for shim contract {}",
                 self.name_with_target_and_address(),
                 dep.name_with_target_and_address()
             );
-            dep.send(APPROVE_CALLER_METHOD_NAME, Address::from_slice(&address))
-                .await?
-                .ok_or(CubistSdkError::ApproveCallerError {
-                    caller_contract: self.meta.fqn.clone(),
-                    shim_contract: dep.meta.fqn.clone(),
-                    target: self.target(),
-                })?;
+            let data =
+                dep.encode_call(APPROVE_CALLER_METHOD_NAME, Address::from_slice(&address))?;
+            dep.send_shim_owner_tx(proposer, description, APPROVE_CALLER_METHOD_NAME, data)
+                .await?;
+            journal.update(|p| {
+                p.deps
+                    .iter_mut()
+                    .filter(|d| {
+                        d.contract.is_same_as(&dep.meta.fqn) && d.deployment.target == dep.target()
+                    })
+                    .for_each(|d| d.approved = true);
+                p.safe_proposals = proposer.proposals().to_vec();
+            })?;
         }
         Ok(())
     }
@@ -580,21 +1537,38 @@ impl<M: Middleware> Contract<M> {
     /// NOTE: that this overwrites any previous deployment manifest for this contract.
     /// TODO: consider changing this to instead fail
     pub async fn save_deployment_manifest(&self) -> Result<()> {
-        let addr = self.address_unsafe();
-        let to_info = |c: &Self| DeploymentInfo {
-            target: c.target(),
-            address: c.address_unsafe(),
-        };
+        self.write_deployment_manifest(None).await
+    }
+
+    /// Implementation of [`Self::save_deployment_manifest`], which records `safe_proposals` as
+    /// the pending Safe proposals (or, if [`None`], keeps the ones recorded for the same
+    /// deployment).
+    async fn write_deployment_manifest(
+        &self,
+        safe_proposals: Option<Vec<SafeProposal>>,
+    ) -> Result<()> {
+        let path = self.deployment_manifest_path();
+        let previous = DeploymentManifest::from_file(&path).ok();
+        let deployment = self.deployment_info().await?;
+        let safe_proposals = safe_proposals.unwrap_or_else(|| {
+            previous
+                .as_ref()
+                .filter(|m| m.deployment == deployment)
+                .map(|m| m.safe_proposals.clone())
+                .unwrap_or_default()
+        });
         let manifest = DeploymentManifest {
             contract: self.meta.fqn.clone(),
-            deployment: to_info(self),
-            shims: self.shims.values().map(Arc::as_ref).map(to_info).collect(),
+            deployment,
+            shims: self.shim_deployment_infos().await?,
+            // keep the proxy info of a contract that was loaded (rather than deployed)
+            proxy: self
+                .proxy
+                .get()
+                .cloned()
+                .or_else(|| previous.and_then(|m| m.proxy)),
+            safe_proposals,
         };
-        let path = self
-            .project
-            .paths
-            .for_deployment_manifest(&self.meta.fqn, &addr);
-
         manifest
             .write_atomic(&path)
             .map_err(|e| CubistSdkError::SaveDeploymentManifestError(path.clone(), Box::new(e)))?;
@@ -603,6 +1577,217 @@ impl<M: Middleware> Contract<M> {
         Ok(())
     }
 
+    /// Admin transactions concerning this contract that are proposed to Safes and pending
+    /// execution, as recorded in its deployment manifest (see
+    /// [`CommonConfig::safe`](cubist_config::network::CommonConfig::safe)).
+    pub fn pending_safe_proposals(&self) -> Vec<SafeProposal> {
+        DeploymentManifest::from_file(&self.deployment_manifest_path())
+            .map(|m| m.safe_proposals)
+            .unwrap_or_default()
+    }
+
+    /// Records in this contract's deployment manifest that the pending Safe proposals (see
+    /// [`Self::pending_safe_proposals`]) have been executed (or abandoned).
+    pub async fn clear_safe_proposals(&self) -> Result<()> {
+        self.write_deployment_manifest(Some(vec![])).await
+    }
+
+    /// Transfers ownership of the proxy admin of this (upgradeable) contract to `new_owner`,
+    /// or, if a Safe administers this contract's chain, proposes the transfer to the Safe
+    /// (recording the proposal in the deployment manifest).  Only contracts deployed behind
+    /// transparent proxies have proxy admins.
+    pub async fn transfer_admin_ownership(&self, new_owner: Address) -> Result<AdminTx> {
+        self.inner()?;
+        let path = self.deployment_manifest_path();
+        let manifest = DeploymentManifest::from_file(&path)
+            .map_err(|e| CubistSdkError::LoadDeploymentManifestError(path.clone(), Box::new(e)))?;
+        let admin = manifest
+            .proxy
+            .as_ref()
+            .and_then(|p| p.admin)
+            .ok_or_else(|| CubistSdkError::NotUpgradeable(self.meta.fqn.clone(), self.target()))?;
+
+        let method = PROXY_ADMIN_TRANSFER_OWNERSHIP_METHOD_NAME;
+        let admin_info = self.project.proxy_contract(PROXY_ADMIN_CONTRACT)?;
+        let data = match &admin_info.data {
+            ContractData::SolidityData { abi, .. } => abi
+                .function(method)
+                .and_then(|f| f.encode_input(&[Token::Address(new_owner)]))
+                .map_err(|e| self.to_call_error(method, WrapperError::AbiError(e)))?,
+            ContractData::SorobanData { .. } => {
+                return Err(CubistSdkError::NotUpgradeable(
+                    self.meta.fqn.clone(),
+                    self.target(),
+                ))
+            }
+        };
+        let description = format!(
+            "Transfer ownership of the proxy admin of {} to {new_owner:?}",
+            self.name_with_target_and_address()
+        );
+        let mut proposer = SafeProposer::new(self.meta.fqn.clone(), manifest.safe_proposals);
+        let outcome = self
+            .send_admin_tx(&mut proposer, description, admin, method, data.into())
+            .await?;
+        if let AdminTx::Proposed { .. } = outcome {
+            self.write_deployment_manifest(Some(proposer.proposals().to_vec()))
+                .await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Current role holders (owner, pending owner, approved callers, and guardians) of all shims
+    /// of this (deployed) contract, ordered by chain, and whether they are paused.
+    pub async fn shim_roles(&self) -> Result<Vec<ShimRoles>> {
+        self.inner()?;
+        let mut roles = vec![];
+        for shim in self.sorted_shims() {
+            let owner = shim.call(SHIM_OWNER_METHOD_NAME, ()).await?;
+            let pending_owner: Address = shim.call(PENDING_SHIM_OWNER_METHOD_NAME, ()).await?;
+            roles.push(ShimRoles {
+                target: shim.target(),
+                address: Address::from_slice(&shim.address_unsafe()),
+                owner,
+                pending_owner: (!pending_owner.is_zero()).then_some(pending_owner),
+                callers: shim.call(APPROVED_CALLERS_METHOD_NAME, ()).await?,
+                guardians: shim.call(SHIM_GUARDIANS_METHOD_NAME, ()).await?,
+                paused: shim.is_shim_paused().await?,
+            });
+        }
+        Ok(roles)
+    }
+
+    /// Whether this (deployed) shim is paused (see [`Self::pause_shims`]).
+    pub async fn is_shim_paused(&self) -> Result<bool> {
+        self.call(SHIM_PAUSED_METHOD_NAME, ()).await
+    }
+
+    /// Shims of this contract, ordered by chain.
+    fn sorted_shims(&self) -> Vec<&Arc<Contract<M>>> {
+        let mut shims: Vec<_> = self.shims.values().collect();
+        shims.sort_by_key(|shim| shim.target().to_string());
+        shims
+    }
+
+    /// Sends an admin transaction (calling `method` on the contract at `to` with calldata
+    /// `data`, as described by `description`) to this contract's chain, or, if a Safe
+    /// administers that chain (see
+    /// [`CommonConfig::safe`](cubist_config::network::CommonConfig::safe)), proposes it to the
+    /// Safe through `proposer`.
+    async fn send_admin_tx(
+        &self,
+        proposer: &mut SafeProposer,
+        description: String,
+        to: Address,
+        method: &str,
+        data: Bytes,
+    ) -> Result<AdminTx> {
+        let safe = self.project.safe();
+        self.send_admin_tx_as(proposer, safe, description, to, method, data)
+            .await
+    }
+
+    /// Sends an admin transaction calling `method` (with calldata `data`, as described by
+    /// `description`) on this (deployed) shim, which only its owner may send.  The transaction
+    /// is proposed (through `proposer`) to the Safe administering the shim's chain if that Safe
+    /// owns the shim; otherwise (e.g., for shims owned by the sender, see [`Self::shim_owner`])
+    /// it is sent from the sender.
+    async fn send_shim_owner_tx(
+        &self,
+        proposer: &mut SafeProposer,
+        description: String,
+        method: &str,
+        data: Bytes,
+    ) -> Result<AdminTx> {
+        let owner: Address = self.call(SHIM_OWNER_METHOD_NAME, ()).await?;
+        let safe = admin_safe(self.project.safe(), owner);
+        let to = Address::from_slice(&self.address_unsafe());
+        self.send_admin_tx_as(proposer, safe, description, to, method, data)
+            .await
+    }
+
+    /// Same as [`Self::send_admin_tx`], except that the transaction is proposed to `safe` (if
+    /// any) regardless of whether a Safe administers this contract's chain.
+    async fn send_admin_tx_as(
+        &self,
+        proposer: &mut SafeProposer,
+        safe: Option<Address>,
+        description: String,
+        to: Address,
+        method: &str,
+        data: Bytes,
+    ) -> Result<AdminTx> {
+        if let Some(safe) = safe {
+            debug!("Proposing to Safe {safe:?}: {description}");
+            let batch = proposer
+                .propose(&self.project, safe, description, to, data)
+                .await?;
+            return Ok(AdminTx::Proposed { safe, batch });
+        }
+        debug!("{description}");
+        let tx: TypedTransaction = TransactionRequest::new().to(to).data(data).into();
+        if self.project.simulate() {
+            self.simulate_tx(method, &tx).await?;
+        }
+        let receipt = self
+            .project
+            .send_tx(tx)
+            .await
+            .map_err(|e| self.to_call_error(method, e))?;
+        Ok(AdminTx::Sent(Box::new(receipt)))
+    }
+
+    /// Appends a record of this (deployed) contract and its shims to the deployment history of
+    /// the current network profile.
+    async fn record_deployment(
+        &self,
+        event: DeploymentEvent,
+        implementation: Option<Address>,
+    ) -> Result<()> {
+        let record = DeploymentRecord::now(
+            event,
+            self.meta.fqn.clone(),
+            self.deployment_info().await?,
+            self.shim_deployment_infos().await?,
+            implementation,
+        );
+        let history = DeploymentHistory::new(self.project.paths.deployment_history());
+        history.append(&record).map_err(|e| {
+            CubistSdkError::SaveDeploymentHistoryError(history.path().to_path_buf(), Box::new(e))
+        })?;
+        debug!("Recorded {event:?} in {}", history.path().display());
+        Ok(())
+    }
+
+    /// Deployment info (target, address, and chain id) of this (deployed) contract.
+    async fn deployment_info(&self) -> Result<DeploymentInfo> {
+        let chain_id = match self.target() {
+            Target::Stellar => None,
+            _ => Some(self.project.chain_id().await?),
+        };
+        Ok(DeploymentInfo {
+            target: self.target(),
+            address: self.address_unsafe(),
+            chain_id,
+        })
+    }
+
+    /// Deployment infos of all shims of this (deployed) contract.
+    async fn shim_deployment_infos(&self) -> Result<Vec<DeploymentInfo>> {
+        let mut infos = vec![];
+        for shim in self.shims.values() {
+            infos.push(shim.deployment_info().await?);
+        }
+        Ok(infos)
+    }
+
+    /// Path of the deployment manifest of this (deployed) contract.
+    fn deployment_manifest_path(&self) -> PathBuf {
+        self.project
+            .paths
+            .for_deployment_manifest(&self.meta.fqn, &self.address_unsafe())
+    }
+
     /// Return [`CubistSdkError`] to describe an error raised during calling a contract method.
     fn to_call_error(&self, method_name: &str, source: WrapperError) -> CubistSdkError {
         CubistSdkError::CallError {
@@ -644,3 +1829,349 @@ impl<M: Middleware> Contract<M> {
         }
     }
 }
+
+/// Outcomes of admin transactions that must all be sent (or proposed), or else the first error.
+fn all_sent(outcomes: Vec<(Target, Result<AdminTx>)>) -> Result<Vec<(Target, AdminTx)>> {
+    outcomes
+        .into_iter()
+        .map(|(target, outcome)| Ok((target, outcome?)))
+        .collect()
+}
+
+impl<M: WithSender> Contract<M> {
+    /// Starts transferring ownership of all shims of this (deployed) contract to `new_owner`.
+    /// The transfer completes once `new_owner` accepts it (see [`Self::accept_shim_ownership`]);
+    /// until then, the current owners keep administering the shims.
+    ///
+    /// Like all admin transactions on shims, the transfer is sent from the current owner of each
+    /// shim, or, if that is the Safe administering the shim's chain (see
+    /// [`CommonConfig::safe`](cubist_config::network::CommonConfig::safe)), proposed to the Safe
+    /// (and recorded in the deployment manifest).
+    pub async fn transfer_shim_ownership(
+        &self,
+        new_owner: Address,
+    ) -> Result<Vec<(Target, AdminTx)>> {
+        self.inner()?;
+        let outcomes = self
+            .send_to_shims(
+                &self.sorted_shims(),
+                TRANSFER_SHIM_OWNERSHIP_METHOD_NAME,
+                &[Token::Address(new_owner)],
+                &format!("Transfer ownership to {new_owner:?}"),
+                ShimAdmin::Owner,
+            )
+            .await?;
+        all_sent(outcomes)
+    }
+
+    /// Accepts the pending transfer of ownership (see [`Self::transfer_shim_ownership`]) of all
+    /// shims of this (deployed) contract, completing the transfer.  The acceptance is sent from
+    /// the new owner of each shim (or proposed to the Safe, if that is the new owner).
+    pub async fn accept_shim_ownership(&self) -> Result<Vec<(Target, AdminTx)>> {
+        self.inner()?;
+        let outcomes = self
+            .send_to_shims(
+                &self.sorted_shims(),
+                ACCEPT_SHIM_OWNERSHIP_METHOD_NAME,
+                &[],
+                "Accept ownership",
+                ShimAdmin::PendingOwner,
+            )
+            .await?;
+        all_sent(outcomes)
+    }
+
+    /// Revokes the CALLER role of `caller` on the shim of this (deployed) contract on chain
+    /// `target` (e.g., a contract that no longer needs to call this one), from the owner of the
+    /// shim (or proposed to the Safe, if that is the owner).
+    pub async fn revoke_caller(&self, target: Target, caller: Address) -> Result<AdminTx> {
+        self.inner()?;
+        let shim = self
+            .shims
+            .get(&target)
+            .ok_or_else(|| CubistSdkError::MissingShim(self.meta.fqn.clone(), target))?;
+        let mut outcomes = self
+            .send_to_shims(
+                &[shim],
+                REVOKE_CALLER_METHOD_NAME,
+                &[Token::Address(caller)],
+                &format!("Revoke the CALLER role of {caller:?}"),
+                ShimAdmin::Owner,
+            )
+            .await?;
+        outcomes.pop().or_bug("One outcome per shim").1
+    }
+
+    /// Grants (if `enabled`) or revokes the GUARDIAN role of `guardian` on all shims of this
+    /// (deployed) contract.  Guardians can pause and unpause the shims (see
+    /// [`Self::pause_shims`]) without being able to administer them otherwise.
+    pub async fn set_shim_guardian(
+        &self,
+        guardian: Address,
+        enabled: bool,
+    ) -> Result<Vec<(Target, AdminTx)>> {
+        self.inner()?;
+        let action = match enabled {
+            true => format!("Grant the GUARDIAN role to {guardian:?}"),
+            false => format!("Revoke the GUARDIAN role of {guardian:?}"),
+        };
+        let outcomes = self
+            .send_to_shims(
+                &self.sorted_shims(),
+                SET_SHIM_GUARDIAN_METHOD_NAME,
+                &[Token::Address(guardian), Token::Bool(enabled)],
+                &action,
+                ShimAdmin::Owner,
+            )
+            .await?;
+        all_sent(outcomes)
+    }
+
+    /// Pauses all shims of this (deployed) contract (e.g., when an exploit is found), which
+    /// stops all cross-chain calls to this contract: paused shims reject calls, and the relayer
+    /// skips the events of paused shims.  Each shim is paused from the sender of its chain if
+    /// that is a guardian of the shim, or else from (or, if that is the Safe administering the
+    /// chain, proposed to) its owner.
+    ///
+    /// Pausing on one chain does not depend on pausing on the others, so this tries every chain
+    /// and returns the outcome on each.
+    pub async fn pause_shims(&self) -> Result<Vec<(Target, Result<AdminTx>)>> {
+        self.inner()?;
+        self.send_to_shims(
+            &self.sorted_shims(),
+            PAUSE_SHIM_METHOD_NAME,
+            &[],
+            "Pause",
+            ShimAdmin::Guardian,
+        )
+        .await
+    }
+
+    /// Unpauses all shims of this (deployed) contract (see [`Self::pause_shims`]), returning the
+    /// outcome on each chain.
+    pub async fn unpause_shims(&self) -> Result<Vec<(Target, Result<AdminTx>)>> {
+        self.inner()?;
+        self.send_to_shims(
+            &self.sorted_shims(),
+            UNPAUSE_SHIM_METHOD_NAME,
+            &[],
+            "Unpause",
+            ShimAdmin::Guardian,
+        )
+        .await
+    }
+
+    /// Sends an admin transaction calling `method` (with `args`, to do `action`) on each of
+    /// `shims` from the account administering the shim (see [`ShimAdmin`]).  If that is the
+    /// Safe administering the shim's chain, the transaction is proposed to the Safe instead.
+    ///
+    /// Returns the outcome on each shim's chain (a failure on one chain does not prevent
+    /// sending to the others).  Proposals are recorded in the deployment manifest.
+    async fn send_to_shims(
+        &self,
+        shims: &[&Arc<Contract<M>>],
+        method: &str,
+        args: &[Token],
+        action: &str,
+        admin: ShimAdmin,
+    ) -> Result<Vec<(Target, Result<AdminTx>)>> {
+        let mut proposer = SafeProposer::new(self.meta.fqn.clone(), self.pending_safe_proposals());
+        let mut outcomes = vec![];
+        for shim in shims {
+            let outcome = shim
+                .send_shim_admin_tx(&mut proposer, method, args, action, admin)
+                .await;
+            outcomes.push((shim.target(), outcome));
+        }
+        if outcomes
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, Ok(AdminTx::Proposed { .. })))
+        {
+            self.write_deployment_manifest(Some(proposer.proposals().to_vec()))
+                .await?;
+        }
+        Ok(outcomes)
+    }
+
+    /// Sends (or proposes, see [`Self::send_to_shims`]) an admin transaction calling `method`
+    /// (with `args`, to do `action`) on this (deployed) shim.
+    async fn send_shim_admin_tx(
+        &self,
+        proposer: &mut SafeProposer,
+        method: &str,
+        args: &[Token],
+        action: &str,
+        admin: ShimAdmin,
+    ) -> Result<AdminTx> {
+        let owner = self.call(SHIM_OWNER_METHOD_NAME, ()).await?;
+        let pending_owner = self.call(PENDING_SHIM_OWNER_METHOD_NAME, ()).await?;
+        let guardians: Vec<Address> = self.call(SHIM_GUARDIANS_METHOD_NAME, ()).await?;
+        let sender = self.project.sender().await.ok();
+        let admin = admin
+            .resolve(owner, pending_owner, &guardians, sender)
+            .map_err(|reason| {
+                let e = WrapperError::ContractError(reason.to_owned());
+                self.to_call_error(method, e)
+            })?;
+        let safe = admin_safe(self.project.safe(), admin);
+        let description = format!("{action} on shim {}", self.name_with_target_and_address());
+        let to = Address::from_slice(&self.address_unsafe());
+        let data = self.encode_call(method, args)?;
+        self.as_signer(admin)
+            .send_admin_tx_as(proposer, safe, description, to, method, data)
+            .await
+    }
+}
+
+impl<M: Middleware + 'static> Contract<M> {
+    /// Streams the events named `name` emitted by this (deployed) contract, starting with block
+    /// (or, on Stellar, ledger) `from_block` (to backfill past events) or else with the next one.
+    ///
+    /// Errors while polling the target chain are yielded by the stream (which keeps polling).
+    pub fn events(&self, name: &str, from_block: Option<u64>) -> Result<EventStream> {
+        self.event_stream(&[name.to_owned()], from_block)
+    }
+
+    /// Streams the events emitted by this (deployed) EVM contract, decoded into `T` (e.g., the
+    /// events enum that `abigen` generates for this contract), starting with block `from_block`
+    /// (to backfill past events) or else with the next one.
+    pub fn typed_events<T>(&self, from_block: Option<u64>) -> Result<EventStream<T>>
+    where
+        T: EthLogDecode + Send + 'static,
+    {
+        let fqn = self.meta.fqn.clone();
+        let target = self.target();
+        let (inner, abi) = match (self.inner()?, &self.meta.data) {
+            (DeployedContract::Evm { inner }, ContractData::SolidityData { abi, .. }) => {
+                (inner, abi)
+            }
+            _ => {
+                let e = WrapperError::ContractError(
+                    "typed events are only supported on EVM chains".to_owned(),
+                );
+                return Err(CubistSdkError::EventsError(fqn, target, Box::new(e)));
+            }
+        };
+        let events: Vec<_> = abi.events().cloned().collect();
+        if events.is_empty() {
+            return Ok(Box::pin(futures::stream::empty()));
+        }
+        let filter = evm_filter(inner.address(), &events);
+        let stream = evm_events(target, self.project.provider(), filter, from_block, |log| {
+            T::decode_log(&RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            })
+            .ok()
+        });
+        Ok(Box::pin(stream.map(move |event| {
+            event.map_err(|e| CubistSdkError::EventsError(fqn.clone(), target, Box::new(e)))
+        })))
+    }
+
+    /// Streams the events of this (deployed) contract whose name is one of `names` (or all of
+    /// its events, if `names` is empty).
+    pub(crate) fn event_stream(
+        &self,
+        names: &[String],
+        from_block: Option<u64>,
+    ) -> Result<EventStream> {
+        let fqn = self.meta.fqn.clone();
+        let target = self.target();
+        let to_events_error =
+            move |e: WrapperError| CubistSdkError::EventsError(fqn.clone(), target, Box::new(e));
+        match (self.inner()?, &self.meta.data) {
+            (DeployedContract::Evm { inner }, ContractData::SolidityData { abi, .. }) => {
+                let mut events = vec![];
+                for name in names {
+                    let declared = abi.events_by_name(name).map_err(|_| {
+                        CubistSdkError::UnknownEvent(name.clone(), vec![self.full_name()])
+                    })?;
+                    events.extend(declared.iter().cloned());
+                }
+                if names.is_empty() {
+                    events.extend(abi.events().cloned());
+                }
+                if events.is_empty() {
+                    return Ok(Box::pin(futures::stream::empty()));
+                }
+                let filter = evm_filter(inner.address(), &events);
+                let stream = evm_events(
+                    target,
+                    self.project.provider(),
+                    filter,
+                    from_block,
+                    move |log| decode_evm_event(&events, log),
+                );
+                Ok(Box::pin(stream.map(move |e| e.map_err(&to_events_error))))
+            }
+            (DeployedContract::Stellar { address }, _) => {
+                let rpc_url = self
+                    .project
+                    .endpoint_url()?
+                    .expose_url()?
+                    .join("soroban/rpc")
+                    .map_err(|e| to_events_error(WrapperError::UrlError(e)))?;
+                let stream =
+                    stellar_events(target, rpc_url, address.clone(), names.to_vec(), from_block);
+                Ok(Box::pin(stream.map(move |e| e.map_err(&to_events_error))))
+            }
+            (DeployedContract::Evm { .. }, ContractData::SorobanData { .. }) => {
+                panic!(
+                    "[BUG] Soroban contract {} deployed to an EVM chain",
+                    self.meta.fqn
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shim_admin_resolve() {
+        let owner = Address::repeat_byte(1);
+        let pending_owner = Address::repeat_byte(2);
+        let guardian = Address::repeat_byte(3);
+        let sender = Address::repeat_byte(4);
+        let none = Address::zero();
+
+        assert_eq!(
+            Ok(owner),
+            ShimAdmin::Owner.resolve(owner, pending_owner, &[guardian], Some(guardian))
+        );
+        assert_eq!(
+            Ok(pending_owner),
+            ShimAdmin::PendingOwner.resolve(owner, pending_owner, &[], Some(owner))
+        );
+        // guardians pause from the sender if it is one of them, and otherwise the owner does
+        assert_eq!(
+            Ok(guardian),
+            ShimAdmin::Guardian.resolve(owner, none, &[guardian], Some(guardian))
+        );
+        assert_eq!(
+            Ok(owner),
+            ShimAdmin::Guardian.resolve(owner, none, &[guardian], Some(sender))
+        );
+        assert_eq!(
+            Ok(owner),
+            ShimAdmin::Guardian.resolve(owner, none, &[guardian], None)
+        );
+
+        // the error depends on which admin is missing
+        assert_eq!(
+            Err("no pending ownership transfer"),
+            ShimAdmin::PendingOwner.resolve(owner, none, &[], Some(owner))
+        );
+        assert_eq!(
+            Err("shim has no owner"),
+            ShimAdmin::Owner.resolve(none, pending_owner, &[], Some(owner))
+        );
+        assert_eq!(
+            Err("sender is not a guardian and shim has no owner"),
+            ShimAdmin::Guardian.resolve(none, none, &[guardian], Some(sender))
+        );
+    }
+}
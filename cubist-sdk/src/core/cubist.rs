@@ -1,14 +1,23 @@
 use std::{collections::HashMap, iter::repeat, sync::Arc, time::Duration};
 
 use cubist_config::{paths::ContractFQN, util::OrBug, Config, Target};
+use ethers::abi::Token;
 use ethers::providers::Middleware;
-use ethers::{prelude::Address, types::U256};
+use ethers::{
+    prelude::Address,
+    types::{TransactionReceipt, U256, U64},
+};
 use futures::future::JoinAll;
 use std::process::Command;
+use tracing::debug;
 
-use crate::{core::ContractAddress, gen::backend::Backend, CubistSdkError, Result};
+use crate::{core::ContractAddress, gen::backend::Backend, CubistSdkError, Result, WrapperError};
 
-use super::{Contract, ContractInfo, HttpStack, TargetProject, TargetProjectInfo, WsStack};
+use super::{
+    Batch, BundledTx, Contract, ContractData, ContractInfo, DeploymentEstimate, EventFilter,
+    EventStream, GasTally, HttpStack, Snapshot, TargetProject, TargetProjectInfo, TxBundle,
+    WithSender, WsStack,
+};
 
 type Map<K, V> = HashMap<K, V>;
 
@@ -136,6 +145,67 @@ impl Cubist<WsStack> {
     }
 }
 
+impl<M: WithSender> Cubist<M> {
+    /// Returns a [`Cubist`] instance that sends all transactions on `target` from `sender` (all
+    /// other targets are unaffected).
+    ///
+    /// Unlike [`Clone::clone`], the returned instance shares contracts with `self`: contracts
+    /// that are already deployed (or loaded from their deployment receipts) don't have to be
+    /// deployed (or loaded) again; see [`Contract::as_signer`].
+    pub fn with_sender(&self, target: Target, sender: Address) -> Result<Self> {
+        let project = self
+            .projects
+            .get(&target)
+            .ok_or(CubistSdkError::MissingTarget(target))?;
+        let project = Arc::new(project.with_sender(sender));
+
+        // Shims on `target` send from `sender`; all other shims are shared with `self`
+        let mut shims = self.shims.clone();
+        if let Some(target_shims) = shims.get_mut(&target) {
+            *target_shims = target_shims
+                .iter()
+                .map(|shim| Arc::new(shim.with_project(Arc::clone(&project))))
+                .collect();
+        }
+
+        // Rebuild all contracts from the new shims (so that a contract's shims and dependencies
+        // are the very instances returned by `shims`), sending from `sender` on `target`
+        let new_shim = |old: &Arc<Contract<M>>| {
+            shims
+                .get(&old.target())
+                .and_then(|shims| shims.iter().find(|shim| shim.meta.fqn == old.meta.fqn))
+                .map_or_else(|| Arc::clone(old), Arc::clone)
+        };
+        let contracts = self
+            .contracts
+            .iter()
+            .map(|(t, contracts)| {
+                let contracts = contracts
+                    .iter()
+                    .map(|c| {
+                        let project = match *t == target {
+                            true => Arc::clone(&project),
+                            false => Arc::clone(&c.project),
+                        };
+                        let c_shims = c.shims.iter().map(|(t, s)| (*t, new_shim(s))).collect();
+                        let deps = c.deps.iter().map(new_shim).collect();
+                        Arc::new(c.rebuild(project, c_shims, deps))
+                    })
+                    .collect::<Vec<_>>();
+                (*t, contracts)
+            })
+            .collect::<Map<_, _>>();
+        let mut projects = self.projects.clone();
+        projects.insert(target, project);
+        Ok(Cubist {
+            projects,
+            contracts,
+            shims,
+            config: self.config.clone(),
+        })
+    }
+}
+
 impl<M: Middleware> Cubist<M> {
     /// Create a new [`Cubist`] instance from a set of per-target-chain projects
     pub fn new_from_projects(
@@ -198,7 +268,7 @@ impl<M: Middleware> Cubist<M> {
     /// Returns a future that completes once all initialized contracts in
     /// this project have been bridged (in which case the result is
     /// `true`) or when the timeout (of 10s by default) expires (in
-    /// which case the result is `false`).
+    /// which case the result is `false`).  See [`Contract::when_bridged`].
     ///
     /// # Arguments
     /// * `delays` - how long to wait between retries (defaults to 100ms for 100 times)
@@ -216,6 +286,84 @@ impl<M: Middleware> Cubist<M> {
             .all(|b| *b)
     }
 
+    /// Returns a new (empty) batch of calls, to read many values (or send many transactions)
+    /// with a single call (or transaction) per target (see [`Batch`]).
+    pub fn batch(&self) -> Batch<M> {
+        Batch::new()
+    }
+
+    /// Snapshots the state of all target chains and of the deploy directory of the current
+    /// network profile (i.e., deployment manifests, receipts, and history), e.g., to reset them
+    /// between tests (see [`Snapshot::revert`]).
+    ///
+    /// Chains that can't be snapshot (e.g., Avalanche, which doesn't support `evm_snapshot`) are
+    /// not reverted; they are reported by [`Snapshot::unsupported`].
+    pub async fn snapshot(&self) -> Result<Snapshot<M>> {
+        let mut projects: Vec<_> = self.projects().collect();
+        projects.sort_by_key(|p| p.target.to_string());
+        Snapshot::take(projects, &self.config.paths().deploy_dir).await
+    }
+
+    /// Moves the clocks of all target chains forward by `duration` (in whole seconds) and mines a
+    /// block on each, so that contracts on all chains observe the new time.
+    ///
+    /// Fails without touching any chain if some chain doesn't support manipulating time (see
+    /// [`TargetProject::increase_time`]).
+    pub async fn advance_time(&self, duration: Duration) -> Result<()> {
+        let projects = self.time_controlled_projects().await?;
+        for project in &projects {
+            project.increase_time(duration.as_secs()).await?;
+        }
+        for project in &projects {
+            project.mine_blocks(1).await?;
+        }
+        Ok(())
+    }
+
+    /// Sets the timestamp of the next block on all target chains.
+    ///
+    /// Fails without touching any chain if some chain doesn't support manipulating time (see
+    /// [`TargetProject::set_next_block_timestamp`]).
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<()> {
+        for project in self.time_controlled_projects().await? {
+            project.set_next_block_timestamp(timestamp).await?;
+        }
+        Ok(())
+    }
+
+    /// Mines `blocks` (empty) blocks on `target` (see [`TargetProject::mine_blocks`]).
+    pub async fn mine_blocks(&self, target: Target, blocks: u64) -> Result<()> {
+        let project = self
+            .project(target)
+            .ok_or(CubistSdkError::ProjectError(target))?;
+        project.mine_blocks(blocks).await
+    }
+
+    /// Returns all projects (sorted by target name), after checking that the time of all of their
+    /// chains can be manipulated.
+    async fn time_controlled_projects(&self) -> Result<Vec<Arc<TargetProject<M>>>> {
+        let mut projects: Vec<_> = self.projects().collect();
+        projects.sort_by_key(|p| p.target.to_string());
+        for project in &projects {
+            project.check_time_control().await?;
+        }
+        Ok(projects)
+    }
+
+    /// Estimates the cost of deploying `contracts` (each with its constructor arguments) on each
+    /// chain, like [`Contract::estimate_deploy`] but accounting for contracts and shims shared
+    /// by several of them (e.g., the shims of a contract that others call) only once.
+    pub async fn estimate_deploy(
+        &self,
+        contracts: impl IntoIterator<Item = (Arc<Contract<M>>, Token)>,
+    ) -> Result<DeploymentEstimate> {
+        let mut tally = GasTally::default();
+        for (contract, args) in contracts {
+            contract.tally_deploy(&mut tally, args).await?;
+        }
+        tally.price().await
+    }
+
     /// Find a (non-shim) contract by its name.
     pub fn contract(&self, name: &str) -> Option<Arc<Contract<M>>> {
         for tc in self.contracts.values() {
@@ -274,6 +422,60 @@ impl<M: Middleware> Cubist<M> {
         &self.config
     }
 
+    /// Broadcasts the signed transactions of `bundle` (see [`TxBundle`]) in order, waiting for
+    /// each to be confirmed, and then completes the deployments made through `bundle` (see
+    /// [`Contract::deploy_unsigned`]), i.e., saves their receipts and deployment manifests.
+    /// Transactions that were broadcast before (e.g., by an interrupted broadcast) are not sent
+    /// again.  Returns the receipts of all transactions.
+    ///
+    /// Nothing is sent unless all transactions are properly signed (see
+    /// [`BundledTx::checked_signed`]).
+    pub async fn broadcast(&self, bundle: &TxBundle) -> Result<Vec<TransactionReceipt>> {
+        let utx_err = |utx: &BundledTx, e| {
+            CubistSdkError::BroadcastError(utx.description.clone(), Box::new(e))
+        };
+        let signed = bundle
+            .transactions
+            .iter()
+            .map(|utx| {
+                utx.checked_signed()
+                    .map_err(|reason| utx_err(utx, WrapperError::ContractError(reason)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut receipts = Vec::with_capacity(bundle.transactions.len());
+        for (utx, signed) in bundle.transactions.iter().zip(signed) {
+            let broadcast_err = |e| utx_err(utx, e);
+            let project = self
+                .project(utx.target)
+                .ok_or(CubistSdkError::ProjectError(utx.target))?;
+            debug!("Broadcasting '{}' to {}", utx.description, utx.target);
+            let mut receipt = project.send_raw_tx(signed).await.map_err(broadcast_err)?;
+            if receipt.status != Some(U64::one()) {
+                return Err(broadcast_err(WrapperError::ContractError(format!(
+                    "transaction {:?} reverted",
+                    receipt.transaction_hash
+                ))));
+            }
+            if let (Some(fqn), Some(address)) = (&utx.contract, utx.creates) {
+                // receipts don't report contracts created through the CREATE2 factory
+                receipt.contract_address = Some(address);
+                project.save_receipt(fqn, address, &receipt).await?;
+            }
+            receipts.push(receipt);
+        }
+        for manifest in &bundle.deployments {
+            let target = manifest.deployment.target;
+            let contract = self
+                .find_contract(target, &manifest.contract)
+                .ok_or_else(|| {
+                    CubistSdkError::MissingRelayedContract(manifest.contract.clone(), target)
+                })?;
+            contract.complete_unsigned_deploy(manifest).await?;
+        }
+        Ok(receipts)
+    }
+
     /// Retrieve all managed accounts used on a target chain.
     pub async fn accounts_on(&self, t: Target) -> Result<Vec<Address>> {
         let proj = self.project(t).ok_or(CubistSdkError::ProjectError(t))?;
@@ -344,3 +546,47 @@ impl<M: Middleware> Cubist<M> {
             .to_vec())
     }
 }
+
+impl<M: Middleware + 'static> Cubist<M> {
+    /// Streams the events selected by `filter` that are emitted by deployed (non-shim)
+    /// contracts, merged across all of their targets.
+    ///
+    /// Errors while polling a target chain are yielded by the stream (which keeps polling).
+    pub fn events(&self, filter: EventFilter) -> Result<EventStream> {
+        let contracts: Vec<_> = self
+            .contracts()
+            .filter(|c| filter.matches(&c.meta.fqn.name, c.target()))
+            .collect();
+        // events of Stellar contracts are not declared ahead of time, so any name may match
+        let declares = |c: &Contract<M>, name: &str| match &c.meta.data {
+            ContractData::SolidityData { abi, .. } => abi.events.contains_key(name),
+            ContractData::SorobanData { .. } => true,
+        };
+        if !contracts.is_empty() {
+            if let Some(name) = filter
+                .events
+                .iter()
+                .find(|name| !contracts.iter().any(|c| declares(c, name)))
+            {
+                let names = contracts.iter().map(|c| c.full_name()).collect();
+                return Err(CubistSdkError::UnknownEvent(name.clone(), names));
+            }
+        }
+
+        let mut streams = vec![];
+        for contract in contracts.iter().filter(|c| c.is_deployed()) {
+            let names: Vec<_> = filter
+                .events
+                .iter()
+                .filter(|name| declares(contract, name))
+                .cloned()
+                .collect();
+            if !filter.events.is_empty() && names.is_empty() {
+                continue;
+            }
+            let from_block = filter.from_block.get(&contract.target()).copied();
+            streams.push(contract.event_stream(&names, from_block)?);
+        }
+        Ok(Box::pin(futures::stream::select_all(streams)))
+    }
+}
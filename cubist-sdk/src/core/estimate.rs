@@ -0,0 +1,272 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use cubist_config::paths::ContractFQN;
+use cubist_config::Target;
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use ethers::utils::format_units;
+
+use crate::core::{HttpStack, TargetProject};
+use crate::{CubistSdkError, Result};
+
+/// Gas set aside for each call made while deploying a contract to another contract that isn't
+/// deployed yet (e.g., granting the contract the CALLER role on a shim), since the gas used by
+/// such calls can't be estimated.
+pub const CALL_GAS_ALLOWANCE: u64 = 100_000;
+
+/// Gas set aside for deploying each proxy (or proxy admin) of an upgradeable contract, since
+/// proxies can't be estimated before their implementation is deployed.
+pub const PROXY_GAS_ALLOWANCE: u64 = 1_000_000;
+
+/// Estimated cost of the transactions a deployment sends to one chain, along with the balance
+/// of the account paying for them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainCost {
+    /// Chain
+    pub target: Target,
+    /// Account sending the transactions
+    pub sender: Address,
+    /// Number of transactions
+    pub transactions: usize,
+    /// Estimated gas used by all transactions
+    pub gas: U256,
+    /// Price (in wei) per unit of gas that transactions pay at most, according to the chain's
+    /// fee strategy (see [`TargetProject::max_fee_per_gas`])
+    pub gas_price: U256,
+    /// Balance of the sender (in wei)
+    pub balance: U256,
+}
+
+impl ChainCost {
+    /// Estimated cost (in wei) of all transactions.
+    pub fn cost(&self) -> U256 {
+        self.gas.saturating_mul(self.gas_price)
+    }
+
+    /// Whether the sender can't afford the estimated cost.
+    pub fn is_underfunded(&self) -> bool {
+        self.cost() > self.balance
+    }
+}
+
+/// Estimated cost of a deployment (of one or more contracts, along with their shims) on each of
+/// the chains it sends transactions to (see
+/// [`Contract::estimate_deploy`](crate::Contract::estimate_deploy)).
+///
+/// Displays as a table with one row per chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeploymentEstimate {
+    /// Costs per chain, ordered by target name
+    chains: Vec<ChainCost>,
+}
+
+impl DeploymentEstimate {
+    /// Costs per chain (ordered by target name).
+    pub fn chains(&self) -> &[ChainCost] {
+        &self.chains
+    }
+
+    /// Chains whose senders can't afford the estimated cost.
+    pub fn underfunded(&self) -> impl Iterator<Item = &ChainCost> {
+        self.chains.iter().filter(|c| c.is_underfunded())
+    }
+
+    /// Returns [`CubistSdkError::InsufficientFunds`] if any chain is underfunded.
+    pub fn check(&self) -> Result<()> {
+        match self.underfunded().next() {
+            Some(_) => Err(CubistSdkError::InsufficientFunds(self.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the costs of `other` (e.g., of deploying another contract) to these costs.
+    pub fn merge(&mut self, other: DeploymentEstimate) {
+        for cost in other.chains {
+            match self
+                .chains
+                .iter_mut()
+                .find(|c| c.target == cost.target && c.sender == cost.sender)
+            {
+                Some(c) => {
+                    c.transactions += cost.transactions;
+                    c.gas = c.gas.saturating_add(cost.gas);
+                }
+                None => self.chains.push(cost),
+            }
+        }
+        self.chains.sort_by_key(|c| c.target.to_string());
+    }
+}
+
+impl fmt::Display for DeploymentEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |cells: [String; 7]| {
+            let [target, sender, txs, gas, gas_price, cost, balance] = cells;
+            format!("{target:<12} {sender:<42} {txs:>4} {gas:>12} {gas_price:>20} {cost:>24} {balance:>24}")
+        };
+        let header = [
+            "chain",
+            "sender",
+            "txs",
+            "gas",
+            "gas price (gwei)",
+            "cost (ether)",
+            "balance (ether)",
+        ];
+        writeln!(f, "{}", row(header.map(String::from)))?;
+        for c in &self.chains {
+            let cells = [
+                c.target.to_string(),
+                format!("{:?}", c.sender),
+                c.transactions.to_string(),
+                c.gas.to_string(),
+                format_wei(c.gas_price, "gwei"),
+                format_wei(c.cost(), "ether"),
+                format_wei(c.balance, "ether"),
+            ];
+            let status = if c.is_underfunded() {
+                "  UNDERFUNDED"
+            } else {
+                ""
+            };
+            writeln!(f, "{}{status}", row(cells))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats an amount of `wei` in `units` (e.g., "ether").
+fn format_wei(wei: U256, units: &str) -> String {
+    format_units(wei, units).unwrap_or_else(|_| format!("{wei} wei"))
+}
+
+/// Gas (and number of transactions) a deployment needs on each chain, before pricing it.
+pub(crate) struct GasTally<M: Middleware = HttpStack> {
+    /// Chains mapped to their projects, the gas needed, and the number of transactions
+    chains: HashMap<Target, (Arc<TargetProject<M>>, U256, usize)>,
+    /// Contracts (and shims) whose deployments are accounted for, by target, name, and whether
+    /// they are shims
+    deploys: HashSet<(Target, ContractFQN, bool)>,
+}
+
+impl<M: Middleware> Default for GasTally<M> {
+    fn default() -> Self {
+        Self {
+            chains: HashMap::new(),
+            deploys: HashSet::new(),
+        }
+    }
+}
+
+impl<M: Middleware> GasTally<M> {
+    /// Marks the deployment of contract `fqn` (or of its shim, if `is_shim`) on `target` as
+    /// accounted for.  Returns whether it wasn't already, since contracts (and shims) shared by
+    /// several deployments are only deployed once.
+    pub(crate) fn first_deploy(
+        &mut self,
+        target: Target,
+        fqn: &ContractFQN,
+        is_shim: bool,
+    ) -> bool {
+        self.deploys.insert((target, fqn.clone(), is_shim))
+    }
+
+    /// Records a transaction sent to `project` that uses `gas`.
+    pub(crate) fn add(&mut self, project: &Arc<TargetProject<M>>, gas: U256) {
+        let entry = self
+            .chains
+            .entry(project.target)
+            .or_insert_with(|| (Arc::clone(project), U256::zero(), 0));
+        entry.1 = entry.1.saturating_add(gas);
+        entry.2 += 1;
+    }
+
+    /// Prices the gas on each chain (at the max fee per gas of its fee strategy), and looks up
+    /// the balance of each chain's sender.
+    pub(crate) async fn price(self) -> Result<DeploymentEstimate> {
+        let mut estimate = DeploymentEstimate::default();
+        for (target, (project, gas, transactions)) in self.chains {
+            let sender = project.sender().await?;
+            let estimate_err = |e| CubistSdkError::EstimateError(target, Box::new(e));
+            let gas_price = project.max_fee_per_gas().await.map_err(estimate_err)?;
+            let balance = project.balance(sender).await.map_err(estimate_err)?;
+            estimate.merge(DeploymentEstimate {
+                chains: vec![ChainCost {
+                    target,
+                    sender,
+                    transactions,
+                    gas,
+                    gas_price,
+                    balance,
+                }],
+            });
+        }
+        Ok(estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(target: Target, gas: u64, balance: u64) -> ChainCost {
+        ChainCost {
+            target,
+            sender: Address::zero(),
+            transactions: 1,
+            gas: gas.into(),
+            gas_price: 10.into(),
+            balance: balance.into(),
+        }
+    }
+
+    #[test]
+    fn test_first_deploy() {
+        let mut tally: GasTally = GasTally::default();
+        let fqn = ContractFQN::new("Receiver.sol".into(), "Receiver".into());
+        assert!(tally.first_deploy(Target::Ethereum, &fqn, false));
+        // shims of a contract are deployed once, however many contracts call it
+        assert!(tally.first_deploy(Target::Polygon, &fqn, true));
+        assert!(!tally.first_deploy(Target::Polygon, &fqn, true));
+        // Axelar receiver shims live on the same chain as their contracts
+        assert!(tally.first_deploy(Target::Ethereum, &fqn, true));
+        assert!(!tally.first_deploy(Target::Ethereum, &fqn, false));
+    }
+
+    #[test]
+    fn test_merge_and_check() {
+        let mut estimate = DeploymentEstimate {
+            chains: vec![cost(Target::Polygon, 100, 1500)],
+        };
+        assert!(estimate.check().is_ok());
+
+        estimate.merge(DeploymentEstimate {
+            chains: vec![
+                cost(Target::Polygon, 100, 1500),
+                cost(Target::Ethereum, 10, 50),
+            ],
+        });
+        assert_eq!(
+            vec![Target::Ethereum, Target::Polygon],
+            estimate
+                .chains()
+                .iter()
+                .map(|c| c.target)
+                .collect::<Vec<_>>()
+        );
+        let polygon = &estimate.chains()[1];
+        assert_eq!(2, polygon.transactions);
+        assert_eq!(U256::from(2000), polygon.cost());
+        assert_eq!(
+            vec![Target::Ethereum, Target::Polygon],
+            estimate.underfunded().map(|c| c.target).collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            estimate.check(),
+            Err(CubistSdkError::InsufficientFunds(_))
+        ));
+        assert!(estimate.to_string().contains("UNDERFUNDED"));
+    }
+}
@@ -0,0 +1,582 @@
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cubist_config::Target;
+use ethers::abi::{Event as AbiEvent, LogParam, RawLog};
+use ethers::providers::{Http, JsonRpcClient, Middleware};
+use ethers::types::{Filter, Log, ValueOrArray, H256};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use soroban_env_host::xdr::{Limits, ReadXdr, ScVal};
+use tracing::trace;
+use url::Url;
+
+use crate::core::ContractAddress;
+use crate::{Result, WrapperError};
+
+/// How often to poll target chains for new events.
+pub const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest delay between polls after consecutive polling errors (see [`Backoff`]).
+const MAX_POLL_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Max number of blocks to retrieve EVM logs for at once (RPC providers limit the block range
+/// of `eth_getLogs`, so backfilling from an old block is split into ranges of this size).
+pub const MAX_LOG_BLOCK_RANGE: u64 = 2000;
+
+/// Max number of Stellar events to retrieve at once.
+const STELLAR_EVENTS_PAGE_SIZE: u32 = 100;
+
+/// Exponential backoff for pollers: after each consecutive error, the delay before the next poll
+/// doubles (starting at [`EVENT_POLL_INTERVAL`], up to [`MAX_POLL_BACKOFF`]).
+#[derive(Default)]
+struct Backoff {
+    failures: u32,
+}
+
+impl Backoff {
+    /// Delay before the next poll (zero if the last poll succeeded).
+    fn delay(&self) -> Duration {
+        match self.failures {
+            0 => Duration::ZERO,
+            n => EVENT_POLL_INTERVAL
+                .saturating_mul(1 << (n - 1).min(16))
+                .min(MAX_POLL_BACKOFF),
+        }
+    }
+
+    /// Waits out the delay before the next poll (see [`Self::delay`]).
+    async fn wait(&self) {
+        if self.failures > 0 {
+            tokio::time::sleep(self.delay()).await;
+        }
+    }
+
+    fn failed(&mut self) {
+        self.failures = self.failures.saturating_add(1);
+    }
+
+    fn succeeded(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// Block range (inclusive) to retrieve logs for next, starting at block `from`: up to `latest`,
+/// but at most [`MAX_LOG_BLOCK_RANGE`] blocks.
+fn log_block_range(from: u64, latest: u64) -> (u64, u64) {
+    (
+        from,
+        latest.min(from.saturating_add(MAX_LOG_BLOCK_RANGE - 1)),
+    )
+}
+
+/// Where an event was emitted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventMeta {
+    /// Target chain on which the event was emitted
+    pub target: Target,
+    /// Address of the contract that emitted the event
+    pub address: ContractAddress,
+    /// Block (or, on Stellar, ledger) that includes the event
+    pub block_number: Option<u64>,
+    /// Hash of the transaction that emitted the event
+    pub tx_hash: Option<H256>,
+    /// Index of the event within its block (or, on Stellar, within its transaction)
+    pub log_index: Option<u64>,
+}
+
+/// An event (decoded into `T`) along with where it was emitted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractEvent<T = EventData> {
+    /// Where the event was emitted
+    pub meta: EventMeta,
+    /// Decoded event
+    pub data: T,
+}
+
+/// Decoded event of any contract (see [`Contract::events`](crate::core::Contract::events)).
+#[derive(Clone, Debug, PartialEq)]
+pub enum EventData {
+    /// Event emitted by an EVM contract
+    Evm {
+        /// Event name
+        name: String,
+        /// Event parameters
+        params: Vec<LogParam>,
+    },
+    /// Event emitted by a Soroban contract
+    Stellar {
+        /// Event name, i.e., the first topic (if it is a symbol)
+        name: Option<String>,
+        /// Event topics (base64-encoded XDR values)
+        topics: Vec<String>,
+        /// Event value (base64-encoded XDR value)
+        value: String,
+    },
+}
+
+impl EventData {
+    /// Name of the event (if known).
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            EventData::Evm { name, .. } => Some(name),
+            EventData::Stellar { name, .. } => name.as_deref(),
+        }
+    }
+}
+
+/// Stream of (decoded) events, possibly emitted on several targets.
+pub type EventStream<T = EventData> = Pin<Box<dyn Stream<Item = Result<ContractEvent<T>>> + Send>>;
+
+impl EventMeta {
+    fn from_log(target: Target, log: &Log) -> Self {
+        EventMeta {
+            target,
+            address: log.address.as_bytes().to_vec(),
+            block_number: log.block_number.map(|n| n.as_u64()),
+            tx_hash: log.transaction_hash,
+            log_index: log.log_index.map(|i| i.as_u64()),
+        }
+    }
+}
+
+/// Filter matching the logs of `events` emitted by the contract at `address`.
+pub(crate) fn evm_filter(address: ethers::types::Address, events: &[AbiEvent]) -> Filter {
+    let signatures = events.iter().map(|e| Some(e.signature())).collect();
+    Filter::new()
+        .address(address)
+        .topic0(ValueOrArray::Array(signatures))
+}
+
+/// Decodes a log emitted by one of `events` (or returns `None` if it matches none of them).
+pub(crate) fn decode_evm_event(events: &[AbiEvent], log: &Log) -> Option<EventData> {
+    let topic0 = log.topics.first()?;
+    let event = events.iter().find(|e| e.signature() == *topic0)?;
+    let raw = RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    };
+    let parsed = event.parse_log(raw).ok()?;
+    Some(EventData::Evm {
+        name: event.name.clone(),
+        params: parsed.params,
+    })
+}
+
+/// State of [`evm_logs`].
+struct LogPoller<M> {
+    provider: Arc<M>,
+    filter: Filter,
+    next_block: Option<u64>,
+    pending: VecDeque<Log>,
+    backoff: Backoff,
+}
+
+/// Polls for logs matching `filter`, starting at block `from_block` (or with the next block, if
+/// `None`).  Past blocks are retrieved in ranges of at most [`MAX_LOG_BLOCK_RANGE`] blocks.
+/// Errors are yielded (without ending the stream), and polling backs off while they persist.
+fn evm_logs<M: Middleware + 'static>(
+    provider: Arc<M>,
+    filter: Filter,
+    from_block: Option<u64>,
+) -> impl Stream<Item = Result<Log, WrapperError>> + Send {
+    let poller = LogPoller {
+        provider,
+        filter,
+        next_block: from_block,
+        pending: VecDeque::new(),
+        backoff: Backoff::default(),
+    };
+    stream::unfold(poller, |mut poller| async move {
+        loop {
+            if let Some(log) = poller.pending.pop_front() {
+                return Some((Ok(log), poller));
+            }
+            poller.backoff.wait().await;
+            let latest = match poller.provider.get_block_number().await {
+                Ok(latest) => latest.as_u64(),
+                Err(e) => {
+                    poller.backoff.failed();
+                    let e =
+                        WrapperError::ProviderError("get_block_number".to_owned(), e.to_string());
+                    return Some((Err(e), poller));
+                }
+            };
+            let from = *poller.next_block.get_or_insert(latest + 1);
+            if from > latest {
+                poller.backoff.succeeded();
+                tokio::time::sleep(EVENT_POLL_INTERVAL).await;
+                continue;
+            }
+            let (from, to) = log_block_range(from, latest);
+            let filter = poller.filter.clone().from_block(from).to_block(to);
+            match poller.provider.get_logs(&filter).await {
+                Ok(logs) => {
+                    poller.backoff.succeeded();
+                    poller.pending.extend(logs);
+                    poller.next_block = Some(to + 1);
+                }
+                Err(e) => {
+                    poller.backoff.failed();
+                    let e = WrapperError::ProviderError("get_logs".to_owned(), e.to_string());
+                    return Some((Err(e), poller));
+                }
+            }
+        }
+    })
+}
+
+/// Polls for logs matching `filter` (starting at block `from_block`, or with the next block, if
+/// `None`) and decodes them with `decode` (skipping logs that it cannot decode).
+pub(crate) fn evm_events<M, T, F>(
+    target: Target,
+    provider: Arc<M>,
+    filter: Filter,
+    from_block: Option<u64>,
+    decode: F,
+) -> impl Stream<Item = Result<ContractEvent<T>, WrapperError>> + Send
+where
+    M: Middleware + 'static,
+    T: Send + 'static,
+    F: Fn(&Log) -> Option<T> + Send + 'static,
+{
+    evm_logs(provider, filter, from_block).filter_map(move |log| {
+        let event = match log {
+            Ok(log) => match decode(&log) {
+                Some(data) => Some(Ok(ContractEvent {
+                    meta: EventMeta::from_log(target, &log),
+                    data,
+                })),
+                None => {
+                    trace!("Skipping undecodable log {log:?}");
+                    None
+                }
+            },
+            Err(e) => Some(Err(e)),
+        };
+        future::ready(event)
+    })
+}
+
+/// Event as returned by Soroban RPC's `getEvents` method.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SorobanEvent {
+    ledger: Value,
+    id: String,
+    paging_token: Option<String>,
+    #[serde(default)]
+    topic: Vec<String>,
+    value: Value,
+    tx_hash: Option<String>,
+}
+
+impl SorobanEvent {
+    fn into_event(self, target: Target, address: &ContractAddress) -> ContractEvent {
+        // older versions of Soroban RPC report numbers as strings and values as '{ "xdr": ... }'
+        let ledger = match &self.ledger {
+            Value::String(s) => s.parse().ok(),
+            v => v.as_u64(),
+        };
+        let value = match &self.value {
+            Value::String(s) => s.clone(),
+            v => v["xdr"].as_str().unwrap_or_default().to_owned(),
+        };
+        ContractEvent {
+            meta: EventMeta {
+                target,
+                address: address.clone(),
+                block_number: ledger,
+                tx_hash: self.tx_hash.and_then(|h| H256::from_str(&h).ok()),
+                // event ids end with the index of the event within its transaction
+                log_index: self.id.rsplit('-').next().and_then(|i| i.parse().ok()),
+            },
+            data: EventData::Stellar {
+                name: self.topic.first().and_then(|t| symbol(t)),
+                topics: self.topic,
+                value,
+            },
+        }
+    }
+}
+
+/// Decodes a base64-encoded XDR value if it is a symbol.
+fn symbol(xdr: &str) -> Option<String> {
+    match ScVal::from_xdr_base64(xdr, Limits::none()).ok()? {
+        ScVal::Symbol(symbol) => Some(symbol.0.to_utf8_string_lossy()),
+        _ => None,
+    }
+}
+
+/// State of [`stellar_events`].
+struct StellarPoller {
+    client: Http,
+    contract_id: String,
+    next_ledger: Option<u64>,
+    cursor: Option<String>,
+    pending: VecDeque<SorobanEvent>,
+    backoff: Backoff,
+}
+
+impl StellarPoller {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, WrapperError> {
+        self.client
+            .request(method, params)
+            .await
+            .map_err(|e| WrapperError::ProviderError(method.to_owned(), e.to_string()))
+    }
+
+    /// Retrieves the next page of events (if any).
+    async fn poll(&mut self) -> Result<(), WrapperError> {
+        let filters = json!([{ "type": "contract", "contractIds": [self.contract_id] }]);
+        let params = match &self.cursor {
+            Some(cursor) => json!({
+                "filters": filters,
+                "pagination": { "cursor": cursor, "limit": STELLAR_EVENTS_PAGE_SIZE },
+            }),
+            None => {
+                let start_ledger = match self.next_ledger {
+                    Some(ledger) => ledger,
+                    None => {
+                        let latest = self.request("getLatestLedger", json!({})).await?;
+                        latest["sequence"].as_u64().unwrap_or_default()
+                    }
+                };
+                self.next_ledger = Some(start_ledger);
+                json!({
+                    "startLedger": start_ledger,
+                    "filters": filters,
+                    "pagination": { "limit": STELLAR_EVENTS_PAGE_SIZE },
+                })
+            }
+        };
+        let response = self.request("getEvents", params).await?;
+        let events: Vec<SorobanEvent> = serde_json::from_value(response["events"].clone())
+            .map_err(|e| WrapperError::ProviderError("getEvents".to_owned(), e.to_string()))?;
+        if let Some(last) = events.last() {
+            self.cursor = Some(last.paging_token.clone().unwrap_or_else(|| last.id.clone()));
+        }
+        self.pending.extend(events);
+        Ok(())
+    }
+}
+
+/// Polls Soroban RPC (at `rpc_url`) for the events of contract `address` (starting at ledger
+/// `from_ledger`, or with the latest ledger, if `None`) whose name is one of `names` (or any
+/// name, if `names` is empty).
+pub(crate) fn stellar_events(
+    target: Target,
+    rpc_url: Url,
+    address: ContractAddress,
+    names: Vec<String>,
+    from_ledger: Option<u64>,
+) -> impl Stream<Item = Result<ContractEvent, WrapperError>> + Send {
+    let poller = StellarPoller {
+        client: Http::new(rpc_url),
+        contract_id: String::from_utf8_lossy(&address).into_owned(),
+        next_ledger: from_ledger,
+        cursor: None,
+        pending: VecDeque::new(),
+        backoff: Backoff::default(),
+    };
+    let events = stream::unfold(poller, move |mut poller| {
+        let address = address.clone();
+        async move {
+            loop {
+                if let Some(event) = poller.pending.pop_front() {
+                    return Some((Ok(event.into_event(target, &address)), poller));
+                }
+                poller.backoff.wait().await;
+                if let Err(e) = poller.poll().await {
+                    poller.backoff.failed();
+                    return Some((Err(e), poller));
+                }
+                poller.backoff.succeeded();
+                if poller.pending.is_empty() {
+                    tokio::time::sleep(EVENT_POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+    events.filter(move |event| {
+        let keep = match event {
+            Ok(event) => {
+                names.is_empty()
+                    || event
+                        .data
+                        .name()
+                        .is_some_and(|n| names.iter().any(|m| m == n))
+            }
+            Err(_) => true,
+        };
+        future::ready(keep)
+    })
+}
+
+/// Which events to stream (see [`Cubist::events`](crate::core::Cubist::events)).
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    /// Names of the contracts whose events to stream (all contracts, if empty)
+    pub contracts: Vec<String>,
+    /// Names of the events to stream (all events, if empty)
+    pub events: Vec<String>,
+    /// Targets on which to stream events (all targets, if empty)
+    pub targets: Vec<Target>,
+    /// Per-target block (or, on Stellar, ledger) from which to backfill past events; only new
+    /// events are streamed on targets not listed here
+    pub from_block: HashMap<Target, u64>,
+}
+
+impl EventFilter {
+    /// Whether events of contract `name` on `target` pass this filter.
+    pub(crate) fn matches(&self, name: &str, target: Target) -> bool {
+        (self.contracts.is_empty() || self.contracts.iter().any(|c| c == name))
+            && (self.targets.is_empty() || self.targets.contains(&target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{EventParam, ParamType, Token};
+    use ethers::types::{Address, Bytes, U256, U64};
+
+    fn stored_event() -> AbiEvent {
+        AbiEvent {
+            name: "Stored".to_owned(),
+            inputs: vec![
+                EventParam {
+                    name: "from".to_owned(),
+                    kind: ParamType::Address,
+                    indexed: true,
+                },
+                EventParam {
+                    name: "value".to_owned(),
+                    kind: ParamType::Uint(256),
+                    indexed: false,
+                },
+            ],
+            anonymous: false,
+        }
+    }
+
+    #[test]
+    fn test_decode_evm_event() {
+        let event = stored_event();
+        let from = Address::random();
+        let log = Log {
+            address: Address::random(),
+            topics: vec![event.signature(), H256::from(from)],
+            data: Bytes::from(ethers::abi::encode(&[Token::Uint(U256::from(42))])),
+            block_number: Some(U64::from(7)),
+            transaction_hash: Some(H256::random()),
+            log_index: Some(U256::from(3)),
+            ..Default::default()
+        };
+
+        let data = decode_evm_event(&[event.clone()], &log).unwrap();
+        assert_eq!(Some("Stored"), data.name());
+        assert_eq!(
+            EventData::Evm {
+                name: "Stored".to_owned(),
+                params: vec![
+                    LogParam {
+                        name: "from".to_owned(),
+                        value: Token::Address(from),
+                    },
+                    LogParam {
+                        name: "value".to_owned(),
+                        value: Token::Uint(U256::from(42)),
+                    },
+                ],
+            },
+            data
+        );
+
+        let meta = EventMeta::from_log(Target::Ethereum, &log);
+        assert_eq!(Some(7), meta.block_number);
+        assert_eq!(log.transaction_hash, meta.tx_hash);
+        assert_eq!(Some(3), meta.log_index);
+
+        // logs of other events are not decoded
+        let other = Log {
+            topics: vec![H256::random()],
+            ..log
+        };
+        assert_eq!(None, decode_evm_event(&[event], &other));
+    }
+
+    #[test]
+    fn test_soroban_event() {
+        let event: SorobanEvent = serde_json::from_value(json!({
+            "type": "contract",
+            "ledger": 1234,
+            "ledgerClosedAt": "2023-11-09T14:27:40Z",
+            "contractId": "CCQUESTION",
+            "id": "0000005299989434368-0000000001",
+            "pagingToken": "0000005299989434368-0000000001",
+            // the symbol 'stored'
+            "topic": ["AAAADwAAAAZzdG9yZWQAAA=="],
+            "value": "AAAAAwAAACo=",
+            "inSuccessfulContractCall": true,
+            "txHash": "6ba87fb5a2d7e0c2d2bbbc5c0a7e1f1fbdf5ff08df6b3b2ec1d3d51c9c4d8a3b"
+        }))
+        .unwrap();
+        let event = event.into_event(Target::Stellar, &b"CCQUESTION".to_vec());
+        assert_eq!(Some(1234), event.meta.block_number);
+        assert_eq!(Some(1), event.meta.log_index);
+        assert!(event.meta.tx_hash.is_some());
+        assert_eq!(Some("stored"), event.data.name());
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let all = EventFilter::default();
+        assert!(all.matches("Receiver", Target::Ethereum));
+
+        let filter = EventFilter {
+            contracts: vec!["Receiver".to_owned()],
+            targets: vec![Target::Polygon],
+            ..Default::default()
+        };
+        assert!(filter.matches("Receiver", Target::Polygon));
+        assert!(!filter.matches("Receiver", Target::Ethereum));
+        assert!(!filter.matches("Sender", Target::Polygon));
+    }
+
+    #[test]
+    fn test_log_block_range() {
+        assert_eq!((5, 5), log_block_range(5, 5));
+        assert_eq!((5, 100), log_block_range(5, 100));
+        // backfilling is split into bounded ranges
+        assert_eq!((0, MAX_LOG_BLOCK_RANGE - 1), log_block_range(0, 10_000));
+        assert_eq!(
+            (MAX_LOG_BLOCK_RANGE, 2 * MAX_LOG_BLOCK_RANGE - 1),
+            log_block_range(MAX_LOG_BLOCK_RANGE, 10_000)
+        );
+        assert_eq!((u64::MAX, u64::MAX), log_block_range(u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::default();
+        assert_eq!(Duration::ZERO, backoff.delay());
+        backoff.failed();
+        assert_eq!(EVENT_POLL_INTERVAL, backoff.delay());
+        backoff.failed();
+        assert_eq!(2 * EVENT_POLL_INTERVAL, backoff.delay());
+        backoff.failed();
+        assert_eq!(4 * EVENT_POLL_INTERVAL, backoff.delay());
+        for _ in 0..100 {
+            backoff.failed();
+        }
+        assert_eq!(MAX_POLL_BACKOFF, backoff.delay());
+        backoff.succeeded();
+        assert_eq!(Duration::ZERO, backoff.delay());
+    }
+}
@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use cubist_config::FeeStrategy;
+use ethers::providers::{FromErr, JsonRpcClient, Middleware, PendingTransaction, Provider};
+use ethers::types::{
+    transaction::eip2718::TypedTransaction, Address, BlockId, Eip1559TransactionRequest,
+    TransactionRequest,
+};
+use thiserror::Error;
+
+use super::WithSender;
+
+/// Middleware that sets the fees of every transaction (right before it's filled and sent)
+/// according to a [`FeeStrategy`].
+#[derive(Debug, Clone)]
+pub struct FeeMiddleware<M> {
+    /// The inner middleware
+    inner: M,
+    /// How to set transaction fees
+    strategy: FeeStrategy,
+}
+
+/// Errors raised by [`FeeMiddleware`]
+#[derive(Error, Debug)]
+pub enum FeeMiddlewareError<M: Middleware> {
+    /// Error raised by the inner middleware (e.g., when estimating fees)
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> FromErr<M::Error> for FeeMiddlewareError<M> {
+    fn from(src: M::Error) -> Self {
+        FeeMiddlewareError::MiddlewareError(src)
+    }
+}
+
+impl<M: Middleware> FeeMiddleware<M> {
+    /// Creates a new middleware that sets transaction fees according to `strategy`
+    pub fn new(inner: M, strategy: FeeStrategy) -> Self {
+        Self { inner, strategy }
+    }
+
+    /// Converts `tx` into the transaction type required by the fee strategy and sets its fees
+    /// (unless they are already set).
+    async fn set_fees(&self, tx: &mut TypedTransaction) -> Result<(), FeeMiddlewareError<M>> {
+        match &self.strategy {
+            FeeStrategy::Legacy => {
+                let mut req: TransactionRequest = tx.clone().into();
+                if req.gas_price.is_none() {
+                    // Some chains report a gas price below the base fee, so we use the max fee
+                    // per gas if that's higher
+                    let gas_price = self.inner.get_gas_price().await.map_err(FromErr::from)?;
+                    let (max_fee_per_gas, _) = self
+                        .inner
+                        .estimate_eip1559_fees(None)
+                        .await
+                        .map_err(FromErr::from)?;
+                    req.gas_price = Some(std::cmp::max(gas_price, max_fee_per_gas));
+                }
+                *tx = req.into();
+            }
+            FeeStrategy::Eip1559 => {
+                let mut req = to_eip1559(tx);
+                if req.max_fee_per_gas.is_none() || req.max_priority_fee_per_gas.is_none() {
+                    let (max_fee_per_gas, max_priority_fee_per_gas) = self
+                        .inner
+                        .estimate_eip1559_fees(None)
+                        .await
+                        .map_err(FromErr::from)?;
+                    req.max_fee_per_gas.get_or_insert(max_fee_per_gas);
+                    req.max_priority_fee_per_gas
+                        .get_or_insert(max_priority_fee_per_gas);
+                }
+                *tx = req.into();
+            }
+            FeeStrategy::Fixed {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                let mut req = to_eip1559(tx);
+                req.max_fee_per_gas = Some((*max_fee_per_gas).into());
+                req.max_priority_fee_per_gas = Some((*max_priority_fee_per_gas).into());
+                *tx = req.into();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: JsonRpcClient + Clone> WithSender for FeeMiddleware<Provider<P>> {
+    fn with_sender(&self, sender: Address) -> Self {
+        Self::new(
+            self.inner.clone().with_sender(sender),
+            self.strategy.clone(),
+        )
+    }
+}
+
+/// Converts `tx` into an EIP-1559 transaction request (dropping its gas price, if any)
+fn to_eip1559(tx: &TypedTransaction) -> Eip1559TransactionRequest {
+    let (req, access_list) = match tx {
+        TypedTransaction::Eip1559(req) => return req.clone(),
+        TypedTransaction::Eip2930(req) => (req.tx.clone(), req.access_list.clone()),
+        TypedTransaction::Legacy(req) => (req.clone(), Default::default()),
+    };
+    let mut eip1559 = Eip1559TransactionRequest::new();
+    eip1559.from = req.from;
+    eip1559.to = req.to;
+    eip1559.gas = req.gas;
+    eip1559.value = req.value;
+    eip1559.data = req.data;
+    eip1559.nonce = req.nonce;
+    eip1559.chain_id = req.chain_id;
+    eip1559.access_list = access_list;
+    eip1559
+}
+
+#[async_trait]
+impl<M: Middleware> Middleware for FeeMiddleware<M> {
+    type Error = FeeMiddlewareError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    async fn fill_transaction(
+        &self,
+        tx: &mut TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<(), Self::Error> {
+        self.set_fees(tx).await?;
+        self.inner
+            .fill_transaction(tx, block)
+            .await
+            .map_err(FromErr::from)
+    }
+
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let mut tx = tx.into();
+        self.fill_transaction(&mut tx, block).await?;
+        self.inner
+            .send_transaction(tx, block)
+            .await
+            .map_err(FromErr::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Address, U256};
+
+    #[test]
+    fn test_to_eip1559() {
+        let from = Address::random();
+        let legacy: TypedTransaction = TransactionRequest::new()
+            .from(from)
+            .gas(21000)
+            .gas_price(7)
+            .value(42)
+            .into();
+        let req = to_eip1559(&legacy);
+        assert_eq!(Some(from), req.from);
+        assert_eq!(Some(U256::from(21000)), req.gas);
+        assert_eq!(Some(U256::from(42)), req.value);
+        assert_eq!(None, req.max_fee_per_gas);
+        assert_eq!(None, req.max_priority_fee_per_gas);
+    }
+}
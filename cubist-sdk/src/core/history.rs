@@ -0,0 +1,204 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cubist_config::paths::ContractFQN;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::core::DeploymentInfo;
+use crate::WrapperError;
+
+/// What happened to a contract (see [`DeploymentRecord`]).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentEvent {
+    /// The contract (and its shims) got deployed
+    Deployed,
+    /// The contract got upgraded to a new implementation
+    Upgraded,
+}
+
+/// Entry of a [`DeploymentHistory`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DeploymentRecord {
+    /// When the event was recorded (in seconds since the Unix epoch)
+    pub timestamp: u64,
+    /// What happened
+    pub event: DeploymentEvent,
+    /// Deployed contract
+    pub contract: ContractFQN,
+    /// Non-shim contract's deployment info
+    #[serde(flatten)]
+    pub deployment: DeploymentInfo,
+    /// All of its shims' deployment infos
+    pub shims: Vec<DeploymentInfo>,
+    /// Address of the implementation contract (upgradeable contracts only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implementation: Option<Address>,
+}
+
+impl DeploymentRecord {
+    /// Creates a record of an event that happens now.
+    pub fn now(
+        event: DeploymentEvent,
+        contract: ContractFQN,
+        deployment: DeploymentInfo,
+        shims: Vec<DeploymentInfo>,
+        implementation: Option<Address>,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        DeploymentRecord {
+            timestamp,
+            event,
+            contract,
+            deployment,
+            shims,
+            implementation,
+        }
+    }
+}
+
+/// Append-only history of the deployments (and upgrades) made with a network profile, stored as
+/// one JSON record per line (see
+/// [`Paths::deployment_history`](cubist_config::paths::Paths::deployment_history)).
+pub struct DeploymentHistory {
+    path: PathBuf,
+}
+
+impl DeploymentHistory {
+    /// Constructor.
+    pub fn new(path: PathBuf) -> Self {
+        DeploymentHistory { path }
+    }
+
+    /// The file backing this history.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a record to the history.
+    pub fn append(&self, record: &DeploymentRecord) -> Result<(), WrapperError> {
+        let io_err = |e| WrapperError::IOError(self.path.clone(), e);
+        let mut line = serde_json::to_string(record).map_err(|e| {
+            WrapperError::JsonError(self.path.clone(), "DeploymentRecord".to_owned(), e)
+        })?;
+        line.push('\n');
+        if let Some(parent_dir) = self.path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|e| WrapperError::IOError(parent_dir.to_path_buf(), e))?;
+        }
+        // a single write of a whole line, so concurrent appends don't interleave
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(io_err)
+    }
+
+    /// Returns all records (oldest first), or none if there is no history yet.
+    pub fn records(&self) -> Result<Vec<DeploymentRecord>, WrapperError> {
+        if !self.path.is_file() {
+            return Ok(vec![]);
+        }
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| WrapperError::IOError(self.path.clone(), e))?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| {
+                    WrapperError::JsonError(self.path.clone(), "DeploymentRecord".to_owned(), e)
+                })
+            })
+            .collect()
+    }
+
+    /// Rewrites the history keeping only the records for which `keep` returns `true`, and returns
+    /// the removed records.
+    pub fn retain(
+        &self,
+        mut keep: impl FnMut(&DeploymentRecord) -> bool,
+    ) -> Result<Vec<DeploymentRecord>, WrapperError> {
+        let (kept, removed): (Vec<_>, Vec<_>) = self.records()?.into_iter().partition(&mut keep);
+        if removed.is_empty() {
+            return Ok(removed);
+        }
+        let mut content = String::new();
+        for record in &kept {
+            content += &serde_json::to_string(record).map_err(|e| {
+                WrapperError::JsonError(self.path.clone(), "DeploymentRecord".to_owned(), e)
+            })?;
+            content.push('\n');
+        }
+        let tmp_file = self.path.with_extension("tmp");
+        fs::write(&tmp_file, content).map_err(|e| WrapperError::IOError(tmp_file.clone(), e))?;
+        fs::rename(&tmp_file, &self.path)
+            .map_err(|e| WrapperError::IOError(tmp_file.clone(), e))?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cubist_config::Target;
+    use tempfile::tempdir;
+
+    fn record(event: DeploymentEvent, name: &str, chain_id: u64) -> DeploymentRecord {
+        DeploymentRecord::now(
+            event,
+            ContractFQN::new("Counter.sol".into(), name.to_owned()),
+            DeploymentInfo {
+                target: Target::Ethereum,
+                address: Address::random().as_bytes().to_vec(),
+                chain_id: Some(chain_id),
+            },
+            vec![DeploymentInfo {
+                target: Target::Polygon,
+                address: Address::random().as_bytes().to_vec(),
+                chain_id: Some(80001),
+            }],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempdir().unwrap();
+        let history = DeploymentHistory::new(dir.path().join("dev").join("deployments.jsonl"));
+        assert!(history.records().unwrap().is_empty());
+
+        let first = record(DeploymentEvent::Deployed, "Counter", 31337);
+        let second = record(DeploymentEvent::Upgraded, "Counter", 31337);
+        history.append(&first).unwrap();
+        history.append(&second).unwrap();
+        assert_eq!(vec![first, second], history.records().unwrap());
+    }
+
+    #[test]
+    fn test_retain() {
+        let dir = tempdir().unwrap();
+        let history = DeploymentHistory::new(dir.path().join("deployments.jsonl"));
+        let local = record(DeploymentEvent::Deployed, "Counter", 31337);
+        let testnet = record(DeploymentEvent::Deployed, "Counter", 5);
+        history.append(&local).unwrap();
+        history.append(&testnet).unwrap();
+
+        let removed = history
+            .retain(|r| r.deployment.chain_id != Some(31337))
+            .unwrap();
+        assert_eq!(vec![local], removed);
+        assert_eq!(vec![testnet.clone()], history.records().unwrap());
+
+        // appending still works after rewriting
+        let another = record(DeploymentEvent::Deployed, "Other", 5);
+        history.append(&another).unwrap();
+        assert_eq!(vec![testnet, another], history.records().unwrap());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cubist_config::paths::ContractFQN;
+use cubist_config::Target;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{DeploymentInfo, ProxyDeployment, SafeProposal};
+use crate::{CubistSdkError, Result, WrapperError};
+
+/// Shim of another contract that a contract calls (see [`DeploymentProgress::deps`]).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct JournaledDep {
+    /// The shim
+    pub contract: ContractFQN,
+    /// Deployment info of the shim
+    #[serde(flatten)]
+    pub deployment: DeploymentInfo,
+    /// Whether the contract has been granted the CALLER role on the shim
+    #[serde(default)]
+    pub approved: bool,
+}
+
+/// Steps of deploying a contract (see [`Contract::deploy`](crate::Contract::deploy)) completed
+/// so far.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DeploymentProgress {
+    /// Deployed shims of the contract
+    #[serde(default)]
+    pub shims: Vec<DeploymentInfo>,
+    /// The deployed contract (the proxy, if the contract is upgradeable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<DeploymentInfo>,
+    /// Proxy info, if the contract is upgradeable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyDeployment>,
+    /// Whether the shims have been updated with the address of the contract (Axelar only)
+    #[serde(default)]
+    pub shims_updated: bool,
+    /// Deployed shims (of other contracts) that the contract calls
+    #[serde(default)]
+    pub deps: Vec<JournaledDep>,
+    /// Admin transactions proposed to Safes so far (see [`SafeProposal`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub safe_proposals: Vec<SafeProposal>,
+}
+
+impl DeploymentProgress {
+    /// Deployment infos of everything deployed so far.
+    pub fn deployments(&self) -> impl Iterator<Item = &DeploymentInfo> {
+        self.shims
+            .iter()
+            .chain(&self.deployment)
+            .chain(self.deps.iter().map(|d| &d.deployment))
+    }
+
+    /// Returns the journaled dependency `contract` on `target`, if any.
+    pub fn dep(&self, contract: &ContractFQN, target: Target) -> Option<&JournaledDep> {
+        self.deps
+            .iter()
+            .find(|d| d.contract.is_same_as(contract) && d.deployment.target == target)
+    }
+}
+
+/// Journal of a deployment in progress, which is updated (on disk) after every step, so that
+/// an interrupted deployment can be resumed (reusing the contracts deployed so far) rather than
+/// started over (leaking them).  The journal is removed once the deployment completes (see
+/// [`Paths::for_deployment_journal`](cubist_config::paths::Paths::for_deployment_journal)).
+pub struct DeploymentJournal {
+    path: PathBuf,
+    progress: DeploymentProgress,
+}
+
+impl DeploymentJournal {
+    /// Opens the journal at `path`, which records no progress if the file doesn't exist.
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let progress = if path.is_file() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| journal_err(&path, WrapperError::IOError(path.clone(), e)))?;
+            serde_json::from_str(&content).map_err(|e| {
+                journal_err(
+                    &path,
+                    WrapperError::JsonError(path.clone(), "DeploymentProgress".to_owned(), e),
+                )
+            })?
+        } else {
+            DeploymentProgress::default()
+        };
+        Ok(DeploymentJournal { path, progress })
+    }
+
+    /// The file backing this journal.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The progress recorded so far.
+    pub fn progress(&self) -> &DeploymentProgress {
+        &self.progress
+    }
+
+    /// Whether the journal records any progress, i.e., whether it belongs to an interrupted
+    /// deployment.
+    pub fn has_progress(&self) -> bool {
+        self.progress != DeploymentProgress::default()
+    }
+
+    /// Records progress (by applying `update`) and writes the journal to disk atomically.
+    pub fn update(&mut self, update: impl FnOnce(&mut DeploymentProgress)) -> Result<()> {
+        update(&mut self.progress);
+        let content = serde_json::to_string_pretty(&self.progress).map_err(|e| {
+            self.err(WrapperError::JsonError(
+                self.path.clone(),
+                "DeploymentProgress".to_owned(),
+                e,
+            ))
+        })?;
+        let tmp_file = self.path.with_extension("tmp");
+        if let Some(parent_dir) = self.path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|e| self.err(WrapperError::IOError(parent_dir.to_path_buf(), e)))?;
+        }
+        fs::write(&tmp_file, content)
+            .map_err(|e| self.err(WrapperError::IOError(tmp_file.clone(), e)))?;
+        fs::rename(&tmp_file, &self.path)
+            .map_err(|e| self.err(WrapperError::IOError(self.path.clone(), e)))
+    }
+
+    /// Discards all recorded progress (and removes the journal from disk).
+    pub fn clear(&mut self) -> Result<()> {
+        self.progress = DeploymentProgress::default();
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(self.err(WrapperError::IOError(self.path.clone(), e))),
+        }
+    }
+
+    /// Wraps `e` into an error pointing at this journal.
+    fn err(&self, e: WrapperError) -> CubistSdkError {
+        journal_err(&self.path, e)
+    }
+}
+
+/// Wraps `e` into an error pointing at the journal at `path`.
+fn journal_err(path: &Path, e: WrapperError) -> CubistSdkError {
+    CubistSdkError::DeploymentJournalError(path.to_path_buf(), Box::new(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal").join("Receiver.json");
+        let mut journal = DeploymentJournal::open(path.clone()).unwrap();
+        assert!(!journal.has_progress());
+
+        let shim = DeploymentInfo {
+            target: Target::Polygon,
+            address: vec![1; 20],
+            chain_id: Some(80001),
+        };
+        journal.update(|p| p.shims.push(shim.clone())).unwrap();
+        journal
+            .update(|p| {
+                p.deps.push(JournaledDep {
+                    contract: ContractFQN::new(PathBuf::from("a.sol"), "Sender".to_owned()),
+                    deployment: shim.clone(),
+                    approved: true,
+                })
+            })
+            .unwrap();
+
+        let reopened = DeploymentJournal::open(path.clone()).unwrap();
+        assert!(reopened.has_progress());
+        assert_eq!(journal.progress(), reopened.progress());
+        assert_eq!(2, reopened.progress().deployments().count());
+
+        journal.clear().unwrap();
+        assert!(!path.exists());
+        assert!(!DeploymentJournal::open(path).unwrap().has_progress());
+    }
+}
@@ -3,28 +3,45 @@ use cubist_config::bridge::Bridge;
 use cubist_config::paths::{ContractFQN, Paths, TargetPaths};
 use cubist_config::secret::SecretUrl;
 use cubist_config::util::OrBug;
-use cubist_config::BridgeProvider;
 use cubist_config::{
-    network::EndpointConfig, Compiler, CompilerConfig, Config, NetworkName, PreCompileManifest,
-    Target, TargetConfig,
+    network::{
+        CommonConfig, EndpointConfig, DEFAULT_CONFIRMATIONS, DEFAULT_POLL_INTERVAL,
+        DEFAULT_RECEIPT_TIMEOUT,
+    },
+    Compiler, CompilerConfig, Config, NetworkName, PreCompileManifest, Target, TargetConfig,
+};
+use cubist_config::{
+    BridgeProvider, ContractName, Create2Config, FeeStrategy, ProxyKind, CREATE2_FACTORY,
 };
 use cubist_localchains::provider::Provider as CubistProvider;
+use cubist_localchains::UrlExt;
 use ethers::abi::Abi;
 use ethers::abi::{Token, Tokenize};
-use ethers::prelude::transformer::TransformerMiddleware;
 use ethers::prelude::*;
 use ethers::providers::Provider;
 use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers_solc::artifacts::StorageLayout;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::iter::repeat;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::Duration;
 use std::{convert::TryFrom, sync::Arc};
-use tracing::debug;
+use tokio::sync::OnceCell;
+use tracing::{debug, trace};
 
-use crate::core::{ContractAddress, ContractData, LegacyTransformer};
+use crate::core::{
+    storage_layout_incompatibilities, ContractAddress, ContractData, FeeMiddleware, RevertReason,
+    SafeProposal,
+};
+use crate::gen::multicall::{
+    CANONICAL_MULTICALL, MULTICALL_CONTRACT, MULTICALL_FILE, MULTICALL_SALT,
+};
+use crate::gen::proxy::{
+    PROXIES_FILE, PROXY_ADMIN_CONTRACT, TRANSPARENT_PROXY_CONTRACT, UUPS_PROXY_CONTRACT,
+};
 use crate::hex::serde_hex;
 use crate::target_handler::solang::SolangCompiler;
 use crate::target_handler::solc::SolcCompiler;
@@ -42,10 +59,31 @@ pub struct DeploymentInfo {
     /// Contract address
     #[serde(with = "serde_hex")]
     pub address: ContractAddress,
+
+    /// Id of the chain to which the contract was deployed (not set for Stellar contracts and
+    /// for manifests written by older versions of Cubist)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+}
+
+/// Proxy info of a contract deployed behind an ERC-1967 proxy (see
+/// [`ContractsConfig::upgradeable`](cubist_config::ContractsConfig::upgradeable)).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProxyDeployment {
+    /// Kind of proxy
+    pub kind: ProxyKind,
+    /// Address of the current implementation contract
+    pub implementation: Address,
+    /// Address of the proxy admin contract (transparent proxies only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<Address>,
+    /// Storage layout of the current implementation (checked before upgrading)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<StorageLayout>,
 }
 
 /// Per-contract manifest generated by the deployer.
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct DeploymentManifest {
     /// Deployed contract
     pub contract: ContractFQN,
@@ -54,9 +92,36 @@ pub struct DeploymentManifest {
     pub deployment: DeploymentInfo,
     /// All of its shims' deployment infos
     pub shims: Vec<DeploymentInfo>,
+    /// Proxy info if the contract is upgradeable (in which case [`Self::deployment`] is the
+    /// address of the proxy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyDeployment>,
+    /// Admin transactions concerning the contract that are proposed to Safes and pending
+    /// execution (see [`CommonConfig::safe`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub safe_proposals: Vec<SafeProposal>,
+}
+
+// Storage layouts are not hashable, so we hash everything but the proxy info and the Safe
+// proposals (which is consistent with `Eq`, since equal manifests still have equal hashes)
+impl Hash for DeploymentManifest {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.contract.hash(state);
+        self.deployment.hash(state);
+        self.shims.hash(state);
+    }
 }
 
 impl DeploymentManifest {
+    /// Reads a manifest from a file.
+    pub fn from_file(path: &Path) -> Result<Self, WrapperError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| WrapperError::IOError(path.to_path_buf(), e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            WrapperError::JsonError(path.to_path_buf(), "DeploymentManifest".to_owned(), e)
+        })
+    }
+
     /// Writes the manifest to a file atomically, which guarantees
     /// that the "deployment watcher" reads it after it has been
     /// flushed.
@@ -100,18 +165,24 @@ pub trait ContractCompiler {
 type HttpProvider = Provider<Http>;
 type WsProvider = Provider<Ws>;
 
-type Stack<M> = TransformerMiddleware<M, LegacyTransformer>;
+type Stack<M> = FeeMiddleware<M>;
 
-/// Type alias for a middleware implementation over HTTP that transforms requests into legacy
-/// requests and signs them. The signer sends requests directly through the HTTP provider, because
-/// it only issues requests that are answered by the proxy itself, which supports EIP-1599
-/// transactions.
+/// Type alias for a middleware implementation over HTTP that sets the fees of each transaction
+/// according to the target's [`FeeStrategy`] (converting it into a legacy transaction, unless
+/// the strategy calls for EIP-1559 transactions).
 pub type HttpStack = Stack<HttpProvider>;
 
 /// Same as `MiddlewareStack` but over web sockets
 pub type WsStack = Stack<WsProvider>;
 
+/// Middleware whose default sender can be changed (see [`TargetProject::with_sender`]).
+pub trait WithSender: Middleware + Sized {
+    /// Returns a copy of this middleware that sends transactions from `sender`.
+    fn with_sender(&self, sender: Address) -> Self;
+}
+
 /// Additional bridge information depending on which [`BridgeProvider`] is in use
+#[derive(Clone)]
 pub enum BridgeInfo {
     /// Corresponds to [BridgeProvider::Cubist]
     Cubist,
@@ -135,18 +206,47 @@ pub struct TargetProjectInfo {
     pub network_config: Option<EndpointConfig>,
     /// What kind of bridge provider is used.
     pub bridge_provider: BridgeProvider,
+    /// Settings for deterministic (CREATE2) deployments, if enabled.
+    pub create2: Option<Create2Config>,
+    /// Endpoint on which the relayer publishes bridged contracts, if enabled.
+    pub relayer_endpoint: Option<String>,
+    /// Contracts deployed behind proxies, mapped to the kind of proxy.
+    upgradeable: HashMap<ContractName, ProxyKind>,
     /// Manifest containing paths to source contracts and generated shims.
     manifest: PreCompileManifest,
 }
 
+/// Per-call overrides of how long and how often to wait for transaction receipts (see
+/// [`TargetProject::with_receipt_options`]).  Unset options default to the target's network
+/// config (see [`CommonConfig`](cubist_config::network::CommonConfig)).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReceiptOptions {
+    /// Number of confirmations to wait for
+    pub confirmations: Option<usize>,
+    /// How long to wait for a transaction to be confirmed
+    pub timeout: Option<Duration>,
+    /// How often to poll for transaction receipts
+    pub poll_interval: Option<Duration>,
+}
+
 /// Generic project targeting a single chain either via HTTP or WS.
 pub struct TargetProject<M: Middleware = HttpStack> {
     /// Project for this chain
-    project: TargetProjectInfo,
+    project: Arc<TargetProjectInfo>,
     /// Provider for this chain.
     provider: Arc<M>,
     /// Additional info pertaining to the bridge provider in use.
     pub bridge: BridgeInfo,
+    /// Id of this chain, retrieved on first use.
+    chain_id: OnceCell<u64>,
+    /// Name and version of the node serving this chain (see [`Self::client_version`]),
+    /// retrieved on first use.
+    client_version: OnceCell<String>,
+    /// Overrides of the configured receipt settings.
+    receipt_options: ReceiptOptions,
+    /// Address of the multicall contract, resolved (and deployed, if needed) on first use and
+    /// forgotten when reverting to a snapshot (see [`Self::forget_multicall`]).
+    multicall: Mutex<Option<Address>>,
 }
 
 /// Instances of [`TargetProject`] can be automatically dereferenced
@@ -161,56 +261,43 @@ impl<M: Middleware> std::ops::Deref for TargetProject<M> {
 
 async fn wrap<M: JsonRpcClient>(
     provider: Provider<M>,
-    target: Target,
+    project: &TargetProjectInfo,
 ) -> Result<Stack<Provider<M>>, WrapperError> {
+    let target = project.target;
+    let fees = project.fee_strategy();
     if target == Target::Stellar {
-        // TODO: This is a dummy transformer
-        return Ok(TransformerMiddleware::new(
-            provider,
-            LegacyTransformer {
-                gas_price: 10.into(),
-            },
-        ));
+        // TODO: This is a dummy middleware
+        return Ok(FeeMiddleware::new(provider, fees));
     }
 
     let provider = provider.interval(Duration::from_millis(50));
 
-    // Use the first account for all requests if it exists
-    // TODO(#261): Allow configuration to override it
-    let address = provider
-        .get_accounts()
-        .await
-        .map_err(|e| WrapperError::ProviderError("get_accounts".to_owned(), e.to_string()))?
-        .first()
-        .copied();
+    // Use the configured default sender for all requests, or else the first account if it exists
+    let address = match project.default_sender() {
+        Some(address) => Some(address),
+        None => provider
+            .get_accounts()
+            .await
+            .map_err(|e| WrapperError::ProviderError("get_accounts".to_owned(), e.to_string()))?
+            .first()
+            .copied(),
+    };
+    debug!("Default sender on {target}: {address:?}");
 
     let provider = match address {
         Some(address) => provider.with_sender(address),
         None => provider,
     };
 
-    // TODO(#261): Allow configuration to override gas price
-    let gas_price = provider
-        .get_gas_price()
-        .await
-        .map_err(|e| WrapperError::ProviderError("get_gas_price".to_owned(), e.to_string()))?;
-    let fees = provider
-        .estimate_eip1559_fees(None)
-        .await
-        .map_err(|e| WrapperError::ProviderError("estimate_fees".to_owned(), e.to_string()))?;
-    debug!(
-        "Fees on {target}: gas_price: {gas_price}, max fee per gas: {:?}",
-        fees.0
-    );
-    let gas_price = std::cmp::max(gas_price, fees.0);
-    Ok(TransformerMiddleware::new(
-        provider,
-        LegacyTransformer { gas_price },
-    ))
+    debug!("Fee strategy on {target}: {fees:?}");
+    Ok(FeeMiddleware::new(provider, fees))
 }
 
 /// Factory method for creating an ethers provider over HTTP
-async fn create_provider_http(url: &SecretUrl, target: Target) -> Result<HttpStack, WrapperError> {
+async fn create_provider_http(
+    url: &SecretUrl,
+    project: &TargetProjectInfo,
+) -> Result<HttpStack, WrapperError> {
     let http_scheme = to_http_scheme(url.scheme());
     let exposed_url = url
         .expose_url_and_update(Some(http_scheme), None, None)
@@ -218,11 +305,14 @@ async fn create_provider_http(url: &SecretUrl, target: Target) -> Result<HttpSta
 
     let provider = HttpProvider::try_from(exposed_url.as_str()).map_err(WrapperError::UrlError)?;
 
-    wrap(provider, target).await
+    wrap(provider, project).await
 }
 
 /// Factory method for creating an ethers provider over WS
-async fn create_provider_ws(url: &SecretUrl, target: Target) -> Result<WsStack, WrapperError> {
+async fn create_provider_ws(
+    url: &SecretUrl,
+    project: &TargetProjectInfo,
+) -> Result<WsStack, WrapperError> {
     let ws_scheme = to_ws_scheme(url.scheme());
     let exposed_url = url
         .expose_url_and_update(Some(ws_scheme), None, None)
@@ -230,7 +320,44 @@ async fn create_provider_ws(url: &SecretUrl, target: Target) -> Result<WsStack,
     let provider = WsProvider::connect(exposed_url.as_str())
         .await
         .map_err(|e| WrapperError::ProviderError("connect".to_owned(), e.to_string()))?;
-    wrap(provider, target).await
+    wrap(provider, project).await
+}
+
+/// Returns the init code for deploying a contract, i.e., its bytecode followed by its encoded
+/// constructor arguments.
+fn init_code(abi: &Abi, bytes: &Bytes, args: Vec<Token>) -> Result<Bytes, WrapperError> {
+    match abi.constructor() {
+        Some(constructor) => constructor
+            .encode_input(bytes.to_vec(), &args)
+            .map(Bytes::from)
+            .map_err(|e| WrapperError::ContractError(e.to_string())),
+        None if args.is_empty() => Ok(bytes.clone()),
+        None => Err(WrapperError::ContractError(
+            "Constructor takes no arguments".to_owned(),
+        )),
+    }
+}
+
+/// Returns the abi and bytecode of a Solidity contract.
+fn solidity_code(contract: &ContractInfo) -> Option<(&Abi, &Bytes)> {
+    match &contract.data {
+        ContractData::SolidityData { abi, bytes, .. } => Some((abi, bytes)),
+        ContractData::SorobanData { .. } => None,
+    }
+}
+
+/// Returns the name of the function that upgrades a UUPS proxy in an implementation's abi
+/// (`upgradeTo` or, since OpenZeppelin 5, only `upgradeToAndCall`), if there is one.
+fn uups_upgrade_function(abi: &Abi) -> Option<&'static str> {
+    ["upgradeTo", "upgradeToAndCall"]
+        .into_iter()
+        .find(|name| abi.function(name).is_ok())
+}
+
+fn missing_uups_upgrade_function() -> WrapperError {
+    WrapperError::ContractError(
+        "UUPS implementation has no 'upgradeTo' or 'upgradeToAndCall' function".to_owned(),
+    )
 }
 
 impl TargetProjectInfo {
@@ -257,16 +384,28 @@ impl TargetProjectInfo {
             paths,
             target_paths: my_paths,
             compiler: *compiler,
-            compiler_config: cubist_config.get_compiler_config(),
+            compiler_config: cubist_config.get_target_compiler_config(target),
             target,
             network_config,
             manifest,
             bridge_provider: cubist_config.bridge_provider.clone(),
+            create2: cubist_config.create2.clone(),
+            relayer_endpoint: cubist_config.relayer_endpoint.clone(),
+            upgradeable: contracts.upgradeable.clone(),
         })
     }
 }
 
 impl TargetProjectInfo {
+    /// Whether Cubist starts the chain of this target locally (see
+    /// [`autostart`](cubist_config::network::CommonConfig::autostart)).
+    pub fn is_started_locally(&self) -> Result<bool> {
+        Ok(match self.network_config.as_ref().map(|c| c.common()) {
+            Some(common) => common.autostart && common.url.is_loopback()?,
+            None => false,
+        })
+    }
+
     /// Connect to the endpoint using HTTP
     pub async fn connect(self) -> Result<TargetProject<HttpStack>> {
         TargetProject::<HttpStack>::create(self).await
@@ -290,12 +429,12 @@ impl TargetProjectInfo {
 
     /// Clean build artifacts
     pub fn clean(&self) -> Result<()> {
-        self.compiler().clean()
+        self.compiler()?.clean()
     }
 
     /// Compile a contract file.
     pub fn compile_file(&self, file: &Path) -> Result<CompileResult> {
-        self.compiler().compile_file(file)
+        self.compiler()?.compile_file(file)
     }
 
     /// Whether contract `cc` is allowed to call contract `dep` (i.e.,
@@ -318,9 +457,13 @@ impl TargetProjectInfo {
     /// Find all (non-shim) contracts targeting this chain.
     pub fn find_contracts(&self) -> Result<Vec<ContractInfo>> {
         let mut result = Vec::new();
-        for source_file in self.manifest.contract_files() {
-            for contract in self.find_compiled_contracts(source_file)?.into_values() {
-                result.push(contract);
+        for file in self.manifest.files.iter().filter(|f| !f.is_shim) {
+            for contract in self.find_compiled_contracts(&file.rel_path)?.into_values() {
+                // A file may also contain contracts that target other chains (when annotated
+                // with `@custom:cubist-target`); the manifest lists only those targeting this one
+                if file.contract_dependencies.contains_key(&contract.fqn.name) {
+                    result.push(contract);
+                }
             }
         }
         Ok(result)
@@ -344,20 +487,101 @@ impl TargetProjectInfo {
         Ok(c)
     }
 
+    /// Returns the kind of proxy contract `name` is deployed behind, if it is upgradeable (see
+    /// [`ContractsConfig::upgradeable`](cubist_config::ContractsConfig::upgradeable)).
+    pub fn proxy_kind(&self, name: &str) -> Option<ProxyKind> {
+        self.upgradeable.get(name).copied()
+    }
+
+    /// Find one of the generated proxy contracts (see [`crate::gen::proxy`]) by its name.
+    pub(crate) fn proxy_contract(&self, name: &str) -> Result<ContractInfo> {
+        self.find_compiled_contracts(Path::new(PROXIES_FILE))
+            .ok()
+            .and_then(|mut contracts| contracts.remove(name))
+            .ok_or_else(|| CubistSdkError::MissingProxyContract(name.to_owned(), self.target))
+    }
+
+    /// Find the generated multicall contract (see [`crate::gen::multicall`]).
+    pub fn multicall_contract(&self) -> Result<ContractInfo> {
+        self.find_compiled_contracts(Path::new(MULTICALL_FILE))
+            .ok()
+            .and_then(|mut contracts| contracts.remove(MULTICALL_CONTRACT))
+            .ok_or(CubistSdkError::MissingMulticallContract(self.target))
+    }
+
     /// Creates a new instance of a compiler.
-    fn compiler(&self) -> Box<dyn ContractCompiler> {
-        match self.compiler {
-            Compiler::Solc => {
-                Box::new(SolcCompiler::new(&self.compiler_config, &self.target_paths))
-            }
+    fn compiler(&self) -> Result<Box<dyn ContractCompiler>> {
+        Ok(match self.compiler {
+            Compiler::Solc => Box::new(SolcCompiler::new(
+                &self.compiler_config,
+                &self.target_paths,
+            )?),
             Compiler::Solang => Box::new(SolangCompiler),
             Compiler::Soroban => Box::new(SorobanCompiler::new(self.target_paths.clone())),
-        }
+        })
     }
 
     /// Find all compiled contracts (on disk) originating from a given source file.
     fn find_compiled_contracts(&self, source_file: &Path) -> Result<HashMap<String, ContractInfo>> {
-        self.compiler().find_compiled_contracts(source_file)
+        self.compiler()?.find_compiled_contracts(source_file)
+    }
+
+    /// Returns the fee strategy configured for this target (see [`FeeStrategy`])
+    pub fn fee_strategy(&self) -> FeeStrategy {
+        self.network_config
+            .as_ref()
+            .map(|c| c.common().fees.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the account to send transactions from by default, if one is configured for this
+    /// target (see [`default_sender`](cubist_config::network::CommonConfig::default_sender)).
+    pub fn default_sender(&self) -> Option<Address> {
+        self.network_config
+            .as_ref()
+            .and_then(|c| c.common().default_sender)
+    }
+
+    /// Returns the Safe that administers this target, if one is configured (see
+    /// [`safe`](cubist_config::network::CommonConfig::safe)).
+    pub fn safe(&self) -> Option<Address> {
+        self.common_config().and_then(|c| c.safe)
+    }
+
+    /// Returns the network config options common to all chains, if this target has a network
+    /// config.
+    fn common_config(&self) -> Option<&CommonConfig> {
+        self.network_config.as_ref().map(|c| c.common())
+    }
+
+    /// Returns whether transactions sent to this target are simulated first (see
+    /// [`simulate`](cubist_config::network::CommonConfig::simulate)).
+    pub fn simulate(&self) -> bool {
+        self.network_config
+            .as_ref()
+            .map_or(false, |c| c.common().simulate)
+    }
+
+    /// Returns the address that `contract` gets when deployed with `constructor_args`, if CREATE2
+    /// deployments are enabled (see [`Create2Config`]), or [`None`] otherwise.
+    pub fn create2_address<T: Tokenize>(
+        &self,
+        contract: &ContractInfo,
+        constructor_args: T,
+    ) -> Result<Option<Address>> {
+        let (create2, abi, bytes) = match (&self.create2, &contract.data) {
+            (Some(create2), ContractData::SolidityData { abi, bytes, .. }) => (create2, abi, bytes),
+            _ => return Ok(None),
+        };
+        let init_code = init_code(abi, bytes, constructor_args.into_tokens()).map_err(|e| {
+            CubistSdkError::DeployError(contract.fqn.clone(), self.target, Box::new(e))
+        })?;
+        let salt = create2.salt_for(&contract.fqn.name);
+        Ok(Some(ethers::utils::get_create2_address(
+            CREATE2_FACTORY,
+            salt,
+            init_code,
+        )))
     }
 
     /// Create a cubist localchains provider ([`CubistProvider`]) from a
@@ -373,10 +597,45 @@ impl TargetProjectInfo {
     }
 }
 
+/// Local development node whose clock can be manipulated (and that mines blocks on demand), as
+/// identified by its client version (see [`TargetProject::client_version`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DevNode {
+    /// Foundry's anvil (which Cubist starts for Ethereum)
+    Anvil,
+    /// Hardhat Network
+    Hardhat,
+}
+
+impl DevNode {
+    /// Identifies the node from its client version (e.g., `anvil/v0.1.0`), or explains why its
+    /// clock can't be manipulated (e.g., for bor or subnet-evm, which Cubist starts for Polygon
+    /// and Avalanche).
+    fn from_client_version(client_version: &str) -> Result<Self, String> {
+        let client = client_version.split('/').next().unwrap_or_default();
+        match client.to_lowercase().as_str() {
+            "anvil" => Ok(DevNode::Anvil),
+            "hardhatnetwork" => Ok(DevNode::Hardhat),
+            "" => Err("the node doesn't support manipulating time or mining on demand".to_owned()),
+            _ => Err(format!(
+                "{client} doesn't support manipulating time or mining on demand"
+            )),
+        }
+    }
+
+    /// RPC method for mining blocks on demand
+    fn mine_method(self) -> &'static str {
+        match self {
+            DevNode::Anvil => "anvil_mine",
+            DevNode::Hardhat => "hardhat_mine",
+        }
+    }
+}
+
 macro_rules! project_constructor {
     ($project: expr, $provider_fn: expr) => {{
         let url = $project.endpoint_url()?;
-        let provider = $provider_fn(&url, $project.target).await.map_err(|e| {
+        let provider = $provider_fn(&url, &$project).await.map_err(|e| {
             CubistSdkError::CreateClientError($project.target.clone(), url, Some(Box::new(e)))
         })?;
         Ok(Self::new($project, provider)?)
@@ -397,6 +656,22 @@ impl TargetProject<WsStack> {
     }
 }
 
+impl<M: WithSender> TargetProject<M> {
+    /// Returns a copy of this project that sends transactions from `sender` (instead of the
+    /// configured default sender).
+    pub fn with_sender(&self, sender: Address) -> Self {
+        Self {
+            project: Arc::clone(&self.project),
+            provider: Arc::new(self.provider.with_sender(sender)),
+            bridge: self.bridge.clone(),
+            chain_id: OnceCell::new_with(self.chain_id.get().copied()),
+            client_version: OnceCell::new_with(self.client_version.get().cloned()),
+            receipt_options: self.receipt_options,
+            multicall: Mutex::new(self.cached_multicall()),
+        }
+    }
+}
+
 impl<M: Middleware> TargetProject<M> {
     /// Constructor
     pub fn new(project: TargetProjectInfo, provider: M) -> Result<Self> {
@@ -416,9 +691,55 @@ impl<M: Middleware> TargetProject<M> {
             }
         };
         Ok(Self {
-            project,
+            project: Arc::new(project),
             bridge,
             provider: Arc::new(provider),
+            chain_id: OnceCell::new(),
+            client_version: OnceCell::new(),
+            receipt_options: ReceiptOptions::default(),
+            multicall: Mutex::new(None),
+        })
+    }
+
+    /// Returns a copy of this project that waits for transaction receipts according to
+    /// `options` (falling back to the configured settings for unset options).
+    pub fn with_receipt_options(&self, options: ReceiptOptions) -> Self {
+        Self {
+            project: Arc::clone(&self.project),
+            provider: Arc::clone(&self.provider),
+            bridge: self.bridge.clone(),
+            chain_id: OnceCell::new_with(self.chain_id.get().copied()),
+            client_version: OnceCell::new_with(self.client_version.get().cloned()),
+            receipt_options: options,
+            multicall: Mutex::new(self.cached_multicall()),
+        }
+    }
+
+    /// Number of confirmations to wait for (see [`ReceiptOptions::confirmations`]).
+    pub fn confirmations(&self) -> usize {
+        self.receipt_options.confirmations.unwrap_or_else(|| {
+            self.common_config()
+                .map_or(DEFAULT_CONFIRMATIONS, |c| c.confirmations)
+        })
+    }
+
+    /// How long to wait for a transaction to be confirmed (see [`ReceiptOptions::timeout`]).
+    pub fn receipt_timeout(&self) -> Duration {
+        self.receipt_options.timeout.unwrap_or_else(|| {
+            let secs = self
+                .common_config()
+                .map_or(DEFAULT_RECEIPT_TIMEOUT, |c| c.receipt_timeout);
+            Duration::from_secs(secs)
+        })
+    }
+
+    /// How often to poll for transaction receipts (see [`ReceiptOptions::poll_interval`]).
+    pub fn poll_interval(&self) -> Duration {
+        self.receipt_options.poll_interval.unwrap_or_else(|| {
+            let millis = self
+                .common_config()
+                .map_or(DEFAULT_POLL_INTERVAL, |c| c.poll_interval);
+            Duration::from_millis(millis)
         })
     }
 
@@ -427,6 +748,82 @@ impl<M: Middleware> TargetProject<M> {
         Arc::clone(&self.provider)
     }
 
+    /// Id of this chain (retrieved from the chain once and cached).
+    pub async fn chain_id(&self) -> Result<u64> {
+        let chain_id = self
+            .chain_id
+            .get_or_try_init(|| async {
+                self.provider
+                    .get_chainid()
+                    .await
+                    .map(|id| id.as_u64())
+                    .map_err(|e| CubistSdkError::ChainIdError(self.target, format!("{e}")))
+            })
+            .await?;
+        Ok(*chain_id)
+    }
+
+    /// Address of the Multicall3 contract that calls to this target are batched through (see
+    /// [`Batch`](crate::core::Batch)): the configured one (see
+    /// [`multicall`](cubist_config::network::CommonConfig::multicall)), or else, on chains that
+    /// Cubist starts locally, the generated one, which gets deployed first if it isn't already
+    /// (see [`Self::deploy_multicall`]).  On other chains (where deploying costs real funds), the
+    /// canonical Multicall3 contract (see [`CANONICAL_MULTICALL`]) is used if it is deployed.
+    pub async fn multicall(&self) -> Result<Address> {
+        if let Some(address) = self.cached_multicall() {
+            return Ok(address);
+        }
+        let address = match self.common_config().and_then(|c| c.multicall) {
+            Some(address) => address,
+            None if self.project.is_started_locally()? => self.deploy_multicall().await?,
+            None => {
+                let deployed = self
+                    .has_code(CANONICAL_MULTICALL)
+                    .await
+                    .map_err(|e| CubistSdkError::BatchError(self.target, Box::new(e)))?;
+                if !deployed {
+                    return Err(CubistSdkError::NoMulticallContract(self.target));
+                }
+                CANONICAL_MULTICALL
+            }
+        };
+        *self.multicall.lock().or_bug("Multicall lock poisoned") = Some(address);
+        Ok(address)
+    }
+
+    /// Address of the multicall contract, if already resolved (see [`Self::multicall`]).
+    fn cached_multicall(&self) -> Option<Address> {
+        *self.multicall.lock().or_bug("Multicall lock poisoned")
+    }
+
+    /// Forgets the address of the multicall contract, which is resolved again on next use (e.g.,
+    /// after reverting the chain to a snapshot taken before the contract was deployed).
+    pub(crate) fn forget_multicall(&self) {
+        *self.multicall.lock().or_bug("Multicall lock poisoned") = None;
+    }
+
+    /// Deploys the generated multicall contract (see [`crate::gen::multicall`]) via the CREATE2
+    /// factory, unless it's already deployed.  Its address is the same on every chain (for the
+    /// same compiler settings).  Returns that address.  [`Self::multicall`] only calls this on
+    /// chains that Cubist starts locally.
+    pub async fn deploy_multicall(&self) -> Result<Address> {
+        let contract = self.multicall_contract()?;
+        let (abi, bytes) = solidity_code(&contract).or_bug("Multicall is a Solidity contract");
+        let (address, receipt) = self
+            .deploy_create2(abi, bytes, vec![], MULTICALL_SALT)
+            .await
+            .map_err(|e| {
+                CubistSdkError::DeployError(contract.fqn.clone(), self.target, Box::new(e))
+            })?;
+        if receipt.is_some() {
+            debug!(
+                "Deployed {MULTICALL_CONTRACT} on {} at {address:?}",
+                self.target
+            );
+        }
+        Ok(address)
+    }
+
     /// Retrieve all accounts used on this target.
     pub async fn accounts(&self) -> Result<Vec<Address>> {
         let acc = self
@@ -462,24 +859,72 @@ impl<M: Middleware> TargetProject<M> {
         bytes: &Bytes,
         constructor_args: T,
     ) -> Result<ethers::contract::Contract<M>> {
+        let (deployed, receipt) = self
+            .deploy_evm_unsaved(
+                fqn,
+                abi,
+                bytes,
+                constructor_args.into_tokens(),
+                Some(&fqn.name),
+            )
+            .await?;
+        self.save_receipt(fqn, deployed.address(), &receipt).await?;
+        Ok(deployed)
+    }
+
+    /// Deploys an EVM contract without saving its deployment receipt. Returns the deployed
+    /// contract and the receipt of its deployment. When CREATE2 deployments are enabled, the
+    /// salt configured for contract `salt_name` is used, unless `salt_name` is [`None`] (in
+    /// which case the contract is deployed with a regular transaction).
+    async fn deploy_evm_unsaved(
+        &self,
+        fqn: &ContractFQN,
+        abi: &Abi,
+        bytes: &Bytes,
+        args: Vec<Token>,
+        salt_name: Option<&str>,
+    ) -> Result<(EthersContract<M>, TransactionReceipt)> {
         // TODO: the user should be able to specify 'gas' setting; otherwise, leave it empty
         //       instead of calling 'estimate_gas', because the estimation can be wrong
         let factory = ContractFactory::new(abi.to_owned(), bytes.to_owned(), self.provider());
 
         let deploy_err = |e| CubistSdkError::DeployError(fqn.clone(), self.target, Box::new(e));
 
-        let args = constructor_args.into_tokens();
         debug!("Deploying {}({:?}) to {}", fqn.name, &args, self.target,);
-        let deployer = factory
-            .deploy(Token::Tuple(args))
-            .map_err(|e| WrapperError::ContractError(e.to_string()))
-            .map_err(deploy_err)?;
-        let receipt = self.send_tx(deployer.tx).await.map_err(deploy_err)?;
-        let address = receipt.contract_address.ok_or_else(|| {
-            deploy_err(WrapperError::ContractError(
-                "No contract address".to_owned(),
-            ))
-        })?;
+        let salt = self
+            .create2
+            .as_ref()
+            .zip(salt_name)
+            .map(|(create2, salt_name)| create2.salt_for(salt_name));
+        let (address, receipt) = match salt {
+            Some(salt) => {
+                let (address, receipt) = self
+                    .deploy_create2(abi, bytes, args, salt)
+                    .await
+                    .map_err(deploy_err)?;
+                // If the contract is already deployed (e.g., by a run with a different deploy
+                // dir), there is no transaction to record, so we record a receipt with just its
+                // address (which is all `Self::deployed` needs to load it)
+                let receipt = receipt.unwrap_or_else(|| TransactionReceipt {
+                    contract_address: Some(address),
+                    ..Default::default()
+                });
+                (address, receipt)
+            }
+            None => {
+                let deployer = factory
+                    .deploy(Token::Tuple(args))
+                    .map_err(|e| WrapperError::ContractError(e.to_string()))
+                    .map_err(deploy_err)?;
+                let receipt = self.send_tx(deployer.tx).await.map_err(deploy_err)?;
+                let address = receipt.contract_address.ok_or_else(|| {
+                    deploy_err(WrapperError::ContractError(
+                        "No contract address".to_owned(),
+                    ))
+                })?;
+                (address, receipt)
+            }
+        };
         let deployed = ethers::contract::Contract::new(address, abi.clone(), self.provider());
 
         debug!(
@@ -489,9 +934,320 @@ impl<M: Middleware> TargetProject<M> {
             deployed.address()
         );
 
-        self.save_receipt(fqn, deployed.address(), &receipt).await?;
+        Ok((deployed, receipt))
+    }
 
-        Ok(deployed)
+    /// Returns the transaction that [`Self::deploy`] sends to deploy `contract` with
+    /// `constructor_args` (a call to the CREATE2 factory if CREATE2 deployments are enabled),
+    /// without sending it.  For upgradeable contracts, this is the deployment of the
+    /// implementation.
+    pub async fn deployment_tx<T: Tokenize>(
+        &self,
+        contract: &ContractInfo,
+        constructor_args: T,
+    ) -> Result<TypedTransaction> {
+        let fqn = &contract.fqn;
+        let deploy_err = |e| CubistSdkError::DeployError(fqn.clone(), self.target, Box::new(e));
+        let (abi, bytes) = solidity_code(contract).ok_or_else(|| {
+            deploy_err(WrapperError::ContractError(
+                "Only Solidity contracts are supported".to_owned(),
+            ))
+        })?;
+        let init_code =
+            init_code(abi, bytes, constructor_args.into_tokens()).map_err(deploy_err)?;
+        let tx = match &self.create2 {
+            Some(create2) => TransactionRequest::new()
+                .to(CREATE2_FACTORY)
+                .data([&create2.salt_for(&fqn.name)[..], &init_code[..]].concat()),
+            None => TransactionRequest::new().data(init_code),
+        };
+        let tx = match self.provider.default_sender() {
+            Some(sender) => tx.from(sender),
+            None => tx,
+        };
+        Ok(tx.into())
+    }
+
+    /// Deploys an upgradeable contract: its implementation (constructed with `constructor_args`)
+    /// and a proxy of the given `kind` in front of it, which is initialized by delegating
+    /// `init_data` (e.g., an encoded call to an initializer) to the implementation.
+    ///
+    /// The receipt of the proxy deployment is saved as the contract's deployment receipt, so
+    /// [`Self::deployed`] loads the proxy (with the implementation's abi). Returns the proxy and
+    /// the info to record in the contract's [`DeploymentManifest`].
+    pub async fn deploy_proxied<T: Tokenize>(
+        &self,
+        contract: &ContractInfo,
+        kind: ProxyKind,
+        constructor_args: T,
+        init_data: Bytes,
+    ) -> Result<(EthersContract<M>, ProxyDeployment)> {
+        let fqn = &contract.fqn;
+        let deploy_err = |e| CubistSdkError::DeployError(fqn.clone(), self.target, Box::new(e));
+        let (abi, bytes) = solidity_code(contract)
+            .ok_or_else(|| CubistSdkError::NotUpgradeable(fqn.clone(), self.target))?;
+        if kind == ProxyKind::Uups && uups_upgrade_function(abi).is_none() {
+            return Err(deploy_err(missing_uups_upgrade_function()));
+        }
+
+        let (implementation, _) = self
+            .deploy_evm_unsaved(
+                fqn,
+                abi,
+                bytes,
+                constructor_args.into_tokens(),
+                Some(&fqn.name),
+            )
+            .await?;
+        let implementation = implementation.address();
+
+        let (proxy_info, admin, args) = match kind {
+            ProxyKind::Uups => (
+                self.proxy_contract(UUPS_PROXY_CONTRACT)?,
+                None,
+                vec![
+                    Token::Address(implementation),
+                    Token::Bytes(init_data.to_vec()),
+                ],
+            ),
+            ProxyKind::Transparent => {
+                let admin_info = self.proxy_contract(PROXY_ADMIN_CONTRACT)?;
+                let (admin_abi, admin_bytes) =
+                    solidity_code(&admin_info).or_bug("Proxy admin is a Solidity contract");
+                // The owner is passed explicitly since the deployer may be the CREATE2 factory
+                let owner = self.sender().await?;
+                let (admin, _) = self
+                    .deploy_evm_unsaved(
+                        &admin_info.fqn,
+                        admin_abi,
+                        admin_bytes,
+                        vec![Token::Address(owner)],
+                        Some(PROXY_ADMIN_CONTRACT),
+                    )
+                    .await?;
+                (
+                    self.proxy_contract(TRANSPARENT_PROXY_CONTRACT)?,
+                    Some(admin.address()),
+                    vec![
+                        Token::Address(implementation),
+                        Token::Address(admin.address()),
+                        Token::Bytes(init_data.to_vec()),
+                    ],
+                )
+            }
+        };
+        let (proxy_abi, proxy_bytes) =
+            solidity_code(&proxy_info).or_bug("Proxy is a Solidity contract");
+        // The proxy delegates `init_data` to the implementation from its constructor, so it is
+        // deployed with a regular transaction: via the CREATE2 factory, the initializer would
+        // see the factory (rather than the sender) as `msg.sender` (e.g., making it the owner)
+        let (proxy, receipt) = self
+            .deploy_evm_unsaved(&proxy_info.fqn, proxy_abi, proxy_bytes, args, None)
+            .await?;
+        self.save_receipt(fqn, proxy.address(), &receipt).await?;
+
+        let deployment = ProxyDeployment {
+            kind,
+            implementation,
+            admin,
+            storage_layout: contract
+                .solidity_artifacts()
+                .and_then(|a| a.storage_layout.clone()),
+        };
+        Ok((self.at(contract, proxy.address()), deployment))
+    }
+
+    /// Upgrades the proxy at address `proxy` (described by `deployment`) to a new implementation
+    /// of `contract` (constructed with `constructor_args`).
+    ///
+    /// Before deploying the new implementation, checks that its storage layout is compatible
+    /// with the storage layout of the current implementation (see
+    /// [`storage_layout_incompatibilities`]). Returns the updated proxy info.
+    pub async fn upgrade_proxy<T: Tokenize>(
+        &self,
+        contract: &ContractInfo,
+        proxy: Address,
+        deployment: &ProxyDeployment,
+        constructor_args: T,
+    ) -> Result<ProxyDeployment> {
+        let fqn = &contract.fqn;
+        let upgrade_err = |e| CubistSdkError::UpgradeError(fqn.clone(), self.target, Box::new(e));
+        let (abi, bytes) = solidity_code(contract)
+            .ok_or_else(|| CubistSdkError::NotUpgradeable(fqn.clone(), self.target))?;
+
+        // Check storage layout compatibility
+        let missing_layout = |reason: &str| {
+            CubistSdkError::MissingStorageLayout(fqn.clone(), self.target, reason.to_owned())
+        };
+        let old_layout = deployment
+            .storage_layout
+            .as_ref()
+            .ok_or_else(|| missing_layout("not recorded in the deployment manifest"))?;
+        let new_layout = contract
+            .solidity_artifacts()
+            .and_then(|a| a.storage_layout.as_ref())
+            .ok_or_else(|| missing_layout("not found in the compiler output"))?;
+        let errors = storage_layout_incompatibilities(old_layout, new_layout);
+        if !errors.is_empty() {
+            return Err(CubistSdkError::IncompatibleStorageLayout(
+                fqn.clone(),
+                self.target,
+                errors,
+            ));
+        }
+
+        // UUPS proxies are upgraded through the implementation, so the new implementation must
+        // support upgrades as well (or else the proxy could never be upgraded again)
+        let uups_function = uups_upgrade_function(abi);
+        if deployment.kind == ProxyKind::Uups && uups_function.is_none() {
+            return Err(upgrade_err(missing_uups_upgrade_function()));
+        }
+
+        let (implementation, _) = self
+            .deploy_evm_unsaved(
+                fqn,
+                abi,
+                bytes,
+                constructor_args.into_tokens(),
+                Some(&fqn.name),
+            )
+            .await?;
+        let implementation = implementation.address();
+
+        let call = match (deployment.kind, uups_function) {
+            (ProxyKind::Uups, Some("upgradeTo")) => self
+                .at(contract, proxy)
+                .method::<_, ()>("upgradeTo", implementation),
+            (ProxyKind::Uups, _) => self
+                .at(contract, proxy)
+                .method::<_, ()>("upgradeToAndCall", (implementation, Bytes::default())),
+            (ProxyKind::Transparent, _) => {
+                let admin = deployment.admin.ok_or_else(|| {
+                    upgrade_err(WrapperError::ContractError(
+                        "No proxy admin recorded in the deployment manifest".to_owned(),
+                    ))
+                })?;
+                self.at(&self.proxy_contract(PROXY_ADMIN_CONTRACT)?, admin)
+                    .method::<_, ()>("upgradeAndCall", (proxy, implementation, Bytes::default()))
+            }
+        }
+        .map_err(|e| upgrade_err(WrapperError::ContractError(e.to_string())))?;
+        let receipt = self.send_tx(call.tx).await.map_err(upgrade_err)?;
+        if receipt.status == Some(U64::zero()) {
+            return Err(upgrade_err(WrapperError::ContractError(format!(
+                "Upgrade transaction {:?} reverted",
+                receipt.transaction_hash
+            ))));
+        }
+        debug!(
+            "Upgraded {} on {} to {implementation:?}",
+            fqn.name, self.target
+        );
+
+        Ok(ProxyDeployment {
+            kind: deployment.kind,
+            implementation,
+            admin: deployment.admin,
+            storage_layout: Some(new_layout.clone()),
+        })
+    }
+
+    /// Deploys an EVM contract via the canonical CREATE2 factory (see [`CREATE2_FACTORY`]),
+    /// unless it's already deployed (e.g., by a previous run).  Returns the address of the
+    /// contract and the receipt of the deployment transaction (if one was sent).
+    async fn deploy_create2(
+        &self,
+        abi: &Abi,
+        bytes: &Bytes,
+        args: Vec<Token>,
+        salt: [u8; 32],
+    ) -> Result<(Address, Option<TransactionReceipt>), WrapperError> {
+        let init_code = init_code(abi, bytes, args)?;
+        let address = ethers::utils::get_create2_address(CREATE2_FACTORY, salt, init_code.clone());
+        if self.has_code(address).await? {
+            debug!("Contract already deployed at {address:?}");
+            return Ok((address, None));
+        }
+        if !self.has_code(CREATE2_FACTORY).await? {
+            return Err(WrapperError::ContractError(format!(
+                "CREATE2 factory not deployed at {CREATE2_FACTORY:?}"
+            )));
+        }
+
+        // The factory expects the salt followed by the init code
+        let data = [&salt[..], &init_code[..]].concat();
+        let tx = TransactionRequest::new().to(CREATE2_FACTORY).data(data);
+        let mut receipt = self.send_tx(tx.into()).await?;
+        if !self.has_code(address).await? {
+            return Err(WrapperError::ContractError(format!(
+                "CREATE2 deployment to {address:?} failed (transaction {:?})",
+                receipt.transaction_hash
+            )));
+        }
+
+        // The transaction is a call to the factory, so we record the address of the deployed
+        // contract in the receipt ourselves (so that the contract can be loaded from it later)
+        receipt.contract_address = Some(address);
+        Ok((address, Some(receipt)))
+    }
+
+    /// Whether there is any code deployed at `address`.
+    pub(crate) async fn has_code(&self, address: Address) -> Result<bool, WrapperError> {
+        let code = self
+            .provider
+            .get_code(address, None)
+            .await
+            .map_err(|e| WrapperError::ProviderError("get_code".to_owned(), e.to_string()))?;
+        Ok(!code.is_empty())
+    }
+
+    /// Estimates the gas that sending `tx` would use.
+    pub async fn estimate_gas(&self, tx: &TypedTransaction) -> Result<U256, WrapperError> {
+        self.provider
+            .estimate_gas(tx, None)
+            .await
+            .map_err(|e| WrapperError::ProviderError("estimate_gas".to_owned(), e.to_string()))
+    }
+
+    /// Returns the current gas price (in wei).
+    pub async fn gas_price(&self) -> Result<U256, WrapperError> {
+        self.provider
+            .get_gas_price()
+            .await
+            .map_err(|e| WrapperError::ProviderError("get_gas_price".to_owned(), e.to_string()))
+    }
+
+    /// Returns the price (in wei) per unit of gas that transactions sent to this chain pay at
+    /// most, according to its [`FeeStrategy`]: the max fee per gas of EIP-1559 transactions, or
+    /// the gas price of legacy transactions (as set when sending them, see [`HttpStack`]).
+    pub async fn max_fee_per_gas(&self) -> Result<U256, WrapperError> {
+        let estimate_fees = || async {
+            self.provider
+                .estimate_eip1559_fees(None)
+                .await
+                .map(|(max_fee_per_gas, _)| max_fee_per_gas)
+                .map_err(|e| {
+                    WrapperError::ProviderError("estimate_eip1559_fees".to_owned(), e.to_string())
+                })
+        };
+        match self.fee_strategy() {
+            FeeStrategy::Legacy => Ok(std::cmp::max(
+                self.gas_price().await?,
+                estimate_fees().await?,
+            )),
+            FeeStrategy::Eip1559 => estimate_fees().await,
+            FeeStrategy::Fixed {
+                max_fee_per_gas, ..
+            } => Ok(max_fee_per_gas.into()),
+        }
+    }
+
+    /// Returns the balance (in wei) of `address`.
+    pub async fn balance(&self, address: Address) -> Result<U256, WrapperError> {
+        self.provider
+            .get_balance(address, None)
+            .await
+            .map_err(|e| WrapperError::ProviderError("get_balance".to_owned(), e.to_string()))
     }
 
     /// Deploy a contract. This method currently only supports the deployment of Solidity contracts
@@ -502,7 +1258,7 @@ impl<M: Middleware> TargetProject<M> {
         constructor_args: T,
     ) -> Result<ethers::contract::Contract<M>> {
         match &contract.data {
-            ContractData::SolidityData { abi, bytes } => {
+            ContractData::SolidityData { abi, bytes, .. } => {
                 self.deploy_evm(&contract.fqn, abi, bytes, constructor_args)
                     .await
             }
@@ -563,29 +1319,246 @@ impl<M: Middleware> TargetProject<M> {
         Ok(bridge)
     }
 
-    /// Submits a given transaction and waits for a receipts
+    /// Submits a given transaction and waits until it is confirmed (see [`Self::wait_for_receipt`])
     pub async fn send_tx(&self, tx: TypedTransaction) -> Result<TransactionReceipt, WrapperError> {
-        let send_err = |e| WrapperError::SendTransactionError(format!("{tx:?}"), self.target, e);
-
         let provider = self.provider();
         let pending_tx = provider
             .send_transaction(tx.clone(), None)
             .await
-            .map_err(|e| send_err(e.to_string()))?;
+            .map_err(|e| {
+                WrapperError::SendTransactionError(format!("{tx:?}"), self.target, e.to_string())
+            })?;
+        self.wait_for_receipt(pending_tx.tx_hash()).await
+    }
 
-        let tx_hash = pending_tx.tx_hash();
-        let receipt = pending_tx.await.map_err(|e| send_err(e.to_string()))?;
+    /// Fills in everything needed to sign `tx` offline (see [`TxBundle`](crate::core::TxBundle)):
+    /// the sender (unless set), the next `nonce` of the sender, the chain id, the gas (estimated,
+    /// unless set), and the fees (according to the target's [`FeeStrategy`]).
+    pub async fn fill_unsigned_tx(
+        &self,
+        tx: &mut TypedTransaction,
+        nonce: U256,
+    ) -> Result<(), WrapperError> {
+        if tx.from().is_none() {
+            if let Some(sender) = self.provider.default_sender() {
+                tx.set_from(sender);
+            }
+        }
+        tx.set_nonce(nonce);
+        tx.set_chain_id(
+            self.chain_id().await.map_err(|e| {
+                WrapperError::ProviderError("eth_chainId".to_owned(), e.to_string())
+            })?,
+        );
+        self.provider
+            .fill_transaction(tx, None)
+            .await
+            .map_err(|e| WrapperError::ProviderError("fill_transaction".to_owned(), e.to_string()))
+    }
 
-        // poll manually if receipt is empty
-        let receipt = if receipt.is_none() {
-            let delays = repeat(Duration::from_secs(1)).take(60);
-            self.poll_tx(tx_hash, delays).await?
-        } else {
-            receipt
-        };
+    /// Returns the nonce of the next transaction of `address` (counting pending transactions).
+    pub async fn pending_nonce(&self, address: Address) -> Result<U256, WrapperError> {
+        self.provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| {
+                WrapperError::ProviderError("get_transaction_count".to_owned(), e.to_string())
+            })
+    }
+
+    /// Submits a signed (RLP-encoded) transaction and waits until it is confirmed (see
+    /// [`Self::wait_for_receipt`]).  If the transaction was submitted before (e.g., by an
+    /// interrupted broadcast), just waits for it.
+    pub async fn send_raw_tx(&self, signed: &Bytes) -> Result<TransactionReceipt, WrapperError> {
+        let tx_hash = H256::from(ethers::utils::keccak256(signed));
+        let known = self.provider.get_transaction(tx_hash).await.map_err(|e| {
+            WrapperError::ProviderError("get_transaction".to_owned(), e.to_string())
+        })?;
+        if known.is_none() {
+            self.provider
+                .send_raw_transaction(signed.clone())
+                .await
+                .map_err(|e| {
+                    WrapperError::SendTransactionError(
+                        format!("{tx_hash:?}"),
+                        self.target,
+                        e.to_string(),
+                    )
+                })?;
+        }
+        self.wait_for_receipt(tx_hash).await
+    }
 
-        let receipt = receipt.ok_or_else(|| send_err("No receipt".to_owned()))?;
-        Ok(receipt)
+    /// Waits until transaction `tx_hash` has the configured number of confirmations (see
+    /// [`Self::confirmations`]) and returns its receipt.  Gives up with
+    /// [`WrapperError::ReceiptTimeout`] once the configured timeout expires (see
+    /// [`Self::receipt_timeout`]), after which waiting can be resumed by calling this again.
+    pub async fn wait_for_receipt(
+        &self,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, WrapperError> {
+        let timeout = self.receipt_timeout();
+        tokio::time::timeout(timeout, self.poll_until_confirmed(tx_hash))
+            .await
+            .map_err(|_| WrapperError::ReceiptTimeout(tx_hash, self.target, timeout))?
+    }
+
+    /// Polls for the receipt of transaction `tx_hash` until the transaction has the configured
+    /// number of confirmations.
+    async fn poll_until_confirmed(
+        &self,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, WrapperError> {
+        let confirmations = self.confirmations() as u64;
+        loop {
+            // the receipt is fetched again on every poll, in case the transaction got reorged
+            let receipt = self
+                .provider
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(|e| {
+                    WrapperError::TransactionNotFound(tx_hash, self.target, e.to_string())
+                })?;
+            if let Some(receipt) = receipt {
+                let block = receipt.block_number.unwrap_or_default().as_u64();
+                if confirmations <= 1 || self.block_number().await? + 1 >= block + confirmations {
+                    return Ok(receipt);
+                }
+                trace!("Waiting for {confirmations} confirmations of tx {tx_hash:?}");
+            }
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Current block number of this chain.
+    async fn block_number(&self) -> Result<u64, WrapperError> {
+        self.provider
+            .get_block_number()
+            .await
+            .map(|n| n.as_u64())
+            .map_err(|e| WrapperError::ProviderError("get_block_number".to_owned(), e.to_string()))
+    }
+
+    /// Simulates a transaction (with `eth_call` against the latest block) and returns its revert
+    /// data if it would revert, or [`None`] if it would succeed.
+    pub async fn simulate_tx(&self, tx: &TypedTransaction) -> Result<Option<Bytes>, WrapperError> {
+        let call_err = |e: String| WrapperError::ProviderError("eth_call".to_owned(), e);
+        let mut tx = tx.clone();
+        if tx.from().is_none() {
+            if let Some(sender) = self.provider.default_sender() {
+                tx.set_from(sender);
+            }
+        }
+        // call the endpoint directly, since errors returned through the middleware stack don't
+        // expose the revert data
+        let url = self.endpoint_url().map_err(|e| call_err(e.to_string()))?;
+        let url = url.expose_url_and_update(Some(to_http_scheme(url.scheme())), None, None)?;
+        let client = Http::new(url);
+        match client
+            .request::<_, Bytes>("eth_call", serde_json::json!([tx, "latest"]))
+            .await
+        {
+            Ok(_) => Ok(None),
+            Err(HttpClientError::JsonRpcError(e)) => match RevertReason::data_from_rpc_error(&e) {
+                Some(data) => Ok(Some(data)),
+                None => Err(call_err(e.to_string())),
+            },
+            Err(e) => Err(call_err(e.to_string())),
+        }
+    }
+
+    /// Snapshots the state of this chain (with `evm_snapshot`) and returns the id of the
+    /// snapshot.  Fails on chains that don't support snapshots.
+    pub async fn evm_snapshot(&self) -> Result<U256, WrapperError> {
+        self.provider
+            .provider()
+            .request("evm_snapshot", ())
+            .await
+            .map_err(|e| WrapperError::ProviderError("evm_snapshot".to_owned(), e.to_string()))
+    }
+
+    /// Reverts the state of this chain to snapshot `id` (see [`Self::evm_snapshot`]), which
+    /// can't be reverted to again.  Returns whether the snapshot was found.
+    pub async fn evm_revert(&self, id: U256) -> Result<bool, WrapperError> {
+        let reverted = self
+            .provider
+            .provider()
+            .request("evm_revert", [id])
+            .await
+            .map_err(|e| WrapperError::ProviderError("evm_revert".to_owned(), e.to_string()))?;
+        self.forget_multicall();
+        Ok(reverted)
+    }
+
+    /// Name and version of the node serving this chain (as returned by `web3_clientVersion`,
+    /// e.g., `anvil/v0.1.0`).
+    pub async fn client_version(&self) -> Result<String, WrapperError> {
+        let version = self
+            .client_version
+            .get_or_try_init(|| async {
+                self.provider
+                    .provider()
+                    .request("web3_clientVersion", ())
+                    .await
+                    .map_err(|e| {
+                        WrapperError::ProviderError("web3_clientVersion".to_owned(), e.to_string())
+                    })
+            })
+            .await?;
+        Ok(version.clone())
+    }
+
+    /// Checks that the clock of this chain can be manipulated (and blocks mined on demand), which
+    /// is only the case for local development nodes (see [`DevNode`]), and returns the node.
+    pub(crate) async fn check_time_control(&self) -> Result<DevNode> {
+        if self.target == Target::Stellar {
+            return Err(CubistSdkError::TimeControlUnsupported(
+                self.target,
+                "manipulating time and mining are not supported on Stellar".to_owned(),
+            ));
+        }
+        let client_version = self
+            .client_version()
+            .await
+            .map_err(|e| CubistSdkError::TimeControlError(self.target, Box::new(e)))?;
+        DevNode::from_client_version(&client_version)
+            .map_err(|reason| CubistSdkError::TimeControlUnsupported(self.target, reason))
+    }
+
+    /// Calls RPC `method` for manipulating time (or mining) with `params`, after checking that
+    /// this chain supports it (see [`Self::check_time_control`]).
+    async fn time_control_rpc(&self, method: &str, params: [U256; 1]) -> Result<()> {
+        self.check_time_control().await?;
+        self.provider
+            .provider()
+            .request::<_, serde_json::Value>(method, params)
+            .await
+            .map_err(|e| {
+                let e = WrapperError::ProviderError(method.to_owned(), e.to_string());
+                CubistSdkError::TimeControlError(self.target, Box::new(e))
+            })?;
+        Ok(())
+    }
+
+    /// Moves the clock of this chain forward by `seconds` (with `evm_increaseTime`), which
+    /// takes effect with the next block.  Fails on chains that don't support it.
+    pub async fn increase_time(&self, seconds: u64) -> Result<()> {
+        self.time_control_rpc("evm_increaseTime", [U256::from(seconds)])
+            .await
+    }
+
+    /// Sets the timestamp of the next block of this chain (with `evm_setNextBlockTimestamp`).
+    /// Fails on chains that don't support it.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<()> {
+        self.time_control_rpc("evm_setNextBlockTimestamp", [U256::from(timestamp)])
+            .await
+    }
+
+    /// Mines `blocks` (empty) blocks on this chain (with `anvil_mine` or `hardhat_mine`).  Fails
+    /// on chains that don't support it.
+    pub async fn mine_blocks(&self, blocks: u64) -> Result<()> {
+        let method = self.check_time_control().await?.mine_method();
+        self.time_control_rpc(method, [U256::from(blocks)]).await
     }
 
     /// Repeatedly tries to fetch the transaction receipt for a given transaction hash.
@@ -615,15 +1588,17 @@ impl<M: Middleware> TargetProject<M> {
         Ok(None)
     }
 
-    async fn save_receipt(
+    pub(crate) async fn save_receipt(
         &self,
         contract: &ContractFQN,
         address: Address,
         receipt: &TransactionReceipt,
     ) -> Result<()> {
-        let path = self
-            .target_paths
-            .for_deployment_receipt(contract, address.as_fixed_bytes());
+        let path = self.target_paths.for_deployment_receipt(
+            self.chain_id().await?,
+            contract,
+            address.as_fixed_bytes(),
+        );
         let parent_dir = path.parent().or_bug("Must have a parent dir");
 
         async {
@@ -642,7 +1617,9 @@ impl<M: Middleware> TargetProject<M> {
     }
 
     async fn find_receipts(&self, contract: &ContractFQN) -> Result<Vec<TransactionReceipt>> {
-        let dir = self.target_paths.deployment_receipts_dir(contract);
+        let dir = self
+            .target_paths
+            .deployment_receipts_dir(self.chain_id().await?, contract);
         if !dir.is_dir() {
             return Ok(vec![]);
         }
@@ -695,7 +1672,7 @@ fn validator(
     compiler_config: &CompilerConfig,
 ) -> Result<Box<dyn ContractCompiler>> {
     match compiler {
-        Compiler::Solc => Ok(Box::new(SolcCompiler::new_validator(compiler_config))),
+        Compiler::Solc => Ok(Box::new(SolcCompiler::new_validator(compiler_config)?)),
         Compiler::Solang => todo!(),
         Compiler::Soroban => todo!(),
     }
@@ -726,3 +1703,33 @@ fn to_ws_scheme(scheme: &str) -> &str {
         s => s,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dev_node_from_client_version() {
+        assert_eq!(
+            Ok(DevNode::Anvil),
+            DevNode::from_client_version("anvil/v0.1.0")
+        );
+        assert_eq!(
+            Ok(DevNode::Hardhat),
+            DevNode::from_client_version("HardhatNetwork/2.12.2/@ethereumjs/vm/5.9.3")
+        );
+        assert_eq!("anvil_mine", DevNode::Anvil.mine_method());
+        assert_eq!("hardhat_mine", DevNode::Hardhat.mine_method());
+
+        // the nodes Cubist starts for Polygon and Avalanche (and public nodes) are not supported
+        for (client_version, client) in [
+            ("bor/v0.3.3-stable/linux-amd64/go1.19.4", "bor"),
+            ("subnet-evm/v0.4.8", "subnet-evm"),
+            ("Geth/v1.10.26-stable/linux-amd64/go1.18.5", "Geth"),
+        ] {
+            let reason = DevNode::from_client_version(client_version).unwrap_err();
+            assert!(reason.starts_with(&format!("{client} doesn't")), "{reason}");
+        }
+        assert!(DevNode::from_client_version("").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+
+use ethers::abi::{decode, Abi, ParamType, Token};
+use ethers::providers::JsonRpcError;
+use ethers::types::{Bytes, U256};
+use serde_json::Value;
+
+/// Selector of `Error(string)`, which `revert("...")` and `require(..., "...")` revert with.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)`, which failed assertions, arithmetic overflows, etc. revert with.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Why a transaction (or call) reverted, decoded from its revert data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RevertReason {
+    /// Reverted with a message, i.e., `Error(string)`
+    Error(String),
+    /// Reverted with a panic code, i.e., `Panic(uint256)`
+    Panic(U256),
+    /// Reverted with a custom error declared in the contract's abi
+    Custom {
+        /// Error name
+        name: String,
+        /// Error arguments
+        args: Vec<Token>,
+    },
+    /// Reverted without any data (e.g., `revert()` or `require(...)` without a message)
+    Empty,
+    /// Reverted with data that could not be decoded
+    Unknown(Bytes),
+}
+
+impl RevertReason {
+    /// Decodes revert data, looking up custom errors in `abi` (if given).
+    pub fn decode(data: &[u8], abi: Option<&Abi>) -> Self {
+        if data.is_empty() {
+            return RevertReason::Empty;
+        }
+        let unknown = || RevertReason::Unknown(Bytes::from(data.to_vec()));
+        if data.len() < 4 {
+            return unknown();
+        }
+        let (selector, args) = data.split_at(4);
+        if selector == ERROR_SELECTOR {
+            if let Ok(Some(Token::String(message))) =
+                decode(&[ParamType::String], args).map(|mut t| t.pop())
+            {
+                return RevertReason::Error(message);
+            }
+        } else if selector == PANIC_SELECTOR {
+            if let Ok(Some(Token::Uint(code))) =
+                decode(&[ParamType::Uint(256)], args).map(|mut t| t.pop())
+            {
+                return RevertReason::Panic(code);
+            }
+        } else if let Some(abi) = abi {
+            let error = abi
+                .errors()
+                .find(|e| e.signature().as_bytes().starts_with(selector));
+            if let Some(error) = error {
+                if let Ok(args) = error.decode(args) {
+                    return RevertReason::Custom {
+                        name: error.name.clone(),
+                        args,
+                    };
+                }
+            }
+        }
+        unknown()
+    }
+
+    /// Returns the revert data carried by a JSON-RPC error, or [`None`] if the error is not
+    /// about a revert.
+    pub fn data_from_rpc_error(error: &JsonRpcError) -> Option<Bytes> {
+        // nodes return revert data either as a string or nested in an object
+        let data = match &error.data {
+            Some(Value::String(data)) => Some(data.as_str()),
+            Some(Value::Object(obj)) => obj.get("data").and_then(Value::as_str),
+            _ => None,
+        };
+        match data.and_then(|d| d.parse::<Bytes>().ok()) {
+            Some(data) => Some(data),
+            None if error.message.contains("revert") => Some(Bytes::default()),
+            None => None,
+        }
+    }
+}
+
+/// Describes a panic code (see the Solidity docs on "Panic via assert and Error via require").
+fn panic_description(code: U256) -> Option<&'static str> {
+    let description = match code.low_u64() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => return None,
+    };
+    (code <= U256::from(0x51)).then_some(description)
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Error(message) => write!(f, "'{message}'"),
+            RevertReason::Panic(code) => match panic_description(*code) {
+                Some(description) => write!(f, "panic 0x{code:02x} ({description})"),
+                None => write!(f, "panic 0x{code:02x}"),
+            },
+            RevertReason::Custom { name, args } => {
+                let args: Vec<_> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{name}({})", args.join(", "))
+            }
+            RevertReason::Empty => write!(f, "no reason given"),
+            RevertReason::Unknown(data) => write!(f, "unknown revert data {data}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{encode, AbiError, Param};
+    use serde_json::json;
+
+    fn revert_data(selector: &[u8], args: &[Token]) -> Vec<u8> {
+        [selector, encode(args).as_slice()].concat()
+    }
+
+    #[test]
+    fn test_decode_builtin_errors() {
+        let data = revert_data(&ERROR_SELECTOR, &[Token::String("not enough".to_owned())]);
+        let reason = RevertReason::decode(&data, None);
+        assert_eq!(RevertReason::Error("not enough".to_owned()), reason);
+        assert_eq!("'not enough'", reason.to_string());
+
+        let data = revert_data(&PANIC_SELECTOR, &[Token::Uint(U256::from(0x11))]);
+        let reason = RevertReason::decode(&data, None);
+        assert_eq!(RevertReason::Panic(U256::from(0x11)), reason);
+        assert_eq!(
+            "panic 0x11 (arithmetic overflow or underflow)",
+            reason.to_string()
+        );
+
+        assert_eq!(RevertReason::Empty, RevertReason::decode(&[], None));
+        assert_eq!(
+            RevertReason::Unknown(Bytes::from(vec![1, 2])),
+            RevertReason::decode(&[1, 2], None)
+        );
+    }
+
+    #[test]
+    fn test_decode_custom_error() {
+        let error = AbiError {
+            name: "InsufficientBalance".to_owned(),
+            inputs: vec![
+                Param {
+                    name: "available".to_owned(),
+                    kind: ParamType::Uint(256),
+                    internal_type: None,
+                },
+                Param {
+                    name: "required".to_owned(),
+                    kind: ParamType::Uint(256),
+                    internal_type: None,
+                },
+            ],
+        };
+        let mut abi: Abi = serde_json::from_value(json!([])).unwrap();
+        abi.errors.insert(error.name.clone(), vec![error.clone()]);
+
+        let args = [Token::Uint(U256::from(1)), Token::Uint(U256::from(2))];
+        let data = revert_data(&error.signature().as_bytes()[..4], &args);
+        let reason = RevertReason::decode(&data, Some(&abi));
+        assert_eq!(
+            RevertReason::Custom {
+                name: "InsufficientBalance".to_owned(),
+                args: args.to_vec(),
+            },
+            reason
+        );
+        assert_eq!("InsufficientBalance(1, 2)", reason.to_string());
+
+        // without the abi, custom errors can't be decoded
+        assert!(matches!(
+            RevertReason::decode(&data, None),
+            RevertReason::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn test_data_from_rpc_error() {
+        let error = |message: &str, data: Option<Value>| JsonRpcError {
+            code: 3,
+            message: message.to_owned(),
+            data,
+        };
+        assert_eq!(
+            Some(Bytes::from(vec![0xab, 0xcd])),
+            RevertReason::data_from_rpc_error(&error("execution reverted", Some(json!("0xabcd"))))
+        );
+        assert_eq!(
+            Some(Bytes::from(vec![0xab])),
+            RevertReason::data_from_rpc_error(&error(
+                "VM Exception while processing transaction: revert",
+                Some(json!({ "data": "0xab" }))
+            ))
+        );
+        assert_eq!(
+            Some(Bytes::default()),
+            RevertReason::data_from_rpc_error(&error("execution reverted", None))
+        );
+        assert_eq!(
+            None,
+            RevertReason::data_from_rpc_error(&error("insufficient funds for gas", None))
+        );
+    }
+}
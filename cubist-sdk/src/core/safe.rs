@@ -0,0 +1,274 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cubist_config::paths::ContractFQN;
+use cubist_config::Target;
+use ethers::providers::Middleware;
+use ethers::types::{Address, Bytes, TransactionReceipt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::TargetProject;
+use crate::{CubistSdkError, Result, WrapperError};
+
+/// Version of the Safe Transaction Builder batch format that [`SafeBatch`] follows.
+pub const SAFE_BATCH_VERSION: &str = "1.0";
+
+/// Transaction of a [`SafeBatch`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeTransaction {
+    /// Contract to call
+    pub to: Address,
+    /// Value (in wei, as a decimal string)
+    pub value: String,
+    /// Calldata
+    pub data: Bytes,
+    /// Method to call (unused, since [`Self::data`] is already encoded)
+    #[serde(default)]
+    pub contract_method: Option<Value>,
+    /// Arguments of the method to call (unused, since [`Self::data`] is already encoded)
+    #[serde(default)]
+    pub contract_inputs_values: Option<Value>,
+}
+
+/// Metadata of a [`SafeBatch`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeBatchMeta {
+    /// Name of the batch
+    pub name: String,
+    /// What the transactions of the batch do, one per line
+    pub description: String,
+    /// Safe that the batch is proposed to
+    pub created_from_safe_address: Address,
+}
+
+/// Batch of transactions proposed to a Safe (multisig), in the JSON format of the Safe
+/// Transaction Builder (so it can be imported into Safe tooling as is).
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeBatch {
+    /// Version of the format (see [`SAFE_BATCH_VERSION`])
+    pub version: String,
+    /// Id of the chain the Safe is on (as a decimal string)
+    pub chain_id: String,
+    /// When the batch was created (in milliseconds since the Unix epoch)
+    pub created_at: u64,
+    /// Metadata
+    pub meta: SafeBatchMeta,
+    /// Transactions, in the order they must be executed
+    pub transactions: Vec<SafeTransaction>,
+}
+
+impl SafeBatch {
+    /// Creates an empty batch named `name`, proposed to `safe` on chain `chain_id`.
+    pub fn new(name: String, safe: Address, chain_id: u64) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        SafeBatch {
+            version: SAFE_BATCH_VERSION.to_owned(),
+            chain_id: chain_id.to_string(),
+            created_at,
+            meta: SafeBatchMeta {
+                name,
+                description: String::new(),
+                created_from_safe_address: safe,
+            },
+            transactions: vec![],
+        }
+    }
+
+    /// Adds a transaction calling `to` with `data` (described by `description`).
+    pub fn push(&mut self, description: &str, to: Address, data: Bytes) {
+        if !self.meta.description.is_empty() {
+            self.meta.description.push('\n');
+        }
+        self.meta.description.push_str(description);
+        self.transactions.push(SafeTransaction {
+            to,
+            value: "0".to_owned(),
+            data,
+            contract_method: None,
+            contract_inputs_values: None,
+        });
+    }
+
+    /// Reads a batch from a file.
+    pub fn from_file(path: &Path) -> Result<Self, WrapperError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| WrapperError::IOError(path.to_path_buf(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| WrapperError::JsonError(path.to_path_buf(), "SafeBatch".to_owned(), e))
+    }
+
+    /// Writes the batch to a file (as pretty-printed JSON).
+    pub fn write_file(&self, path: &Path) -> Result<(), WrapperError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| WrapperError::JsonError(path.to_path_buf(), "SafeBatch".to_owned(), e))?;
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|e| WrapperError::IOError(parent_dir.to_path_buf(), e))?;
+        }
+        fs::write(path, content).map_err(|e| WrapperError::IOError(path.to_path_buf(), e))
+    }
+}
+
+/// Admin transactions proposed to the Safe of a chain (see
+/// [`CommonConfig::safe`](cubist_config::network::CommonConfig::safe)) that are pending
+/// execution, as recorded in deployment manifests.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct SafeProposal {
+    /// Chain
+    pub target: Target,
+    /// The Safe
+    pub safe: Address,
+    /// Safe transaction batch to import into Safe tooling
+    pub batch: PathBuf,
+    /// What the proposed transactions do
+    pub transactions: Vec<String>,
+}
+
+/// What became of an admin transaction.
+#[derive(Clone, Debug)]
+pub enum AdminTx {
+    /// Sent from the sender (and confirmed)
+    Sent(Box<TransactionReceipt>),
+    /// Proposed to a Safe (see [`SafeProposal`]), in the given batch
+    Proposed {
+        /// The Safe
+        safe: Address,
+        /// Safe transaction batch the transaction was added to
+        batch: PathBuf,
+    },
+}
+
+/// The Safe to propose an admin transaction to, given the Safe administering the chain (if any,
+/// see [`CommonConfig::safe`](cubist_config::network::CommonConfig::safe)) and the account
+/// allowed to send the transaction (e.g., the owner of a shim): the chain's Safe if it is that
+/// account, or else none, since the account has to send the transaction itself.
+pub(crate) fn admin_safe(chain_safe: Option<Address>, admin: Address) -> Option<Address> {
+    chain_safe.filter(|safe| *safe == admin)
+}
+
+/// Collects the admin transactions concerning one contract that are proposed to Safes, in one
+/// Safe transaction batch per chain (see
+/// [`Paths::for_safe_batch`](cubist_config::paths::Paths::for_safe_batch)).
+///
+/// Transactions are added to the pending proposal of their chain, if there is one already, or
+/// else to a new batch (replacing batches of proposals that are no longer pending).
+pub(crate) struct SafeProposer {
+    /// The contract
+    contract: ContractFQN,
+    /// Pending proposals
+    proposals: Vec<SafeProposal>,
+}
+
+impl SafeProposer {
+    /// Creates a proposer for the admin transactions concerning `contract`, given the
+    /// `proposals` that are pending already.
+    pub(crate) fn new(contract: ContractFQN, proposals: Vec<SafeProposal>) -> Self {
+        Self {
+            contract,
+            proposals,
+        }
+    }
+
+    /// Pending proposals (including the ones made by this proposer).
+    pub(crate) fn proposals(&self) -> &[SafeProposal] {
+        &self.proposals
+    }
+
+    /// Proposes a transaction calling `to` with `data` (described by `description`) to `safe`,
+    /// which administers `project`'s chain.  Returns the batch the transaction was added to.
+    pub(crate) async fn propose<M: Middleware>(
+        &mut self,
+        project: &TargetProject<M>,
+        safe: Address,
+        description: String,
+        to: Address,
+        data: Bytes,
+    ) -> Result<PathBuf> {
+        let target = project.target;
+        let path = project.paths.for_safe_batch(target, &self.contract);
+        let propose_err =
+            |e| CubistSdkError::SafeProposalError(description.clone(), target, Box::new(e));
+        let pending = self
+            .proposals
+            .iter()
+            .position(|p| p.target == target && p.safe == safe && p.batch == path);
+        let mut batch = match pending {
+            Some(_) => SafeBatch::from_file(&path).map_err(propose_err)?,
+            None => {
+                let name = format!("{} ({target})", self.contract.name);
+                SafeBatch::new(name, safe, project.chain_id().await?)
+            }
+        };
+        batch.push(&description, to, data);
+        batch.write_file(&path).map_err(propose_err)?;
+
+        match pending {
+            Some(i) => self.proposals[i].transactions.push(description),
+            None => {
+                self.proposals.retain(|p| p.batch != path);
+                self.proposals.push(SafeProposal {
+                    target,
+                    safe,
+                    batch: path.clone(),
+                    transactions: vec![description],
+                });
+            }
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_safe() {
+        let safe = Address::repeat_byte(0x5a);
+        let sender = Address::repeat_byte(1);
+        // shims owned by the Safe are administered through proposals
+        assert_eq!(Some(safe), admin_safe(Some(safe), safe));
+        // shims owned by the sender (e.g., deployed before the Safe was configured) are not, even
+        // if a Safe administers the chain, since the Safe couldn't execute the proposals
+        assert_eq!(None, admin_safe(Some(safe), sender));
+        assert_eq!(None, admin_safe(None, sender));
+    }
+
+    #[test]
+    fn test_batch_format() {
+        let safe = Address::repeat_byte(0x5a);
+        let mut batch = SafeBatch::new("Receiver (polygon)".to_owned(), safe, 80001);
+        batch.push("Approve caller", Address::repeat_byte(1), vec![1, 2].into());
+        batch.push(
+            "Approve another caller",
+            Address::repeat_byte(2),
+            vec![3].into(),
+        );
+
+        let json = serde_json::to_value(&batch).unwrap();
+        assert_eq!("1.0", json["version"]);
+        assert_eq!("80001", json["chainId"]);
+        assert_eq!(format!("{safe:?}"), json["meta"]["createdFromSafeAddress"]);
+        assert_eq!(
+            "Approve caller\nApprove another caller",
+            json["meta"]["description"]
+        );
+        let tx = &json["transactions"][0];
+        assert_eq!("0", tx["value"]);
+        assert_eq!("0x0102", tx["data"]);
+        assert!(tx["contractMethod"].is_null());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("batch.json");
+        batch.write_file(&path).unwrap();
+        assert_eq!(batch, SafeBatch::from_file(&path).unwrap());
+    }
+}
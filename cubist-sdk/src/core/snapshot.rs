@@ -0,0 +1,414 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use cubist_config::Target;
+use ethers::providers::Middleware;
+use ethers::types::U256;
+use tracing::{debug, warn};
+
+use crate::core::{HttpStack, TargetProject};
+use crate::{CubistSdkError, Result, WrapperError};
+
+/// Snapshot of the state of all target chains (that support snapshots) and of the deploy
+/// directory of the current network profile (see [`Cubist::snapshot`](crate::Cubist::snapshot)).
+pub struct Snapshot<M: Middleware = HttpStack> {
+    /// Chains that got snapshot, along with the ids of their snapshots
+    chains: Vec<(Arc<TargetProject<M>>, U256)>,
+    /// Chains that could not be snapshot, along with why
+    unsupported: Vec<(Target, String)>,
+    /// Deploy directory of the current network profile
+    deploy_dir: PathBuf,
+    /// All files in the deploy directory (with paths relative to it) and their contents
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl<M: Middleware> Snapshot<M> {
+    /// Snapshots `projects` (see [`TargetProject::evm_snapshot`]) and the files in `deploy_dir`.
+    /// Chains that can't be snapshot (e.g., Avalanche or Stellar) are reported by
+    /// [`Self::unsupported`] (and logged).
+    pub(crate) async fn take(
+        projects: impl IntoIterator<Item = Arc<TargetProject<M>>>,
+        deploy_dir: &Path,
+    ) -> Result<Self> {
+        let mut chains = vec![];
+        let mut unsupported = vec![];
+        for project in projects {
+            let target = project.target;
+            let result = match target {
+                Target::Stellar => Err("snapshots are not supported on Stellar".to_owned()),
+                _ => project.evm_snapshot().await.map_err(|e| e.to_string()),
+            };
+            match result {
+                Ok(id) => {
+                    debug!("Took snapshot {id} of {target}");
+                    chains.push((project, id));
+                }
+                Err(reason) => {
+                    warn!("Cannot snapshot {target}: {reason}");
+                    unsupported.push((target, reason));
+                }
+            }
+        }
+        let files = read_files(deploy_dir)
+            .map_err(|e| CubistSdkError::SnapshotDeployDirError(deploy_dir.into(), Box::new(e)))?;
+        Ok(Snapshot {
+            chains,
+            unsupported,
+            deploy_dir: deploy_dir.into(),
+            files,
+        })
+    }
+
+    /// Targets whose chains got snapshot (and get reverted by [`Self::revert`]).
+    pub fn targets(&self) -> impl Iterator<Item = Target> + '_ {
+        self.chains.iter().map(|(project, _)| project.target)
+    }
+
+    /// Targets whose chains could not be snapshot (and are left alone by [`Self::revert`]),
+    /// along with why.
+    pub fn unsupported(&self) -> &[(Target, String)] {
+        &self.unsupported
+    }
+
+    /// Reverts all snapshot chains and the deploy directory to this snapshot, then snapshots the
+    /// chains again, so that this snapshot can be reverted to repeatedly (e.g., after each
+    /// test).
+    ///
+    /// All snapshots are validated (see [`validate`]) before reverting any chain, so that a
+    /// snapshot lost by one chain (e.g., because it was restarted) leaves all chains and the
+    /// deploy directory alone.  If some chains still fail to revert, the others are reverted
+    /// (and snapshot again), so that this snapshot remains usable for them, and the deploy
+    /// directory is restored (in one go, by replacing the whole directory) to match them.
+    ///
+    /// Contracts (and their shims) that were deployed after taking this snapshot remain deployed
+    /// as far as existing [`Contract`](crate::Contract) handles are concerned; use a fresh
+    /// [`Cubist`](crate::Cubist) instance (e.g., a clone) after reverting.
+    pub async fn revert(&mut self) -> Result<()> {
+        revert_all(&mut self.chains, &self.deploy_dir, &self.files).await
+    }
+}
+
+/// A chain that can be snapshot and reverted (see [`TargetProject::evm_snapshot`]).
+#[async_trait]
+trait SnapshotChain: Send + Sync {
+    /// Target of the chain
+    fn target(&self) -> Target;
+    /// Snapshots the chain and returns the id of the snapshot
+    async fn snapshot(&self) -> Result<U256, WrapperError>;
+    /// Reverts the chain to snapshot `id`; returns whether the snapshot was found
+    async fn revert(&self, id: U256) -> Result<bool, WrapperError>;
+}
+
+#[async_trait]
+impl<M: Middleware> SnapshotChain for Arc<TargetProject<M>> {
+    fn target(&self) -> Target {
+        self.target
+    }
+
+    async fn snapshot(&self) -> Result<U256, WrapperError> {
+        self.evm_snapshot().await
+    }
+
+    async fn revert(&self, id: U256) -> Result<bool, WrapperError> {
+        self.evm_revert(id).await
+    }
+}
+
+/// Checks that `chain` can still be reverted to snapshot `id`.  There is no RPC method to look
+/// up snapshots, so this takes a probe snapshot instead: snapshot ids only ever increase (on
+/// Anvil and Hardhat), so a probe id that isn't greater than `id` means that the chain lost its
+/// snapshots (e.g., because it was restarted).  The probe is reverted to (a no-op) right away,
+/// so that probes don't pile up.
+async fn validate<C: SnapshotChain>(chain: &C, id: U256) -> Result<(), String> {
+    let probe = chain.snapshot().await.map_err(|e| e.to_string())?;
+    chain.revert(probe).await.map_err(|e| e.to_string())?;
+    if probe > id {
+        Ok(())
+    } else {
+        Err(format!("snapshot {id} not found"))
+    }
+}
+
+/// Implements [`Snapshot::revert`] for `chains` (along with the ids of their snapshots, which
+/// get replaced by the new ones), `deploy_dir`, and its snapshot `files`.
+async fn revert_all<C: SnapshotChain>(
+    chains: &mut [(C, U256)],
+    deploy_dir: &Path,
+    files: &[(PathBuf, Vec<u8>)],
+) -> Result<()> {
+    let mut failed = vec![];
+    for (chain, id) in chains.iter() {
+        if let Err(reason) = validate(chain, *id).await {
+            failed.push((chain.target(), reason));
+        }
+    }
+    if !failed.is_empty() {
+        return Err(CubistSdkError::RevertSnapshotError(failed));
+    }
+
+    let mut reverted = chains.is_empty();
+    for (chain, id) in chains.iter_mut() {
+        let target = chain.target();
+        match chain.revert(*id).await {
+            Ok(true) => debug!("Reverted {target} to snapshot {id}"),
+            Ok(false) => {
+                failed.push((target, format!("snapshot {id} not found")));
+                continue;
+            }
+            Err(e) => {
+                failed.push((target, e.to_string()));
+                continue;
+            }
+        }
+        reverted = true;
+        // snapshots are consumed by reverting to them
+        match chain.snapshot().await {
+            Ok(new_id) => *id = new_id,
+            Err(e) => failed.push((target, e.to_string())),
+        }
+    }
+
+    // the deploy directory must match the chains that got reverted, even if others didn't
+    if reverted {
+        restore_files(deploy_dir, files).map_err(|e| {
+            CubistSdkError::SnapshotDeployDirError(deploy_dir.to_path_buf(), Box::new(e))
+        })?;
+    }
+    if !failed.is_empty() {
+        return Err(CubistSdkError::RevertSnapshotError(failed));
+    }
+    Ok(())
+}
+
+/// Reads all files in `dir` (recursively), or none if `dir` doesn't exist.  Returns their paths
+/// (relative to `dir`) and contents.
+fn read_files(dir: &Path) -> Result<Vec<(PathBuf, Vec<u8>)>, WrapperError> {
+    fn read_into(
+        root: &Path,
+        dir: &Path,
+        files: &mut Vec<(PathBuf, Vec<u8>)>,
+    ) -> Result<(), WrapperError> {
+        let io_err = |e| WrapperError::IOError(dir.to_path_buf(), e);
+        for entry in fs::read_dir(dir).map_err(io_err)? {
+            let path = entry.map_err(io_err)?.path();
+            if path.is_dir() {
+                read_into(root, &path, files)?;
+            } else {
+                let content =
+                    fs::read(&path).map_err(|e| WrapperError::IOError(path.clone(), e))?;
+                let rel_path = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+                files.push((rel_path, content));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = vec![];
+    if dir.is_dir() {
+        read_into(dir, dir, &mut files)?;
+    }
+    Ok(files)
+}
+
+/// Replaces the contents of `dir` with `files` (see [`read_files`]).  The files are written to
+/// a sibling directory first, which then replaces `dir`.
+fn restore_files(dir: &Path, files: &[(PathBuf, Vec<u8>)]) -> Result<(), WrapperError> {
+    let sibling = |suffix: &str| {
+        let mut name = dir.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        dir.with_file_name(name)
+    };
+    let io_err = |path: &Path| {
+        let path = path.to_path_buf();
+        move |e| WrapperError::IOError(path, e)
+    };
+    let staging = sibling(".restore");
+    let old = sibling(".old");
+    for stale in [&staging, &old] {
+        if stale.exists() {
+            fs::remove_dir_all(stale).map_err(io_err(stale))?;
+        }
+    }
+
+    fs::create_dir_all(&staging).map_err(io_err(&staging))?;
+    for (rel_path, content) in files {
+        let path = staging.join(rel_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_err(parent))?;
+        }
+        fs::write(&path, content).map_err(io_err(&path))?;
+    }
+
+    if dir.exists() {
+        fs::rename(dir, &old).map_err(io_err(dir))?;
+    }
+    fs::rename(&staging, dir).map_err(io_err(&staging))?;
+    if old.exists() {
+        fs::remove_dir_all(&old).map_err(io_err(&old))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    /// Chain that keeps track of its snapshots (but has no state to revert)
+    struct MockChain {
+        target: Target,
+        /// Ids of the snapshots that can be reverted to, and the id of the next snapshot
+        snapshots: Mutex<(BTreeSet<U256>, U256)>,
+        /// Id of a snapshot that reverting to fails
+        broken: Option<U256>,
+    }
+
+    impl MockChain {
+        fn new(target: Target) -> Self {
+            MockChain {
+                target,
+                snapshots: Mutex::new((BTreeSet::new(), U256::zero())),
+                broken: None,
+            }
+        }
+
+        fn has_snapshot(&self, id: U256) -> bool {
+            self.snapshots.lock().unwrap().0.contains(&id)
+        }
+    }
+
+    #[async_trait]
+    impl SnapshotChain for MockChain {
+        fn target(&self) -> Target {
+            self.target
+        }
+
+        async fn snapshot(&self) -> Result<U256, WrapperError> {
+            let mut snapshots = self.snapshots.lock().unwrap();
+            let id = snapshots.1;
+            snapshots.0.insert(id);
+            snapshots.1 = id + 1;
+            Ok(id)
+        }
+
+        async fn revert(&self, id: U256) -> Result<bool, WrapperError> {
+            if self.broken == Some(id) {
+                return Err(WrapperError::ProviderError(
+                    "evm_revert".to_owned(),
+                    "connection refused".to_owned(),
+                ));
+            }
+            Ok(self.snapshots.lock().unwrap().0.remove(&id))
+        }
+    }
+
+    /// Creates a deploy directory (in `tmp`) with a single file, snapshots it, and then changes
+    /// that file.  Returns the directory and the snapshot files.
+    fn changed_deploy_dir(tmp: &Path) -> (PathBuf, Vec<(PathBuf, Vec<u8>)>) {
+        let dir = tmp.join("dev");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("deployments.jsonl"), "{}\n").unwrap();
+        let files = read_files(&dir).unwrap();
+        fs::write(dir.join("deployments.jsonl"), "{}\n{}\n").unwrap();
+        (dir, files)
+    }
+
+    #[tokio::test]
+    async fn test_revert_validates_snapshots_first() {
+        let tmp = tempdir().unwrap();
+        let (dir, files) = changed_deploy_dir(tmp.path());
+        let ethereum = MockChain::new(Target::Ethereum);
+        let id = ethereum.snapshot().await.unwrap();
+        // the Polygon chain got restarted, so it lost its snapshot
+        let polygon = MockChain::new(Target::Polygon);
+        let mut chains = vec![(ethereum, id), (polygon, U256::from(3))];
+
+        let err = revert_all(&mut chains, &dir, &files).await.unwrap_err();
+        assert!(matches!(
+            err,
+            CubistSdkError::RevertSnapshotError(failed)
+                if failed == vec![(Target::Polygon, "snapshot 3 not found".to_owned())]
+        ));
+        // neither the Ethereum chain nor the deploy directory got reverted
+        assert_eq!(id, chains[0].1);
+        assert!(chains[0].0.has_snapshot(id));
+        assert_eq!(
+            "{}\n{}\n",
+            fs::read_to_string(dir.join("deployments.jsonl")).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revert_partial_failure() {
+        let tmp = tempdir().unwrap();
+        let (dir, files) = changed_deploy_dir(tmp.path());
+        let ethereum = MockChain::new(Target::Ethereum);
+        let ethereum_id = ethereum.snapshot().await.unwrap();
+        let mut polygon = MockChain::new(Target::Polygon);
+        let polygon_id = polygon.snapshot().await.unwrap();
+        polygon.broken = Some(polygon_id);
+        let mut chains = vec![(ethereum, ethereum_id), (polygon, polygon_id)];
+
+        let err = revert_all(&mut chains, &dir, &files).await.unwrap_err();
+        assert!(matches!(
+            err,
+            CubistSdkError::RevertSnapshotError(failed)
+                if failed.len() == 1 && failed[0].0 == Target::Polygon
+        ));
+        // the Ethereum chain got reverted (and snapshot again) ...
+        assert!(!chains[0].0.has_snapshot(ethereum_id));
+        assert_ne!(ethereum_id, chains[0].1);
+        assert!(chains[0].0.has_snapshot(chains[0].1));
+        // ... and so did the deploy directory
+        assert_eq!(
+            "{}\n",
+            fs::read_to_string(dir.join("deployments.jsonl")).unwrap()
+        );
+        // the Polygon chain can still be reverted to its snapshot later
+        assert_eq!(polygon_id, chains[1].1);
+        assert!(chains[1].0.has_snapshot(polygon_id));
+    }
+
+    #[test]
+    fn test_read_and_restore_files() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("dev");
+        assert!(read_files(&dir).unwrap().is_empty());
+
+        fs::create_dir_all(dir.join("ethereum").join("31337")).unwrap();
+        fs::write(dir.join("deployments.jsonl"), "{}\n").unwrap();
+        fs::write(dir.join("ethereum").join("31337").join("a.json"), "a").unwrap();
+        let mut files = read_files(&dir).unwrap();
+        files.sort();
+        assert_eq!(
+            vec![
+                (PathBuf::from("deployments.jsonl"), b"{}\n".to_vec()),
+                (
+                    Path::new("ethereum").join("31337").join("a.json"),
+                    b"a".to_vec()
+                ),
+            ],
+            files
+        );
+
+        // changes made after the snapshot are undone
+        fs::write(dir.join("deployments.jsonl"), "{}\n{}\n").unwrap();
+        fs::write(dir.join("ethereum").join("31337").join("b.json"), "b").unwrap();
+        fs::remove_file(dir.join("ethereum").join("31337").join("a.json")).unwrap();
+        restore_files(&dir, &files).unwrap();
+        let mut restored = read_files(&dir).unwrap();
+        restored.sort();
+        assert_eq!(files, restored);
+
+        // no leftovers next to the restored directory
+        let entries: Vec<_> = fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(vec![dir.file_name().unwrap().to_os_string()], entries);
+    }
+}
@@ -0,0 +1,133 @@
+use ethers_solc::artifacts::StorageLayout;
+
+/// Checks whether an implementation with storage layout `new` can replace (behind a proxy) an
+/// implementation with storage layout `old`, and returns a description of every incompatibility
+/// (none if the layouts are compatible).
+///
+/// Every variable of `old` must keep its name, position (slot and offset), and type in `new`;
+/// `new` may only append variables.  Types are compared by their labels and sizes (type ids embed
+/// AST ids, which differ between compilations).
+pub fn storage_layout_incompatibilities(old: &StorageLayout, new: &StorageLayout) -> Vec<String> {
+    let mut errors = vec![];
+    for (i, old_var) in old.storage.iter().enumerate() {
+        let Some(new_var) = new.storage.get(i) else {
+            errors.push(format!("variable '{}' was removed", old_var.label));
+            continue;
+        };
+        if old_var.label != new_var.label {
+            errors.push(format!(
+                "variable '{}' was renamed or replaced by '{}'",
+                old_var.label, new_var.label
+            ));
+        }
+        if (&old_var.slot, old_var.offset) != (&new_var.slot, new_var.offset) {
+            errors.push(format!(
+                "variable '{}' moved from slot {} (offset {}) to slot {} (offset {})",
+                old_var.label, old_var.slot, old_var.offset, new_var.slot, new_var.offset
+            ));
+        }
+        let old_type = type_description(old, &old_var.storage_type);
+        let new_type = type_description(new, &new_var.storage_type);
+        if old_type != new_type {
+            errors.push(format!(
+                "variable '{}' changed type from {old_type} to {new_type}",
+                old_var.label
+            ));
+        }
+    }
+    errors
+}
+
+/// Human-readable description of type `type_id` (its label and size) in `layout`
+fn type_description(layout: &StorageLayout, type_id: &str) -> String {
+    match layout.types.get(type_id) {
+        Some(t) => format!("'{}' ({} bytes)", t.label, t.number_of_bytes),
+        None => format!("'{type_id}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Layout (in solc's format) of a contract with the given `(label, slot, type)` variables
+    fn layout(vars: &[(&str, u64, &str)]) -> StorageLayout {
+        let storage: Vec<_> = vars
+            .iter()
+            .enumerate()
+            .map(|(i, (label, slot, ty))| {
+                json!({
+                    "astId": 10 + i,
+                    "contract": "Counter.sol:Counter",
+                    "label": label,
+                    "offset": 0,
+                    "slot": slot.to_string(),
+                    "type": ty,
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "storage": storage,
+            "types": {
+                "t_uint256": { "encoding": "inplace", "label": "uint256", "numberOfBytes": "32" },
+                "t_address": { "encoding": "inplace", "label": "address", "numberOfBytes": "20" },
+                "t_mapping(t_address,t_uint256)": {
+                    "encoding": "mapping",
+                    "key": "t_address",
+                    "label": "mapping(address => uint256)",
+                    "numberOfBytes": "32",
+                    "value": "t_uint256"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compatible_layouts() {
+        let old = layout(&[("count", 0, "t_uint256"), ("owner", 1, "t_address")]);
+        assert!(storage_layout_incompatibilities(&old, &old).is_empty());
+
+        // appending variables is fine
+        let new = layout(&[
+            ("count", 0, "t_uint256"),
+            ("owner", 1, "t_address"),
+            ("balances", 2, "t_mapping(t_address,t_uint256)"),
+        ]);
+        assert!(storage_layout_incompatibilities(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_incompatible_layouts() {
+        let old = layout(&[("count", 0, "t_uint256"), ("owner", 1, "t_address")]);
+
+        // removed variable
+        let new = layout(&[("count", 0, "t_uint256")]);
+        assert_eq!(
+            vec!["variable 'owner' was removed"],
+            storage_layout_incompatibilities(&old, &new)
+        );
+
+        // changed type
+        let new = layout(&[("count", 0, "t_uint256"), ("owner", 1, "t_uint256")]);
+        assert_eq!(
+            vec!["variable 'owner' changed type from 'address' (20 bytes) to 'uint256' (32 bytes)"],
+            storage_layout_incompatibilities(&old, &new)
+        );
+
+        // inserted variable shifts the others
+        let new = layout(&[
+            ("count", 0, "t_uint256"),
+            ("total", 1, "t_uint256"),
+            ("owner", 2, "t_address"),
+        ]);
+        assert_eq!(
+            vec![
+                "variable 'owner' was renamed or replaced by 'total'",
+                "variable 'owner' changed type from 'address' (20 bytes) to 'uint256' (32 bytes)"
+            ],
+            storage_layout_incompatibilities(&old, &new)
+        );
+    }
+}
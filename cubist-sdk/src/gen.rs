@@ -2,5 +2,31 @@ pub mod backend;
 pub mod common;
 /// The module for analyzing contracts and generating interfaces
 pub mod interface;
+pub mod multicall;
+pub mod proxy;
 /// The name of the shim method that adds the sender to approved callers
 pub const APPROVE_CALLER_METHOD_NAME: &str = "approveCaller";
+/// The name of the shim method that removes an account from approved callers
+pub const REVOKE_CALLER_METHOD_NAME: &str = "revokeCaller";
+/// The name of the shim method that returns the approved callers
+pub const APPROVED_CALLERS_METHOD_NAME: &str = "approvedCallers";
+/// The name of the shim method that returns the owner of the shim
+pub const SHIM_OWNER_METHOD_NAME: &str = "shimOwner";
+/// The name of the shim method that returns the account ownership of the shim is being
+/// transferred to (if any)
+pub const PENDING_SHIM_OWNER_METHOD_NAME: &str = "pendingShimOwner";
+/// The name of the shim method that starts transferring ownership of the shim
+pub const TRANSFER_SHIM_OWNERSHIP_METHOD_NAME: &str = "transferShimOwnership";
+/// The name of the shim method that the new owner calls to complete an ownership transfer
+pub const ACCEPT_SHIM_OWNERSHIP_METHOD_NAME: &str = "acceptShimOwnership";
+/// The name of the shim method that grants (or revokes) the GUARDIAN role, which allows pausing
+/// the shim
+pub const SET_SHIM_GUARDIAN_METHOD_NAME: &str = "setShimGuardian";
+/// The name of the shim method that returns the guardians
+pub const SHIM_GUARDIANS_METHOD_NAME: &str = "shimGuardians";
+/// The name of the shim method that pauses the shim (stopping cross-chain calls through it)
+pub const PAUSE_SHIM_METHOD_NAME: &str = "pauseShim";
+/// The name of the shim method that unpauses the shim
+pub const UNPAUSE_SHIM_METHOD_NAME: &str = "unpauseShim";
+/// The name of the shim method that returns whether the shim is paused
+pub const SHIM_PAUSED_METHOD_NAME: &str = "shimPaused";
@@ -16,7 +16,12 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tera::{Context, Tera};
 
-use super::APPROVE_CALLER_METHOD_NAME;
+use super::{
+    ACCEPT_SHIM_OWNERSHIP_METHOD_NAME, APPROVED_CALLERS_METHOD_NAME, APPROVE_CALLER_METHOD_NAME,
+    PAUSE_SHIM_METHOD_NAME, PENDING_SHIM_OWNER_METHOD_NAME, REVOKE_CALLER_METHOD_NAME,
+    SET_SHIM_GUARDIAN_METHOD_NAME, SHIM_GUARDIANS_METHOD_NAME, SHIM_OWNER_METHOD_NAME,
+    SHIM_PAUSED_METHOD_NAME, TRANSFER_SHIM_OWNERSHIP_METHOD_NAME, UNPAUSE_SHIM_METHOD_NAME,
+};
 
 #[derive(RustEmbed)]
 #[folder = "templates"]
@@ -28,6 +33,35 @@ lazy_static! {
     pub static ref TEMPLATES: Tera = CubeTemplates::tera_from_prefix("");
 }
 
+/// Inserts the names of the access control methods of shims (see the `access_control` and
+/// `pausable` macros) into a template context.
+fn insert_access_control_names(context: &mut Context) {
+    context.insert("APPROVE_CALLER_METHOD_NAME", APPROVE_CALLER_METHOD_NAME);
+    context.insert("REVOKE_CALLER_METHOD_NAME", REVOKE_CALLER_METHOD_NAME);
+    context.insert("APPROVED_CALLERS_METHOD_NAME", APPROVED_CALLERS_METHOD_NAME);
+    context.insert("SHIM_OWNER_METHOD_NAME", SHIM_OWNER_METHOD_NAME);
+    context.insert(
+        "PENDING_SHIM_OWNER_METHOD_NAME",
+        PENDING_SHIM_OWNER_METHOD_NAME,
+    );
+    context.insert(
+        "TRANSFER_SHIM_OWNERSHIP_METHOD_NAME",
+        TRANSFER_SHIM_OWNERSHIP_METHOD_NAME,
+    );
+    context.insert(
+        "ACCEPT_SHIM_OWNERSHIP_METHOD_NAME",
+        ACCEPT_SHIM_OWNERSHIP_METHOD_NAME,
+    );
+    context.insert(
+        "SET_SHIM_GUARDIAN_METHOD_NAME",
+        SET_SHIM_GUARDIAN_METHOD_NAME,
+    );
+    context.insert("SHIM_GUARDIANS_METHOD_NAME", SHIM_GUARDIANS_METHOD_NAME);
+    context.insert("PAUSE_SHIM_METHOD_NAME", PAUSE_SHIM_METHOD_NAME);
+    context.insert("UNPAUSE_SHIM_METHOD_NAME", UNPAUSE_SHIM_METHOD_NAME);
+    context.insert("SHIM_PAUSED_METHOD_NAME", SHIM_PAUSED_METHOD_NAME);
+}
+
 /// Metadata associated with an artifact generated by a back end. The metadata that we store
 /// depends on the type of artifact.
 #[derive(Debug)]
@@ -184,7 +218,7 @@ impl Backend for CubistBackend {
             .map(|contract| contract.get_contract_name().clone())
             .collect();
         context.insert("file", file);
-        context.insert("APPROVE_CALLER_METHOD_NAME", APPROVE_CALLER_METHOD_NAME);
+        insert_access_control_names(&mut context);
         result.push(Artifact {
             target: file.get_sender_target(),
             name: file_name.clone(),
@@ -306,7 +340,7 @@ impl Backend for AxelarBackend {
             "AXELAR_SET_TARGET_ADDR_METHOD_NAME",
             AXELAR_SET_TARGET_ADDR_METHOD_NAME,
         );
-        context.insert("APPROVE_CALLER_METHOD_NAME", APPROVE_CALLER_METHOD_NAME);
+        insert_access_control_names(&mut context);
 
         // Generate the receiver file
         result.push(Artifact {
@@ -1,4 +1,5 @@
 //! Common types for modules in this crate
+use cubist_config::Target;
 use thiserror::Error;
 
 /// Errors that can occur in the interface generator
@@ -29,6 +30,16 @@ pub enum InterfaceGenError {
     /// Expected a certain contract that wasn't found
     #[error("Did not find expected contract {0} in sources")]
     MissingContract(String),
+    /// A function annotated with `@custom:cubist-expose never` is called cross-chain
+    #[error("Function {1} of contract {0} is called cross-chain, but it is annotated with '@custom:cubist-expose never'")]
+    ForbiddenCrossChainCall(String, String),
+    /// A shim would have to be generated for a file on a target that the file's own contracts
+    /// (some of them moved via `@custom:cubist-target`) already run on
+    #[error("Cannot expose contracts from {0} on target {1} because that file also contains contracts targeting {1}; move them to separate files")]
+    MixedTargetFile(String, Target),
+    /// Contracts from the same file that run on different targets are all exposed on one target
+    #[error("Cannot expose contracts from {0} on target {1} because they run on different targets; move them to separate files")]
+    SplitFileExposure(String, Target),
 }
 
 /// The standard [`Result`] type in the interface generator
@@ -102,6 +102,7 @@ pub fn get_interface_for_contract(
             .iter()
             .map(|target| (Path::new("dummy").to_path_buf(), *target))
             .collect(),
+        forbidden: contract_finder.get_forbidden_functions(contract),
     });
     let interfaces = to_file_interfaces(&interface_config, source_files)?;
     let contract_locations = contract_finder.get_file_contracts();
@@ -174,6 +175,11 @@ fn to_file_interfaces(
                         header_file.license()?.clone(),
                     )
                 })
+                // A file split across targets (via `@custom:cubist-target`) shares a single shim
+                // per target, so skip the parts of it that expose nothing
+                .filter(|interfaces| {
+                    !(source.is_partial() && matches!(interfaces, Ok(i) if i.is_empty()))
+                })
                 .collect::<Result<Vec<_>>>()
         })
         // Consolidate all results into a single result
@@ -1,11 +1,11 @@
 //! Determine which functions and contracts are actually used cross-chain
 use crate::analyze::visit::{walk_contract_definition, walk_expression, Visitor};
 use crate::gen::common::{InterfaceGenError, Result};
-use crate::parse::source_file::{SourceFile, SourceFileContent};
+use crate::parse::source_file::{Exposure, SourceFile, SourceFileContent};
 use cubist_config::util::OrBug;
 use cubist_config::{ContractFile, ContractName, FunctionName, ObjectName, Target};
 use solang_parser::pt;
-use std::collections::{BTreeMap as Map, BTreeSet as OrdSet, HashSet as Set};
+use std::collections::{BTreeMap as Map, BTreeSet as OrdSet, HashMap, HashSet as Set};
 use std::path::{Path, PathBuf};
 use tracing::warn;
 
@@ -44,6 +44,13 @@ pub struct Analyzer {
     /// Would yield:
     /// <AvaStorage.sol <EthStorage, EthereumStorage>>
     aliases: Map<ContractFile, Map<ObjectName, ObjectName>>,
+    /// The targets that the contracts in each file run on. A file contains contracts for more
+    /// than one target only if some of its contracts are annotated with `@custom:cubist-target`.
+    file_targets: Map<ContractFile, Set<Target>>,
+    /// The target of the contracts exposed by each (contract file, sender target) shim
+    shim_receivers: HashMap<(ContractFile, Target), Target>,
+    /// Functions annotated with `@custom:cubist-expose`
+    exposure: Map<ContractName, Map<FunctionName, Exposure>>,
 }
 
 impl Analyzer {
@@ -59,6 +66,9 @@ impl Analyzer {
             contract_files: Map::new(),
             interface_targets: Map::new(),
             aliases: Map::new(),
+            file_targets: Map::new(),
+            shim_receivers: HashMap::new(),
+            exposure: Map::new(),
         }
     }
 
@@ -109,11 +119,29 @@ impl Analyzer {
         &self.interface_targets
     }
 
+    /// Get the functions of `contract` annotated with `@custom:cubist-expose never`
+    pub fn get_forbidden_functions(&self, contract: &ContractName) -> OrdSet<FunctionName> {
+        self.exposure
+            .get(contract)
+            .into_iter()
+            .flatten()
+            .filter(|(_, exposure)| **exposure == Exposure::Never)
+            .map(|(function, _)| function.clone())
+            .collect()
+    }
+
     /// Determine which contracts reside in which files
     pub fn analyze_contract_locations(&mut self, sources: &[SourceFile]) -> Result<()> {
         for source in sources {
             // Save the location of the contract
             for name in &source.contract_names() {
+                if let Some(exposure) = source.annotations.exposure.get(name) {
+                    self.exposure.insert(name.clone(), exposure.clone());
+                }
+                self.file_targets
+                    .entry(source.file_name.clone())
+                    .or_default()
+                    .insert(source.target);
                 tracing::debug!("Analyzing {} in {}", name, source.file_name.display());
                 if self
                     .contracts
@@ -155,6 +183,9 @@ impl Analyzer {
                 }
             }
         }
+
+        // Finally, expose functions annotated with `@custom:cubist-expose`
+        self.add_always_exposed_calls();
         Ok(())
     }
 
@@ -230,6 +261,7 @@ impl Analyzer {
                             contract_file,
                             current_target,
                             self.current_file.clone().unwrap(),
+                            object_target,
                         )?;
                     }
                 }
@@ -262,7 +294,7 @@ impl Analyzer {
                     {
                         let other_contract_str = other_contract.to_string();
                         let call_name_str = call_name.to_string();
-                        self.add_cross_target_call(other_contract_str, call_name_str);
+                        self.add_cross_target_call(other_contract_str, call_name_str)?;
                     }
                 }
             }
@@ -365,22 +397,89 @@ impl Analyzer {
     }
 
     /// Note that `contract`'s `function` is used cross-chain
-    fn add_cross_target_call(&mut self, contract: ContractName, function: FunctionName) {
+    fn add_cross_target_call(
+        &mut self,
+        contract: ContractName,
+        function: FunctionName,
+    ) -> Result<()> {
+        if self.get_forbidden_functions(&contract).contains(&function) {
+            return Err(InterfaceGenError::ForbiddenCrossChainCall(
+                contract, function,
+            ));
+        }
         if let Some(functions) = self.cross_target_calls.get_mut(&contract) {
             functions.insert(function);
         } else {
             self.cross_target_calls
                 .insert(contract, OrdSet::from([function]));
         }
+        Ok(())
     }
 
-    /// Adds `target` to the `source`'s set of targets
+    /// Add the functions annotated with `@custom:cubist-expose` (or `@custom:cubist-expose
+    /// always`) to the cross-target calls of their contracts, provided that the file containing
+    /// the contract is exposed cross-chain at all.
+    fn add_always_exposed_calls(&mut self) {
+        let always_exposed: Vec<(ContractName, FunctionName)> = self
+            .exposure
+            .iter()
+            .flat_map(|(contract, functions)| {
+                functions
+                    .iter()
+                    .filter(|(_, exposure)| **exposure == Exposure::Always)
+                    .map(|(function, _)| (contract.clone(), function.clone()))
+            })
+            .collect();
+        for (contract, function) in always_exposed {
+            if !self
+                .interface_targets
+                .contains_key(self.get_file(&contract))
+            {
+                warn!(
+                    "Ignoring '@custom:cubist-expose' on {contract}.{function} because {contract} is not called cross-chain"
+                );
+                continue;
+            }
+            self.cross_target_calls
+                .entry(contract)
+                .or_default()
+                .insert(function);
+        }
+    }
+
+    /// Adds `target` to the `source`'s set of targets. `receiver_target` is the target of the
+    /// contract (in `source`) that is exposed on `target`.
     fn add_interface_target(
         &mut self,
         source: ContractFile,
         target: Target,
         sender_name: ContractFile,
+        receiver_target: Target,
     ) -> Result<()> {
+        // The shim is written to the same relative path as `source`, so it would overwrite any
+        // contracts in `source` that run on `target`
+        if self
+            .file_targets
+            .get(&source)
+            .map_or(false, |targets| targets.contains(&target))
+        {
+            return Err(InterfaceGenError::MixedTargetFile(
+                source.display().to_string(),
+                target,
+            ));
+        }
+        // Contracts from a single file can only be exposed on `target` if they all run on the
+        // same target (there is only one shim per file and target)
+        let receiver = *self
+            .shim_receivers
+            .entry((source.clone(), target))
+            .or_insert(receiver_target);
+        if receiver != receiver_target {
+            return Err(InterfaceGenError::SplitFileExposure(
+                source.display().to_string(),
+                target,
+            ));
+        }
         self.interface_targets
             .entry(source)
             .or_default()
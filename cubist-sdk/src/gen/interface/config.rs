@@ -23,6 +23,8 @@ pub struct ExplicitInfo {
     pub file: ContractFile,
     /// The targets we're exposing the contract to
     pub targets: Set<(ContractFile, Target)>,
+    /// Functions that must not be exposed (annotated with `@custom:cubist-expose never`)
+    pub forbidden: OrdSet<FunctionName>,
 }
 
 /// Analysis information about what's used cross-chain (and thus what requires an interface)
@@ -88,7 +90,9 @@ impl InterfaceConfig {
     /// Generate an interface for this function?
     pub fn gen_function(&self, contract: &ContractName, function: &FunctionName) -> bool {
         match self {
-            InterfaceConfig::ExposedContract(info) => contract == &info.contract,
+            InterfaceConfig::ExposedContract(info) => {
+                contract == &info.contract && !info.forbidden.contains(function)
+            }
             InterfaceConfig::AnalyzedProject(info) => info
                 .included_code
                 .get(contract)
@@ -59,6 +59,13 @@ pub struct SourceInfo {
 #[derive(Clone, Debug)]
 pub struct Pragma(pub pt::SourceUnitPart);
 
+impl Pragma {
+    /// Whether this is a compiler version pragma (i.e., `pragma solidity ...;`)
+    pub fn is_solidity_version(&self) -> bool {
+        matches!(&self.0, pt::SourceUnitPart::PragmaDirective(_, ident, _) if ident.name == "solidity")
+    }
+}
+
 /// Custom serialize for pragma
 /// This is necessary because our templating library hacks into serialization
 impl Serialize for Pragma {
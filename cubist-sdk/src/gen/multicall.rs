@@ -0,0 +1,48 @@
+//! Generation of the Multicall3-compatible contract that calls are batched through (see
+//! [`Batch`](crate::core::Batch)).
+use crate::gen::backend::TEMPLATES;
+use crate::gen::interface::file::Pragma;
+use cubist_config::util::OrBug;
+use ethers::types::{Address, H160};
+use tera::Context;
+
+/// Name of the generated file (relative to a target's contracts directory) containing the
+/// multicall contract
+pub const MULTICALL_FILE: &str = "CubistMulticall.sol";
+/// Name of the multicall contract
+pub const MULTICALL_CONTRACT: &str = "CubistMulticall";
+/// CREATE2 salt the multicall contract is deployed with, so that it has the same address on
+/// every chain
+pub const MULTICALL_SALT: [u8; 32] = [0; 32];
+
+/// Address of the canonical Multicall3 contract, which is deployed at the same address on most
+/// public chains (see <https://www.multicall3.com>)
+pub const CANONICAL_MULTICALL: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
+/// Version pragma of the multicall contract when the target's contracts don't have any
+const DEFAULT_PRAGMA: &str = "pragma solidity ^0.8.0;";
+
+/// Returns the Solidity source of the multicall contract, with the given compiler version
+/// pragmas (those of the contracts it is compiled with, so that all of them are compiled with the
+/// same compiler)
+pub fn generate_multicall(pragmas: &[Pragma]) -> String {
+    let mut pragmas: Vec<String> = pragmas
+        .iter()
+        .filter(|pragma| pragma.is_solidity_version())
+        .map(|pragma| pragma.to_string())
+        .collect();
+    pragmas.sort();
+    pragmas.dedup();
+    if pragmas.is_empty() {
+        pragmas.push(DEFAULT_PRAGMA.to_owned());
+    }
+    let mut context = Context::new();
+    context.insert("MULTICALL", MULTICALL_CONTRACT);
+    context.insert("pragmas", &pragmas);
+    TEMPLATES
+        .render("multicall.tpl", &context)
+        .or_bug("Rendering 'multicall' template")
+}
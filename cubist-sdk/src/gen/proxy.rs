@@ -0,0 +1,31 @@
+//! Generation of the ERC-1967 proxy contracts that upgradeable contracts are deployed behind (see
+//! [`ContractsConfig::upgradeable`](cubist_config::ContractsConfig::upgradeable)).
+use crate::gen::backend::TEMPLATES;
+use cubist_config::util::OrBug;
+use tera::Context;
+
+/// Name of the generated file (relative to a target's contracts directory) containing the proxy
+/// contracts
+pub const PROXIES_FILE: &str = "CubistProxies.sol";
+/// Name of the base contract shared by all proxies
+pub const BASE_PROXY_CONTRACT: &str = "CubistERC1967Proxy";
+/// Name of the UUPS proxy contract
+pub const UUPS_PROXY_CONTRACT: &str = "CubistUUPSProxy";
+/// Name of the transparent proxy contract
+pub const TRANSPARENT_PROXY_CONTRACT: &str = "CubistTransparentProxy";
+/// Name of the contract administering transparent proxies
+pub const PROXY_ADMIN_CONTRACT: &str = "CubistProxyAdmin";
+/// Name of the proxy admin method that transfers ownership of the proxy admin
+pub const PROXY_ADMIN_TRANSFER_OWNERSHIP_METHOD_NAME: &str = "transferOwnership";
+
+/// Returns the Solidity source of the proxy contracts
+pub fn generate_proxies() -> String {
+    let mut context = Context::new();
+    context.insert("BASE_PROXY", BASE_PROXY_CONTRACT);
+    context.insert("UUPS_PROXY", UUPS_PROXY_CONTRACT);
+    context.insert("TRANSPARENT_PROXY", TRANSPARENT_PROXY_CONTRACT);
+    context.insert("PROXY_ADMIN", PROXY_ADMIN_CONTRACT);
+    TEMPLATES
+        .render("proxies.tpl", &context)
+        .or_bug("Rendering 'proxies' template")
+}
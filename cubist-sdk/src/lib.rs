@@ -94,6 +94,23 @@
 //! compiler (currently, `solc` is the only supported compiler for
 //! contracts written in [Solidity]).
 //!
+//! Targets are usually assigned per file, via the `files` globs in the
+//! config, but individual contracts can also be assigned a target with
+//! a NatSpec annotation, which takes precedence over the globs:
+//!
+//! ```solidity
+//! /// @custom:cubist-target polygon
+//! contract Sender { ... }
+//! ```
+//!
+//! Similarly, `/// @custom:cubist-expose` on a function forces it into
+//! the generated shim (even if no analyzed code calls it cross-chain),
+//! while `/// @custom:cubist-expose never` ensures it's never exposed
+//! (calling it cross-chain becomes a build error).  Contracts in the
+//! same file can't call each other cross-chain, since a shim replaces
+//! its whole source file; such conflicts are reported during
+//! `cubist pre-compile`.
+//!
 //! Once the contracts are compiled, we still need to write an app to
 //! interact with them (e.g., deploy them, invoke methods, run tests,
 //! etc.). For apps written in Rust, this SDK provides the necessary
@@ -210,6 +227,8 @@
 //!
 //!   // if 'Receiver' and 'Sender' were previously deployed using Cubist, and the deployment
 //!   // receipts are still on disk (in 'deploy' directory, by default), we can reload just them
+//!   // (receipts are kept per network profile and chain id, so receipts from other networks
+//!   // don't get in the way)
 //!   receiver.deployed().await.unwrap();
 //!   sender.deployed().await.unwrap();
 //!
@@ -226,6 +245,384 @@
 //! };
 //! ```
 //!
+//! ## Send transactions from different accounts
+//!
+//! Transactions are sent from the target's default sender (see
+//! [`CommonConfig::default_sender`](cubist_config::network::CommonConfig::default_sender)),
+//! unless another account is selected, either for a single contract or for a whole target:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//! use ethers::types::U256;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let sender = cubist.contract("Sender").expect("Contract 'Sender' not found");
+//!   sender.deployed().await.unwrap();
+//!
+//!   // call 'store' on the sender as the second account
+//!   let accounts = cubist.accounts_on(sender.target()).await.unwrap();
+//!   sender.as_signer(accounts[1]).send("store", U256::from(1)).await.unwrap();
+//!
+//!   // act as the second account on the sender's target (contracts are shared with 'cubist')
+//!   let user = cubist.with_sender(sender.target(), accounts[1]).unwrap();
+//!   let sender = user.contract("Sender").expect("Contract 'Sender' not found");
+//!   sender.send("store", U256::from(2)).await.unwrap();
+//! };
+//! ```
+//!
+//! ## Estimate deployment costs
+//!
+//! Deploying a contract sends transactions to several chains (the contract, its shims, and the
+//! CALLER-role grants on the shims it calls).  [`Contract::deploy`] refuses to start if the
+//! sender on any of them can't afford its share (failing with
+//! [`CubistSdkError::InsufficientFunds`]), but the per-chain costs can also be checked upfront:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let receiver = cubist.contract("Receiver").expect("Contract 'Receiver' not found");
+//!   let estimate = receiver.estimate_deploy(()).await.unwrap();
+//!   print!("{estimate}");
+//!   for chain in estimate.underfunded() {
+//!     println!("{} needs {} more wei", chain.target, chain.cost() - chain.balance);
+//!   }
+//! };
+//! ```
+//!
+//! ## Sign transactions offline
+//!
+//! Rather than sending transactions, deployments and calls can add them (unsigned, with their
+//! nonces, gas, and fees filled in) to a [`core::TxBundle`], which is written to a file, signed
+//! offline (e.g., with a hardware wallet), and broadcast later (completing the deployments):
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_sdk::core::TxBundle;
+//! use cubist_config::Config;
+//! use ethers::types::U256;
+//! use std::path::Path;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let receiver = cubist.contract("Receiver").expect("Contract 'Receiver' not found");
+//!   let mut bundle = TxBundle::default();
+//!   receiver.deploy_unsigned(&mut bundle, ()).await.unwrap();
+//!   receiver.send_unsigned(&mut bundle, "store", U256::from(1)).await.unwrap();
+//!   bundle.write_file(Path::new("unsigned.json")).unwrap();
+//!
+//!   // ... sign every transaction (filling in `signed`) ...
+//!
+//!   let signed = TxBundle::from_file(Path::new("signed.json")).unwrap();
+//!   cubist.broadcast(&signed).await.unwrap();
+//! };
+//! ```
+//!
+//! ## Propose admin operations to a Safe
+//!
+//! If a Safe (multisig) administers a chain (see
+//! [`CommonConfig::safe`](cubist_config::network::CommonConfig::safe)), admin operations on that
+//! chain (granting the CALLER role on shims, updating Axelar receiver shims, and transferring
+//! ownership) are not sent from the sender.  Instead, they are added to Safe transaction batches
+//! (to import into the Safe Transaction Builder), and recorded as pending in the deployment
+//! manifest until the Safe executes them:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let sender = cubist.contract("Sender").expect("Contract 'Sender' not found");
+//!   sender.deploy(()).await.unwrap();
+//!   for proposal in sender.pending_safe_proposals() {
+//!     println!("Import {} into the Safe on {}", proposal.batch.display(), proposal.target);
+//!   }
+//!   // ... once the Safe executed the proposed transactions ...
+//!   sender.clear_safe_proposals().await.unwrap();
+//! };
+//! ```
+//!
+//! ## Manage the roles of shims
+//!
+//! Shims are owned by the Safe administering their chain (see
+//! [`safe`](cubist_config::network::CommonConfig::safe)), if any, and otherwise by the account
+//! that deployed them; the owner grants (and revokes) the CALLER role that contracts need to call
+//! them.  Ownership is transferred in two steps (so it can't be
+//! transferred to an account that can't use it), and admin transactions on shims are sent from
+//! (or, if that is the Safe administering the chain, proposed to) the shim's owner:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::{Config, Target};
+//! use ethers::types::Address;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let receiver = cubist.contract("Receiver").expect("Contract 'Receiver' not found");
+//!   receiver.deployed().await.unwrap();
+//!   let new_owner = Address::repeat_byte(0x0f);
+//!   receiver.transfer_shim_ownership(new_owner).await.unwrap();
+//!   receiver.accept_shim_ownership().await.unwrap();
+//!   for roles in receiver.shim_roles().await.unwrap() {
+//!     println!("{}: owner {:?}, callers {:?}", roles.target, roles.owner, roles.callers);
+//!   }
+//!   let old_caller = Address::repeat_byte(0x0c);
+//!   receiver.revoke_caller(Target::Ethereum, old_caller).await.unwrap();
+//! };
+//! ```
+//!
+//! ## Pause shims
+//!
+//! In an emergency (e.g., when an exploit is found), pausing the shims of a contract stops all
+//! cross-chain calls to it: paused shims reject calls, and the relayer skips their events.  The
+//! owner of the shims can grant the GUARDIAN role to accounts that may (only) pause and unpause
+//! them:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//! use ethers::types::Address;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let receiver = cubist.contract("Receiver").expect("Contract 'Receiver' not found");
+//!   receiver.deployed().await.unwrap();
+//!   receiver.set_shim_guardian(Address::repeat_byte(0x0a), true).await.unwrap();
+//!   for (target, outcome) in receiver.pause_shims().await.unwrap() {
+//!     match outcome {
+//!       Ok(_) => println!("Paused on {target}"),
+//!       Err(e) => println!("Failed to pause on {target}: {e}"),
+//!     }
+//!   }
+//!   // ... once the exploit is fixed ...
+//!   receiver.unpause_shims().await.unwrap();
+//! };
+//! ```
+//!
+//! ## Upgrade contracts
+//!
+//! Contracts listed in
+//! [`ContractsConfig::upgradeable`](cubist_config::ContractsConfig::upgradeable) are deployed
+//! behind an ERC-1967 (transparent or UUPS) proxy, whose address is the contract's address (and
+//! the one its shims call).  Deployment manifests record the proxy's implementation, which can be
+//! replaced once the contract has been changed and rebuilt:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//! use ethers::types::U256;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let vault = cubist.contract("Vault").expect("Contract 'Vault' not found");
+//!   // deploy and initialize the proxy (by calling 'initialize' on the implementation)
+//!   vault.deploy_with_initializer((), "initialize", U256::from(100)).await.unwrap();
+//!
+//!   // later: switch to a new implementation (fails if the new storage layout is incompatible)
+//!   vault.upgrade(()).await.unwrap();
+//! };
+//! ```
+//!
+//! ## Wait for confirmations
+//!
+//! Transactions are considered final once they have the number of confirmations configured for
+//! their target (see
+//! [`confirmations`](cubist_config::network::CommonConfig::confirmations)), which can be
+//! overridden per call.  Waiting gives up after a (configurable) timeout, with an error that
+//! carries the hash of the transaction, so that waiting can be resumed:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_sdk::core::ReceiptOptions;
+//! use cubist_config::Config;
+//! use ethers::types::U256;
+//! use std::time::Duration;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let sender = cubist.contract("Sender").expect("Contract 'Sender' not found");
+//!   sender.deployed().await.unwrap();
+//!   let options = ReceiptOptions {
+//!     confirmations: Some(3),
+//!     timeout: Some(Duration::from_secs(300)),
+//!     ..Default::default()
+//!   };
+//!   match sender.with_receipt_options(options).send("store", U256::from(1)).await {
+//!     Ok(_) => {}
+//!     Err(e) => match e.timed_out_tx() {
+//!       Some(tx_hash) => {
+//!         sender.project.wait_for_receipt(tx_hash).await.unwrap();
+//!       }
+//!       None => panic!("{e}"),
+//!     },
+//!   }
+//! };
+//! ```
+//!
+//! ## Simulate transactions
+//!
+//! A transaction can be simulated (with `eth_call`) before it is sent, to find out whether (and
+//! why) it would revert.  The revert reason (an `Error(string)`, a `Panic(uint256)`, or one of
+//! the contract's custom errors) is decoded into [`CubistSdkError::Reverted`].  Setting
+//! [`simulate`](cubist_config::network::CommonConfig::simulate) for a chain simulates every
+//! transaction sent to it:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//! use ethers::types::U256;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let sender = cubist.contract("Sender").expect("Contract 'Sender' not found");
+//!   sender.deployed().await.unwrap();
+//!   match sender.simulate("store", U256::from(1)).await {
+//!     Err(CubistSdkError::Reverted { reason, .. }) => println!("'store' would revert: {reason}"),
+//!     result => result.unwrap(),
+//!   }
+//! };
+//! ```
+//!
+//! ## Batch calls
+//!
+//! Calls to deployed contracts can be batched (see [`core::Batch`]): they are grouped per target
+//! into calls to a Multicall3 contract, so that reading many values takes a single round trip per
+//! target, and each result is decoded back into the type of the call that produced it.  A call
+//! that reverts yields its revert reason without failing the rest of the batch.  The same batch
+//! can also be sent as a single transaction per target, in which either all calls succeed or none
+//! do (note that the calls are then made by the Multicall3 contract, i.e., it is their
+//! `msg.sender`).
+//!
+//! The Multicall3-compatible contract is generated by `cubist build`, predeployed on local chains
+//! by `cubist start` (or deployed via the CREATE2 factory on first use), unless an existing
+//! Multicall3 contract is configured (see
+//! [`multicall`](cubist_config::network::CommonConfig::multicall)).  On chains that Cubist doesn't
+//! start itself, the canonical Multicall3 contract is used instead:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//! use ethers::types::U256;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let sender = cubist.contract("Sender").expect("Contract 'Sender' not found");
+//!   let receiver = cubist.contract("Receiver").expect("Contract 'Receiver' not found");
+//!   sender.deployed().await.unwrap();
+//!   receiver.deployed().await.unwrap();
+//!
+//!   // read values from both chains at once
+//!   let mut batch = cubist.batch();
+//!   let sent = batch.add::<_, U256>(&sender, "retrieve", ()).unwrap();
+//!   let received = batch.add::<_, U256>(&receiver, "retrieve", ()).unwrap();
+//!   let results = batch.call().await.unwrap();
+//!   println!("{} vs {}", results.get(sent).unwrap(), results.get(received).unwrap());
+//!
+//!   // store two values in a single (atomic) transaction
+//!   let mut batch = cubist.batch();
+//!   batch.add::<_, ()>(&sender, "store", U256::from(1)).unwrap();
+//!   batch.add::<_, ()>(&sender, "store", U256::from(2)).unwrap();
+//!   let _receipts = batch.send_atomic().await.unwrap();
+//! };
+//! ```
+//!
+//! ## Snapshot and revert
+//!
+//! Tests that share running local chains can reset them (along with the deployment manifests,
+//! receipts, and history in the deploy directory) instead of redeploying everything: a snapshot
+//! of all target chains can be reverted to repeatedly.  Chains that can't be snapshot (e.g.,
+//! Avalanche) are reported rather than silently skipped:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::Config;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let mut snapshot = cubist.snapshot().await.unwrap();
+//!   for (target, reason) in snapshot.unsupported() {
+//!     println!("{target} won't be reverted: {reason}");
+//!   }
+//!
+//!   // ... deploy and test against a fresh Cubist instance ...
+//!   let test_cubist = cubist.clone();
+//!   test_cubist.contract("Sender").unwrap().deploy(()).await.unwrap();
+//!
+//!   // back to where we started (and ready to revert again after the next test)
+//!   snapshot.revert().await.unwrap();
+//! };
+//! ```
+//!
+//! ## Manipulate time
+//!
+//! Time-dependent logic (e.g., timelocks or vesting) can be tested by moving the clocks of all
+//! target chains forward together, or by mining blocks on a given chain.  Chains that don't
+//! support this (everything but Ethereum, i.e., anvil) fail with
+//! [`CubistSdkError::TimeControlUnsupported`]:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_config::{Config, Target};
+//! use std::time::Duration;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   // a day passes on all chains
+//!   cubist.advance_time(Duration::from_secs(24 * 60 * 60)).await.unwrap();
+//!   // 10 more blocks on Ethereum
+//!   cubist.mine_blocks(Target::Ethereum, 10).await.unwrap();
+//! };
+//! ```
+//!
+//! ## Subscribe to events
+//!
+//! Events emitted by deployed contracts can be streamed (decoded) from one or several targets,
+//! optionally backfilling past events from a given block.  Each event carries the target it was
+//! emitted on, along with its transaction hash and log index:
+//! ```
+//! use cubist_sdk::*;
+//! use cubist_sdk::core::EventFilter;
+//! use cubist_config::Config;
+//! use futures::StreamExt;
+//!
+//! async {
+//!   let cubist = Cubist::<Http>::new(Config::nearest().unwrap()).await.unwrap();
+//!   let receiver = cubist.contract("Receiver").expect("Contract 'Receiver' not found");
+//!   receiver.deployed().await.unwrap();
+//!
+//!   // 'Stored' events emitted by the receiver from now on
+//!   let mut events = receiver.events("Stored", None).unwrap();
+//!   while let Some(event) = events.next().await {
+//!     let event = event.unwrap();
+//!     println!("{:?} on {} in tx {:?}", event.data, event.meta.target, event.meta.tx_hash);
+//!   }
+//!
+//!   // all events of all deployed contracts, including past ones (from block 0) on Ethereum
+//!   let filter = EventFilter {
+//!     from_block: [(cubist_config::Target::Ethereum, 0)].into(),
+//!     ..Default::default()
+//!   };
+//!   let mut events = cubist.events(filter).unwrap();
+//!   let _first = events.next().await;
+//! };
+//! ```
+//!
+//! The generated Rust ORM additionally exposes strongly typed event streams (see
+//! [`Contract<M>::typed_events`]).
+//!
+//! ## Test networks
+//!
+//! Instead of running `cubist start` before `cargo test`, tests can start their own local chains
+//! (on free ports) and relayer in-process.  Each [`testing::TestNetwork`] uses its own ports and
+//! deploy directory, so tests running in parallel don't interfere with each other; everything is
+//! stopped when the network is dropped:
+//! ```
+//! use cubist_sdk::testing::TestNetwork;
+//!
+//! async {
+//!   let network = TestNetwork::builder().start().await.unwrap();
+//!   let cubist = network.cubist();
+//!   let receiver = cubist.contract("Receiver").unwrap();
+//!   let sender = cubist.contract("Sender").unwrap();
+//!   receiver.deploy(()).await.unwrap();
+//!   sender.deploy(receiver.address_on(sender.target())).await.unwrap();
+//!   assert!(cubist.when_bridged(None).await);
+//! };
+//! ```
+//!
 //! [Cubist]: https://cubist.dev
 //! [Solidity]: https://soliditylang.org/
 //! [Ethereum]: cubist_config::Target::Ethereum
@@ -254,13 +651,14 @@
 //! change! The [`Contract<M>`] type will likely be decoupled into
 //! "ContractFactory" and "Contract".
 
-use crate::core::{HttpStack, WsStack};
+use crate::core::{HttpStack, RevertReason, WsStack};
 use cubist_config::secret::SecretUrl;
 use cubist_config::{paths::ContractFQN, ConfigError, Target};
 use ethers::types::{ParseBytesError, H256};
 use solang_parser::diagnostics::Diagnostic;
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Contract and project management data structures.
@@ -269,6 +667,10 @@ pub mod core;
 pub mod gen;
 /// Utilities for parsing contract files
 pub mod parse;
+/// Relaying of events from shims to their target contracts.
+pub mod relayer;
+/// In-process test networks (local chains plus relayer) for tests.
+pub mod testing;
 
 /// Module for analyzing contracts.
 mod analyze;
@@ -315,6 +717,14 @@ pub enum WrapperError {
     SendTransactionError(String, Target, String),
     #[error("Could not fetch transaction with hash '{0}' @ {1}. Reason: {2}")]
     TransactionNotFound(H256, Target, String),
+    #[error("Transaction {0:?} not confirmed on {1} within {2:?}")]
+    ReceiptTimeout(H256, Target, Duration),
+    #[error(transparent)]
+    WatchError(#[from] notify::Error),
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+    #[error(transparent)]
+    ChannelError(#[from] futures::channel::mpsc::SendError),
 }
 
 /// Errors raised by this crate.
@@ -343,6 +753,9 @@ pub enum CubistSdkError {
     /// Error raised when a compilation fails.
     #[error("Error compiling {0}: {1}")]
     CompileError(PathBuf, String, #[source] Option<Box<WrapperError>>),
+    /// Error raised when a compiler setting is not supported by the compiler.
+    #[error("Unsupported compiler setting: {0}")]
+    UnsupportedCompilerSetting(String),
     /// Error raised when cleaning build directory fails
     #[error("Error cleaning project build directory {0}")]
     CleanError(PathBuf, #[source] Box<WrapperError>),
@@ -368,6 +781,20 @@ pub enum CubistSdkError {
         #[source]
         source: Box<WrapperError>,
     },
+    /// Error raised when a transaction (or its simulation) reverts.
+    #[error(
+        "Calling '{method_name}' on contract '{contract}' on chain '{target}' reverted: {reason}"
+    )]
+    Reverted {
+        /// Contract name,
+        contract: ContractFQN,
+        /// Target chain.
+        target: Target,
+        /// Method whose call reverted.
+        method_name: String,
+        /// Decoded revert reason
+        reason: RevertReason,
+    },
     /// Error raised when updating a shim contract's access control fails.
     #[error("Failed to add '{caller_contract}' to approved callers of shim '{shim_contract}' on chain '{target}'")]
     ApproveCallerError {
@@ -394,9 +821,110 @@ pub enum CubistSdkError {
         /// Target chain.
         target: Target,
     },
+    /// Error raised when a contract has no shim on a chain that one is expected on.
+    #[error("Contract '{0}' has no shim on {1}")]
+    MissingShim(ContractFQN, Target),
+    /// Error raised when an upgrade is attempted for a contract that is not deployed behind a proxy.
+    #[error("Contract '{0}' on {1} is not upgradeable (see 'contracts.upgradeable' in cubist-config.json)")]
+    NotUpgradeable(ContractFQN, Target),
+    /// Error raised when the generated proxy contracts are missing.
+    #[error("Proxy contract '{0}' not found on target {1}. Did you run 'cubist build' after marking contracts as upgradeable?")]
+    MissingProxyContract(String, Target),
+    /// Error raised when the generated multicall contract is missing.
+    #[error("Multicall contract not found on target {0}. Did you run 'cubist build'? (Alternatively, configure the address of an existing Multicall3 contract with 'multicall'.)")]
+    MissingMulticallContract(Target),
+    /// Error raised when there is no Multicall3 contract to batch calls through on a chain that
+    /// Cubist doesn't start locally (where it doesn't deploy its own).
+    #[error("No Multicall3 contract found on {0}. Configure the address of an existing one with 'multicall' (Cubist only deploys its own on chains it starts locally).")]
+    NoMulticallContract(Target),
+    /// Error raised when executing a batch of calls (see [`core::Batch`]) fails
+    #[error("Error executing batch of calls on {0}")]
+    BatchError(Target, #[source] Box<WrapperError>),
+    /// Error raised when the storage layout needed to check an upgrade is not available.
+    #[error("Storage layout of contract '{0}' on {1} is not available: {2}")]
+    MissingStorageLayout(ContractFQN, Target, String),
+    /// Error raised when the storage layout of a new implementation is incompatible with the
+    /// storage layout of the deployed implementation.
+    #[error("Cannot upgrade contract '{0}' on {1} due to incompatible storage layout:\n  {}", .2.join("\n  "))]
+    IncompatibleStorageLayout(ContractFQN, Target, Vec<String>),
+    /// Error raised when upgrading a contract fails
+    #[error("Error upgrading contract '{0}' on {1}")]
+    UpgradeError(ContractFQN, Target, #[source] Box<WrapperError>),
+    /// Error raised when subscribing to (or polling for) contract events fails
+    #[error("Error streaming events of contract '{0}' on {1}")]
+    EventsError(ContractFQN, Target, #[source] Box<WrapperError>),
+    /// Error raised when subscribing to an event that none of the given contracts declares
+    #[error("Event '{0}' not declared by contract(s) {}", .1.join(", "))]
+    UnknownEvent(String, Vec<String>),
+    /// Error raised when loading deployment manifest fails
+    #[error("Failed to load deployment manifest from file {0}")]
+    LoadDeploymentManifestError(PathBuf, #[source] Box<WrapperError>),
     /// Error raised when saving deployment manifest fails
     #[error("Failed to save deployment manifest to file {0}")]
     SaveDeploymentManifestError(PathBuf, #[source] Box<WrapperError>),
+    /// Error raised when saving (or restoring) the snapshot of the deploy directory fails
+    #[error("Failed to snapshot (or restore) deploy directory {0}")]
+    SnapshotDeployDirError(PathBuf, #[source] Box<WrapperError>),
+    /// Error raised when reverting chains to a snapshot fails
+    #[error("Failed to revert to snapshot on {}", .0.iter().map(|(t, e)| format!("{t} ({e})")).collect::<Vec<_>>().join(", "))]
+    RevertSnapshotError(Vec<(Target, String)>),
+    /// Error raised when estimating the cost of a deployment fails
+    #[error("Failed to estimate deployment cost on {0}")]
+    EstimateError(Target, #[source] Box<WrapperError>),
+    /// Error raised when the sender on some chain can't afford a deployment (see
+    /// [`core::DeploymentEstimate::check`])
+    #[error("Insufficient funds for deployment:\n{0}")]
+    InsufficientFunds(core::DeploymentEstimate),
+    /// Error raised when manipulating time (or mining blocks) on a chain that doesn't support it
+    #[error("Cannot manipulate time on {0}: {1}")]
+    TimeControlUnsupported(Target, String),
+    /// Error raised when manipulating time (or mining blocks) fails
+    #[error("Failed to manipulate time on {0}")]
+    TimeControlError(Target, #[source] Box<WrapperError>),
+    /// Error raised when the relayer can't find a contract listed in a deployment manifest
+    #[error("Contract '{0}' not found for '{1}'")]
+    MissingRelayedContract(ContractFQN, Target),
+    /// Error raised when the relayer can't find a shim listed in a deployment manifest
+    #[error("Shim '{0}' not found for '{1}'")]
+    MissingRelayedShim(ContractFQN, Target),
+    /// Error raised when the relayer can't listen on its endpoint (see
+    /// [`Config::relayer_endpoint`](cubist_config::Config::relayer_endpoint))
+    #[error("Failed to listen on relayer endpoint {0}")]
+    RelayerEndpointError(String, #[source] io::Error),
+    /// Error raised when relaying events (see [`relayer::Relayer`]) fails
+    #[error("Relayer failed: {0}")]
+    RelayerError(#[source] Box<WrapperError>),
+    /// Error raised when starting a test network (see [`testing::TestNetwork`]) fails
+    #[error("Failed to start test network: {0}")]
+    TestNetworkError(String),
+    /// Error raised when reading (or writing) a transaction bundle (see [`core::TxBundle`]) fails
+    #[error("Failed to access transaction bundle {0}")]
+    TxBundleError(PathBuf, #[source] Box<WrapperError>),
+    /// Error raised when preparing a transaction for offline signing fails
+    #[error("Failed to prepare transaction '{0}' for offline signing")]
+    BundleTxError(String, #[source] Box<WrapperError>),
+    /// Error raised when a contract can't be deployed through a transaction bundle
+    #[error("Cannot deploy contract '{0}' on {1} offline: {2}")]
+    OfflineDeployUnsupported(ContractFQN, Target, String),
+    /// Error raised when broadcasting a transaction of a transaction bundle fails
+    #[error("Failed to broadcast transaction '{0}'")]
+    BroadcastError(String, #[source] Box<WrapperError>),
+    /// Error raised when proposing an admin transaction to a Safe (see [`core::SafeProposal`])
+    /// fails
+    #[error("Failed to propose '{0}' to the Safe on {1}")]
+    SafeProposalError(String, Target, #[source] Box<WrapperError>),
+    /// Error raised when reading (or writing) the journal of a deployment in progress fails
+    #[error("Failed to access deployment journal {0}")]
+    DeploymentJournalError(PathBuf, #[source] Box<WrapperError>),
+    /// Error raised when an interrupted deployment can't be resumed
+    #[error("Cannot resume deployment of '{0}' on {1}: {2}")]
+    ResumeDeploymentError(ContractFQN, Target, String),
+    /// Error raised when loading the deployment history fails
+    #[error("Failed to load deployment history from file {0}")]
+    LoadDeploymentHistoryError(PathBuf, #[source] Box<WrapperError>),
+    /// Error raised when appending to the deployment history fails
+    #[error("Failed to save deployment history to file {0}")]
+    SaveDeploymentHistoryError(PathBuf, #[source] Box<WrapperError>),
     /// Error raised when saving deployment receipt fails
     #[error("Failed to save deployment receipt to file {0}")]
     SaveDeploymentReceiptError(PathBuf, #[source] Box<WrapperError>),
@@ -409,6 +937,12 @@ pub enum CubistSdkError {
     /// Failed to parse contract file
     #[error("Error parsing file {0}: {1:#?}")]
     ParseError(PathBuf, Vec<Diagnostic>),
+    /// A file contained a malformed Cubist annotation (e.g., `@custom:cubist-target`)
+    #[error("Invalid annotation in file {0}: {1}")]
+    InvalidAnnotation(PathBuf, String),
+    /// A contract was annotated with a target that is not configured
+    #[error("Contract {0} is annotated with target '{1}', which is not configured in 'contracts.targets'")]
+    UnconfiguredAnnotatedTarget(String, Target),
     /// A file contained a unicode import
     #[error("Unsupported unicode import {0} in file {1}")]
     UnicodeImportError(String, PathBuf),
@@ -427,6 +961,9 @@ pub enum CubistSdkError {
     /// Error raised when retrieving accounts from the chain provider fails
     #[error("Failed to retrieve accounts for chain '{0}': {1}")]
     AccountsError(Target, String),
+    /// Error raised when retrieving the chain id from the chain provider fails
+    #[error("Failed to retrieve chain id of chain '{0}': {1}")]
+    ChainIdError(Target, String),
     /// Error raised when retrieving balance from the chain provider fails
     #[error("Failed to retrieve balance of account '{0}' on chain '{1}': {2}")]
     GetBalanceError(String, Target, String),
@@ -450,5 +987,21 @@ pub enum CubistSdkError {
     ConfigError(#[from] cubist_config::ConfigError),
 }
 
+impl CubistSdkError {
+    /// Returns the hash of the transaction whose receipt this error timed out waiting for (see
+    /// [`WrapperError::ReceiptTimeout`]), if any.  Waiting can be resumed with
+    /// [`TargetProject::wait_for_receipt`].
+    pub fn timed_out_tx(&self) -> Option<H256> {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(e) = source {
+            if let Some(WrapperError::ReceiptTimeout(tx_hash, ..)) = e.downcast_ref() {
+                return Some(*tx_hash);
+            }
+            source = e.source();
+        }
+        None
+    }
+}
+
 /// Result with error type defaulting to [`CubistSdkError`].
 pub type Result<T, E = CubistSdkError> = ::core::result::Result<T, E>;
@@ -1,12 +1,17 @@
 /// Module for the representation of source files and their AST
 pub mod source_file;
 
-use crate::Result;
-use cubist_config::ContractsConfig;
+use crate::{CubistSdkError, Result};
+use cubist_config::{ContractName, ContractsConfig, Target};
 use solang_parser::pt;
 use source_file::SourceFile;
+use std::collections::{BTreeSet as OrdSet, HashMap};
 
-/// Parses a set of files
+/// Parses a set of files.
+///
+/// Each file runs on the target whose `files` globs match it, unless some of its contracts are
+/// annotated with `@custom:cubist-target <target>`; in that case, the file is split into one
+/// [`SourceFile`] per target, each containing only the contracts running on that target.
 pub fn parse_files(config: &ContractsConfig) -> Result<Vec<SourceFile>> {
     let sources = config
         .targets
@@ -19,7 +24,38 @@ pub fn parse_files(config: &ContractsConfig) -> Result<Vec<SourceFile>> {
                 .collect::<Vec<_>>()
         })
         .collect::<Result<Vec<_>>>()?;
-    Ok(sources)
+    let mut split_sources = vec![];
+    for source in sources {
+        split_sources.extend(split_by_target(source, config)?);
+    }
+    Ok(split_sources)
+}
+
+/// Splits `source` according to the `@custom:cubist-target` annotations of its contracts.
+fn split_by_target(source: SourceFile, config: &ContractsConfig) -> Result<Vec<SourceFile>> {
+    let mut by_target: HashMap<Target, OrdSet<ContractName>> = HashMap::new();
+    let mut targets = vec![];
+    for name in source.contract_names() {
+        let target = match source.annotations.targets.get(&name) {
+            Some(target) if !config.targets.contains_key(target) => {
+                return Err(CubistSdkError::UnconfiguredAnnotatedTarget(name, *target))
+            }
+            Some(target) => *target,
+            None => source.target,
+        };
+        if !by_target.contains_key(&target) {
+            targets.push(target);
+        }
+        by_target.entry(target).or_default().insert(name);
+    }
+    // Nothing to split
+    if targets.is_empty() || targets == [source.target] {
+        return Ok(vec![source]);
+    }
+    Ok(targets
+        .into_iter()
+        .map(|target| source.restricted_to(target, by_target.remove(&target).unwrap()))
+        .collect())
 }
 
 /// Returns the path part of an import (i.e., the part that refers to the actual file to import).
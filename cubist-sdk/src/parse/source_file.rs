@@ -11,18 +11,26 @@ use crate::{
     CubistSdkError, Result,
 };
 use convert_case::{Case, Casing};
-use cubist_config::{Config, ContractName, Target};
+use cubist_config::{Config, ContractName, FunctionName, Target};
 use cubist_util::fs::is_within;
 use solang_parser::pt;
 use soroban_env_host::xdr::ScSpecEntry;
+use std::collections::{BTreeMap as Map, BTreeSet as OrdSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 const STELLAR_IMPORT_PREFIX: &str = "stellar://";
 
+/// NatSpec tag for assigning a contract to a target (e.g., `/// @custom:cubist-target polygon`)
+const TARGET_TAG: &str = "@custom:cubist-target";
+
+/// NatSpec tag for forcing or forbidding cross-chain exposure of a function
+/// (e.g., `/// @custom:cubist-expose never`)
+const EXPOSE_TAG: &str = "@custom:cubist-expose";
+
 /// A source file with its AST and additional meta information
 // TODO: Make trait
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SourceFile {
     /// The source file's absolute path
     pub file_name: PathBuf,
@@ -32,10 +40,43 @@ pub struct SourceFile {
     pub target: Target,
     /// Information related to the content of the file
     pub content: SourceFileContent,
+    /// Cubist annotations found in the file's NatSpec comments
+    pub annotations: Annotations,
+    /// The contracts in the file that run on `target`, if the file's contracts are split across
+    /// multiple targets (via `@custom:cubist-target` annotations); `None` means all of them.
+    contracts: Option<OrdSet<ContractName>>,
+}
+
+/// Cubist annotations on contracts and functions, written as custom NatSpec tags:
+///
+/// ```solidity
+/// /// @custom:cubist-target polygon
+/// contract PolyStorage {
+///     /// @custom:cubist-expose never
+///     function reset() public { ... }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Annotations {
+    /// Targets assigned to contracts with `@custom:cubist-target <target>`. These take
+    /// precedence over the targets implied by the config globs.
+    pub targets: Map<ContractName, Target>,
+    /// Cross-chain exposure of functions, set with `@custom:cubist-expose [always|never]`
+    pub exposure: Map<ContractName, Map<FunctionName, Exposure>>,
+}
+
+/// Cross-chain exposure of a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exposure {
+    /// Always expose the function cross-chain (if its contract is exposed at all), even if it's
+    /// never called cross-chain from analyzed code.
+    Always,
+    /// Never expose the function cross-chain; calling it cross-chain is an error.
+    Never,
 }
 
 /// Content of a source file
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SourceFileContent {
     /// Solidity content
     SolidityContent {
@@ -57,17 +98,29 @@ impl SourceFile {
     pub fn new(file: impl AsRef<Path>, rel_path: PathBuf, target: Target) -> Result<Self> {
         let file_name = file.as_ref().to_path_buf();
 
-        let content = match target {
+        let (content, annotations) = match target {
             Target::Stellar => {
                 let wasm = fs::read(file).unwrap();
                 let spec_entries = soroban_spec::read::from_wasm(&wasm).unwrap();
-                SourceFileContent::SorobanContent { spec_entries }
+                (
+                    SourceFileContent::SorobanContent { spec_entries },
+                    Annotations::default(),
+                )
             }
             _ => {
                 let code = fs::read_to_string(file.as_ref())
                     .map_err(|e| CubistSdkError::ReadFileError(file.as_ref().into(), e))?;
                 match solang_parser::parse(&code, 0) {
-                    Ok((pt, comments)) => SourceFileContent::SolidityContent { pt, comments },
+                    Ok((pt, comments)) => {
+                        let annotations =
+                            Annotations::parse(&code, &pt, &comments).map_err(|msg| {
+                                CubistSdkError::InvalidAnnotation(file_name.clone(), msg)
+                            })?;
+                        (
+                            SourceFileContent::SolidityContent { pt, comments },
+                            annotations,
+                        )
+                    }
                     Err(es) => Err(CubistSdkError::ParseError(file_name.clone(), es))?,
                 }
             }
@@ -78,9 +131,34 @@ impl SourceFile {
             rel_path,
             target,
             content,
+            annotations,
+            contracts: None,
         })
     }
 
+    /// Returns a copy of this source file that only contains `contracts`, running on `target`.
+    /// Used when a file's contracts are assigned to different targets.
+    pub fn restricted_to(&self, target: Target, contracts: OrdSet<ContractName>) -> Self {
+        SourceFile {
+            target,
+            contracts: Some(contracts),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if this source file was restricted to a subset of the file's contracts (see
+    /// [`SourceFile::restricted_to`])
+    pub fn is_partial(&self) -> bool {
+        self.contracts.is_some()
+    }
+
+    /// Returns whether contract `name` (defined in this file) runs on this file's target
+    fn hosts(&self, name: &ContractName) -> bool {
+        self.contracts
+            .as_ref()
+            .map_or(true, |contracts| contracts.contains(name))
+    }
+
     /// Returns a list of contracts in the file
     pub fn contract_names(&self) -> Vec<ContractName> {
         match &self.content {
@@ -90,6 +168,7 @@ impl SourceFile {
                         pt::SourceUnitPart::ContractDefinition(cd) => Some(cd.name.name.clone()),
                         _ => None,
                     })
+                    .filter(|name| self.hosts(name))
                     .collect()
             }
             SourceFileContent::SorobanContent { .. } => {
@@ -184,7 +263,7 @@ impl SourceFile {
                     if let pt::SourceUnitPart::ContractDefinition(cd) = part {
                         let contract_name = &cd.name.name;
                         // Are we supposed to generate an interface for this contract?
-                        if !config.gen_contract(contract_name) {
+                        if !self.hosts(contract_name) || !config.gen_contract(contract_name) {
                             continue;
                         }
                         let interface = ContractInterface::new(config, cd)?;
@@ -208,8 +287,12 @@ impl SourceFile {
     ///     This is a problem since Cubist copies the contents of the contracts dir.
     /// (2) Relative paths that point outside the contracts root directory.
     ///     This is a problem for the same reason as the previous.
+    ///
+    /// Imports that are resolved through one of the target's remappings (e.g., `forge-std/`
+    /// in Foundry projects) are left to the compiler.
     pub fn check_imports(&self, config: &Config) -> Result<()> {
         let root_dir = &config.contracts().root_dir;
+        let compiler_config = config.get_target_compiler_config(self.target);
         for import in self.import_directives() {
             let import_lit = match import {
                 pt::Import::Plain(s, ..) => s,
@@ -225,6 +308,9 @@ impl SourceFile {
             if import_lit.string.starts_with(STELLAR_IMPORT_PREFIX) {
                 continue;
             }
+            if compiler_config.remap(&import_lit.string).is_some() {
+                continue;
+            }
             if import_lit.unicode {
                 return Err(CubistSdkError::UnicodeImportError(
                     import_lit.string,
@@ -285,3 +371,109 @@ impl SourceFile {
         Ok(())
     }
 }
+
+impl Annotations {
+    /// Collects the Cubist annotations from the NatSpec comments that immediately precede
+    /// contract and function definitions.  Returns a description of the problem if an annotation
+    /// is malformed.
+    fn parse(code: &str, pt: &pt::SourceUnit, comments: &[pt::Comment]) -> Result<Self, String> {
+        let mut annotations = Annotations::default();
+        for part in &pt.0 {
+            let cd = match part {
+                pt::SourceUnitPart::ContractDefinition(cd) => cd,
+                _ => continue,
+            };
+            let contract_name = &cd.name.name;
+            for (tag, value) in doc_tags(code, &cd.loc, comments) {
+                let location = format!("'{tag}' on contract {contract_name}");
+                if tag != TARGET_TAG {
+                    return Err(format!("Unexpected {location} (allowed only on functions)"));
+                }
+                let value = value.ok_or_else(|| format!("Missing target in {location}"))?;
+                let target = value
+                    .parse::<Target>()
+                    .map_err(|_| format!("Unknown target '{value}' in {location}"))?;
+                annotations.targets.insert(contract_name.clone(), target);
+            }
+            for cp in &cd.parts {
+                let (fd, function_name) = match cp {
+                    pt::ContractPart::FunctionDefinition(fd) => match &fd.name {
+                        Some(name) => (fd, &name.name),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                for (tag, value) in doc_tags(code, &fd.loc, comments) {
+                    let location = format!("'{tag}' on {contract_name}.{function_name}");
+                    if tag != EXPOSE_TAG {
+                        return Err(format!("Unexpected {location} (allowed only on contracts)"));
+                    }
+                    let exposure = match value {
+                        None | Some("always") => Exposure::Always,
+                        Some("never") => Exposure::Never,
+                        Some(value) => {
+                            return Err(format!(
+                                "Expected 'always' or 'never' in {location}, found '{value}'"
+                            ))
+                        }
+                    };
+                    annotations
+                        .exposure
+                        .entry(contract_name.clone())
+                        .or_default()
+                        .insert(function_name.clone(), exposure);
+                }
+            }
+        }
+        Ok(annotations)
+    }
+}
+
+/// Returns the Cubist tags (and their optional values) found in the comments immediately
+/// preceding the definition at `loc` (i.e., separated from it only by whitespace).
+fn doc_tags<'a>(
+    code: &str,
+    loc: &pt::Loc,
+    comments: &'a [pt::Comment],
+) -> Vec<(&'static str, Option<&'a str>)> {
+    let mut start = match loc {
+        pt::Loc::File(_, start, _) => *start,
+        _ => return vec![],
+    };
+    let mut tags = vec![];
+    for comment in comments.iter().rev() {
+        let (comment_start, comment_end, text) = match comment {
+            pt::Comment::Line(pt::Loc::File(_, s, e), text)
+            | pt::Comment::Block(pt::Loc::File(_, s, e), text)
+            | pt::Comment::DocLine(pt::Loc::File(_, s, e), text)
+            | pt::Comment::DocBlock(pt::Loc::File(_, s, e), text) => (*s, *e, text),
+            _ => continue,
+        };
+        if comment_end > start {
+            continue;
+        }
+        if !code
+            .get(comment_end..start)
+            .map_or(false, |gap| gap.trim().is_empty())
+        {
+            break;
+        }
+        for line in text.lines() {
+            let line = line
+                .trim()
+                .trim_start_matches('/')
+                .trim_start_matches('*')
+                .trim_end_matches("*/")
+                .trim();
+            let mut words = line.split_whitespace();
+            let tag = match words.next() {
+                Some(TARGET_TAG) => TARGET_TAG,
+                Some(EXPOSE_TAG) => EXPOSE_TAG,
+                _ => continue,
+            };
+            tags.push((tag, words.next()));
+        }
+        start = comment_start;
+    }
+    tags
+}
@@ -8,10 +8,10 @@ use notify::{
 
 use futures::{channel::mpsc::Sender, future::JoinAll, SinkExt};
 
-use cubist_sdk::core::DeploymentManifest;
+use crate::core::DeploymentManifest;
+use crate::{Result, WrapperError};
 
-use crate::stylist;
-use eyre::Result;
+use super::{relayer_err, stylist};
 use tokio::fs;
 use tracing::{debug, trace, warn};
 
@@ -46,7 +46,8 @@ impl DeploymentWatcher {
         let watcher = PollWatcher::new(
             event_handler,
             Config::default().with_poll_interval(poll_interval),
-        )?;
+        )
+        .map_err(relayer_err)?;
 
         Ok(DeploymentWatcher { watcher })
     }
@@ -61,7 +62,8 @@ impl DeploymentWatcher {
     pub fn watch(&mut self, dir: &Path) -> Result<()> {
         debug_assert!(dir.is_dir());
         self.watcher
-            .watch(dir, notify::RecursiveMode::NonRecursive)?;
+            .watch(dir, notify::RecursiveMode::NonRecursive)
+            .map_err(relayer_err)?;
         Ok(())
     }
 
@@ -128,8 +130,9 @@ impl DeploymentWatcher {
 
         // read all directory entries
         let mut result = Vec::new();
-        let mut read_result = fs::read_dir(manifest_dir).await?;
-        while let Some(dir_entry) = read_result.next_entry().await? {
+        let io_err = |e| relayer_err(WrapperError::IOError(manifest_dir.to_path_buf(), e));
+        let mut read_result = fs::read_dir(manifest_dir).await.map_err(io_err)?;
+        while let Some(dir_entry) = read_result.next_entry().await.map_err(io_err)? {
             // ignore all unreadable/invalid files
             if let Some(manifest) = Self::try_load_manifest(&dir_entry.path()).await {
                 result.push(manifest);
@@ -177,9 +180,10 @@ impl DeploymentWatcher {
 
 #[cfg(test)]
 mod tests {
+    use crate::core::DeploymentInfo;
     use cubist_config::{paths::ContractFQN, Target};
-    use cubist_sdk::core::DeploymentInfo;
-    use ethers_core::abi::Address;
+    use ethers::abi::Address;
+    use eyre::Result;
     use futures::{channel::mpsc, StreamExt};
     use std::collections::HashSet as Set;
     use std::path::PathBuf;
@@ -225,8 +229,11 @@ mod tests {
             deployment: DeploymentInfo {
                 target: Target::Ethereum,
                 address: random_address(),
+                chain_id: None,
             },
             shims: vec![],
+            proxy: None,
+            safe_proposals: vec![],
         };
         dm.write_atomic(&man_file)?;
         assert_eq!(
@@ -245,11 +252,15 @@ mod tests {
             deployment: DeploymentInfo {
                 target: Target::Ethereum,
                 address: random_address(),
+                chain_id: Some(31337),
             },
             shims: vec![DeploymentInfo {
                 target: Target::Polygon,
                 address: random_address(),
+                chain_id: Some(80001),
             }],
+            proxy: None,
+            safe_proposals: vec![],
         };
         dm.write_atomic(&man_file)?;
         assert_eq!(
@@ -283,11 +294,15 @@ mod tests {
             deployment: DeploymentInfo {
                 target: Target::Ethereum,
                 address: random_address(),
+                chain_id: None,
             },
             shims: vec![DeploymentInfo {
                 target: Target::Polygon,
                 address: random_address(),
+                chain_id: None,
             }],
+            proxy: None,
+            safe_proposals: vec![],
         };
         // write a good deployment
         fs::write(&man_file, serde_json::to_string(&dm)?).await?;
@@ -317,17 +332,22 @@ mod tests {
             deployment: DeploymentInfo {
                 target: Target::Ethereum,
                 address: random_address(),
+                chain_id: None,
             },
             shims: vec![
                 DeploymentInfo {
                     target: Target::Polygon,
                     address: random_address(),
+                    chain_id: None,
                 },
                 DeploymentInfo {
                     target: Target::Avalanche,
                     address: random_address(),
+                    chain_id: None,
                 },
             ],
+            proxy: None,
+            safe_proposals: vec![],
         };
 
         // write some manifests and some bogus files and assert that only manifests are reported
@@ -357,8 +377,11 @@ mod tests {
             deployment: DeploymentInfo {
                 target: Target::Ethereum,
                 address: random_address(),
+                chain_id: None,
             },
             shims: vec![],
+            proxy: None,
+            safe_proposals: vec![],
         };
         let dm3_path = watched_path.join("empty.json");
         dm.write_atomic(&dm3_path)?;
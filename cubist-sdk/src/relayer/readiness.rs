@@ -0,0 +1,206 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cubist_config::paths::ContractFQN;
+use cubist_config::util::OrBug;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::{debug, trace, warn};
+
+use crate::hex::serde_hex;
+use crate::{CubistSdkError, Result};
+
+/// Notice (published by the relayer) that a deployed contract has been bridged, i.e., that
+/// events from all of its shims are being relayed to it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgedNotice {
+    /// The bridged contract
+    pub contract: ContractFQN,
+    /// Address of the bridged contract
+    #[serde(with = "serde_hex")]
+    pub address: Vec<u8>,
+}
+
+/// Notices published so far, and the channel through which new ones are sent to clients.
+struct Published {
+    notices: Vec<BridgedNotice>,
+    tx: broadcast::Sender<BridgedNotice>,
+}
+
+/// Publishes [`BridgedNotice`]s to all clients connected to a TCP endpoint, as one JSON object
+/// per line.  Clients first receive all notices published before they connected.
+pub(crate) struct ReadinessServer {
+    published: Arc<Mutex<Published>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl ReadinessServer {
+    /// Starts listening on `endpoint` (`host:port`).
+    pub(crate) async fn bind(endpoint: &str) -> Result<Self> {
+        let listener = TcpListener::bind(endpoint)
+            .await
+            .map_err(|e| CubistSdkError::RelayerEndpointError(endpoint.to_owned(), e))?;
+        let (tx, _) = broadcast::channel(100);
+        let published = Arc::new(Mutex::new(Published {
+            notices: vec![],
+            tx,
+        }));
+        let accept_task = tokio::spawn(Self::accept(listener, Arc::clone(&published)));
+        debug!("Publishing bridged contracts on {endpoint}");
+        Ok(Self {
+            published,
+            accept_task,
+        })
+    }
+
+    /// Publishes `notice` to all current (and future) clients.
+    pub(crate) fn publish(&self, notice: BridgedNotice) {
+        let mut published = self.published.lock().or_bug("Poisoned lock");
+        published.notices.push(notice.clone());
+        // not having any clients is fine
+        let _ = published.tx.send(notice);
+    }
+
+    /// Accepts clients (forever), serving each from its own task.
+    async fn accept(listener: TcpListener, published: Arc<Mutex<Published>>) {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!("Failed to accept relayer endpoint client: {e}");
+                    continue;
+                }
+            };
+            trace!("Relayer endpoint client {peer} connected");
+            let (notices, rx) = {
+                let published = published.lock().or_bug("Poisoned lock");
+                (published.notices.clone(), published.tx.subscribe())
+            };
+            tokio::spawn(async move {
+                if let Err(e) = Self::serve(stream, notices, rx).await {
+                    trace!("Relayer endpoint client {peer} disconnected: {e}");
+                }
+            });
+        }
+    }
+
+    /// Sends `notices`, then all notices received from `rx`, to a client.
+    async fn serve(
+        mut stream: TcpStream,
+        notices: Vec<BridgedNotice>,
+        mut rx: broadcast::Receiver<BridgedNotice>,
+    ) -> io::Result<()> {
+        for notice in notices {
+            write_notice(&mut stream, &notice).await?;
+        }
+        loop {
+            match rx.recv().await {
+                Ok(notice) => write_notice(&mut stream, &notice).await?,
+                Err(RecvError::Lagged(n)) => warn!("Relayer endpoint client missed {n} notice(s)"),
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+}
+
+impl Drop for ReadinessServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Writes `notice` to `stream` (as a line of JSON).
+async fn write_notice(stream: &mut TcpStream, notice: &BridgedNotice) -> io::Result<()> {
+    let mut line = serde_json::to_vec(notice)?;
+    line.push(b'\n');
+    stream.write_all(&line).await
+}
+
+/// Waits for up to `timeout` until the relayer publishing on `endpoint` (see
+/// [`ReadinessServer`]) reports that `contract` (deployed at `address`) is bridged.  Returns
+/// whether it did, or an error if the endpoint can't be reached.
+pub(crate) async fn wait_bridged(
+    endpoint: &str,
+    contract: &ContractFQN,
+    address: &[u8],
+    timeout: Duration,
+) -> io::Result<bool> {
+    let stream = tokio::time::timeout(timeout, TcpStream::connect(endpoint))
+        .await
+        .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+    let mut lines = BufReader::new(stream).lines();
+    let bridged = async {
+        while let Some(line) = lines.next_line().await? {
+            match serde_json::from_str::<BridgedNotice>(&line) {
+                Ok(n) if n.contract.is_same_as(contract) && n.address == address => {
+                    return Ok(true)
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Invalid notice from relayer endpoint {endpoint}: {e}"),
+            }
+        }
+        // the relayer went away
+        Ok::<_, io::Error>(false)
+    };
+    tokio::time::timeout(timeout, bridged)
+        .await
+        .unwrap_or(Ok(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn notice(name: &str, address: u8) -> BridgedNotice {
+        BridgedNotice {
+            contract: ContractFQN::new(PathBuf::from("a.sol"), name.to_owned()),
+            address: vec![address; 20],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_wait() {
+        let port = cubist_util::net::free_port().unwrap();
+        let endpoint = format!("127.0.0.1:{port}");
+        let server = ReadinessServer::bind(&endpoint).await.unwrap();
+        let timeout = Duration::from_secs(5);
+
+        // published before the client connects
+        server.publish(notice("A", 1));
+        let a = notice("A", 1);
+        assert!(wait_bridged(&endpoint, &a.contract, &a.address, timeout)
+            .await
+            .unwrap());
+
+        // published after the client connects
+        let b = notice("B", 2);
+        let wait = tokio::spawn({
+            let endpoint = endpoint.clone();
+            let b = b.clone();
+            async move { wait_bridged(&endpoint, &b.contract, &b.address, timeout).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.publish(notice("A", 2));
+        server.publish(b);
+        assert!(wait.await.unwrap().unwrap());
+
+        // never published
+        let c = notice("C", 3);
+        let short = Duration::from_millis(100);
+        assert!(!wait_bridged(&endpoint, &c.contract, &c.address, short)
+            .await
+            .unwrap());
+
+        // no relayer
+        let port = cubist_util::net::free_port().unwrap();
+        let endpoint = format!("127.0.0.1:{port}");
+        assert!(wait_bridged(&endpoint, &a.contract, &a.address, timeout)
+            .await
+            .is_err());
+    }
+}
@@ -1,43 +1,60 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use crate::core::{CompileResult, ContractCompiler, ContractData, ContractInfo};
+use crate::core::{CompileResult, ContractCompiler, ContractData, ContractInfo, SolidityArtifacts};
 use crate::{CubistSdkError, Result, WrapperError};
 use cubist_config::paths::{ContractFQN, TargetPaths};
 use cubist_config::util::OrBug;
 use cubist_config::CompilerConfig;
 use ethers::core::abi::Abi;
 use ethers::core::types::Bytes;
-use ethers_solc::artifacts::Severity;
+use ethers_solc::artifacts::output_selection::ContractOutputSelection;
+use ethers_solc::artifacts::{BytecodeHash, SettingsMetadata, Severity};
 use ethers_solc::remappings::Remapping;
-use ethers_solc::{Project, ProjectPathsConfig};
+use ethers_solc::{AggregatedCompilerOutput, EvmVersion, Project, ProjectPathsConfig, SolcConfig};
+
+/// Name of the file (in the build root) mapping each compiled source file (by its path relative
+/// to the contracts root) to the id of its most recent build info (see
+/// [`SolcCompiler::find_build_info`]).
+const BUILD_INFO_IDS_FILE: &str = "build_info_ids.json";
 
 pub struct SolcCompiler {
     project: Project,
+    /// Root directory of the contract source files
+    contracts: PathBuf,
 }
 
 impl SolcCompiler {
     /// Creates a new instance of the solc compiler
-    pub fn new(compiler_config: &CompilerConfig, paths: &TargetPaths) -> Self {
-        Self {
-            project: configure_solc_project(compiler_config, paths),
-        }
+    pub fn new(compiler_config: &CompilerConfig, paths: &TargetPaths) -> Result<Self> {
+        Ok(Self {
+            project: configure_solc_project(compiler_config, paths)?,
+            contracts: paths.contracts.clone(),
+        })
     }
 
     /// Creates a new instance of the solc compiler that can serve as a validator
-    pub fn new_validator(compiler_config: &CompilerConfig) -> Self {
+    pub fn new_validator(compiler_config: &CompilerConfig) -> Result<Self> {
+        let mut project_paths =
+            ProjectPathsConfig::current_hardhat().or_bug("Configuring 'solc' paths failed");
+        project_paths
+            .remappings
+            .extend(configured_remappings(compiler_config));
+        let contracts = project_paths.root.clone();
         let project = Project::builder()
+            .paths(project_paths)
+            .solc_config(configure_solc_settings(compiler_config)?)
             .no_artifacts()
             .include_paths(compiler_config.import_dirs.clone())
             .build()
             .unwrap();
-        SolcCompiler { project }
+        Ok(SolcCompiler { project, contracts })
     }
 }
 
-fn parse_contract(json_path: &Path) -> Result<(Abi, Bytes)> {
+fn parse_contract(json_path: &Path) -> Result<(Abi, Bytes, SolidityArtifacts)> {
     let invalid_contract = |reason: &str, source: Option<Box<WrapperError>>| {
         CubistSdkError::ParseContractError(json_path.to_path_buf(), reason.into(), source)
     };
@@ -79,7 +96,94 @@ fn parse_contract(json_path: &Path) -> Result<(Abi, Bytes)> {
             )
         })?;
 
-    Ok((abi, bytes))
+    // Everything else is optional (e.g., the storage layout is missing for artifacts produced
+    // before we started requesting it)
+    let str_at = |pointer: &str| val.pointer(pointer).and_then(|v| v.as_str());
+    let deployed_bytes = str_at("/deployedBytecode/object")
+        .map(Bytes::from_str)
+        .transpose()
+        .map_err(WrapperError::ParseBytesError)
+        .map_err(|e| {
+            invalid_contract(
+                "Property 'deployedBytecode.object' is not a hex string",
+                Some(Box::new(e)),
+            )
+        })?;
+    let storage_layout = val
+        .get("storageLayout")
+        .map(|v| serde_json::from_value(v.to_owned()))
+        .transpose()
+        .map_err(|e| {
+            WrapperError::JsonError(json_path.to_path_buf(), "StorageLayout".to_owned(), e)
+        })
+        .map_err(|e| invalid_contract("Invalid 'storageLayout' value", Some(Box::new(e))))?;
+    let method_identifiers = val
+        .get("methodIdentifiers")
+        .map(|v| serde_json::from_value(v.to_owned()))
+        .transpose()
+        .map_err(|e| {
+            WrapperError::JsonError(json_path.to_path_buf(), "MethodIdentifiers".to_owned(), e)
+        })
+        .map_err(|e| invalid_contract("Invalid 'methodIdentifiers' value", Some(Box::new(e))))?
+        .unwrap_or_default();
+    let artifacts = SolidityArtifacts {
+        deployed_bytes,
+        source_map: str_at("/bytecode/sourceMap").map(String::from),
+        deployed_source_map: str_at("/deployedBytecode/sourceMap").map(String::from),
+        storage_layout,
+        method_identifiers,
+        build_info: None,
+    };
+
+    Ok((abi, bytes, artifacts))
+}
+
+impl SolcCompiler {
+    fn build_info_ids_file(&self) -> PathBuf {
+        self.project.root().join(BUILD_INFO_IDS_FILE)
+    }
+
+    /// Reads the build info ids recorded by [`Self::record_build_info`] (keyed by
+    /// [`Self::build_info_key`], so that files with the same name in different directories don't
+    /// clash).
+    fn build_info_ids(&self) -> HashMap<String, String> {
+        fs::read_to_string(self.build_info_ids_file())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Key of `file` (either absolute or relative to the contracts root) in the build info ids:
+    /// its path relative to the contracts root.
+    fn build_info_key(&self, file: &Path) -> String {
+        file.strip_prefix(&self.contracts)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Records the id of the build info produced by compiling `file` (if any: a cached
+    /// compilation does not produce one, in which case the previously recorded id still holds).
+    fn record_build_info(&self, file: &Path, output: &AggregatedCompilerOutput) -> Result<()> {
+        let Some(build_info) = output.build_infos.values().next_back() else {
+            return Ok(());
+        };
+        let mut ids = self.build_info_ids();
+        ids.insert(self.build_info_key(file), build_info.id.clone());
+        let ids_file = self.build_info_ids_file();
+        let json = serde_json::to_string_pretty(&ids).or_bug("Serializing build info ids failed");
+        fs::write(&ids_file, json).map_err(|e| WrapperError::IOError(ids_file, e))?;
+        Ok(())
+    }
+
+    /// Find the build info file containing the output for the contracts defined in `file`
+    /// (relative to the contracts root), using the build info id recorded when that file was
+    /// last compiled.
+    fn find_build_info(&self, file: &Path) -> Option<PathBuf> {
+        let id = self.build_info_ids().remove(&self.build_info_key(file))?;
+        let path = self.project.build_info_path().join(format!("{id}.json"));
+        path.is_file().then_some(path)
+    }
 }
 
 impl ContractCompiler for SolcCompiler {
@@ -111,10 +215,14 @@ impl ContractCompiler for SolcCompiler {
             })?;
 
         let has_errors = out.has_compiler_errors();
-        let diagnostics = out.output().diagnostics(&[], Severity::Info).to_string();
+        let output = out.output();
+        let diagnostics = output.diagnostics(&[], Severity::Info).to_string();
         match has_errors {
             true => Err(CubistSdkError::CompileError(file.into(), diagnostics, None)),
-            false => Ok(CompileResult { diagnostics }),
+            false => {
+                self.record_build_info(file, &output)?;
+                Ok(CompileResult { diagnostics })
+            }
         }
     }
 
@@ -125,23 +233,29 @@ impl ContractCompiler for SolcCompiler {
             .map_err(|e| WrapperError::IOError(artifacts_dir.clone(), e))
             .map_err(|e| CubistSdkError::NoArtifactsDir(artifacts_dir, Box::new(e)))?;
 
+        let build_info = self.find_build_info(source_file);
         let mut result = HashMap::new();
         for json_file in read_dir_result
             .flat_map(|ent| ent.ok())
             .filter(|ent| ent.file_name().to_string_lossy().ends_with(".json"))
         {
-            let (abi, bytes) = parse_contract(&json_file.path())?;
+            let (abi, bytes, mut artifacts) = parse_contract(&json_file.path())?;
             let name = json_file
                 .path()
                 .file_stem()
                 .unwrap()
                 .to_string_lossy()
                 .to_string();
+            artifacts.build_info = build_info.clone();
             result.insert(
                 name.clone(),
                 ContractInfo {
                     fqn: ContractFQN::new(source_file.to_path_buf(), name),
-                    data: ContractData::SolidityData { abi, bytes },
+                    data: ContractData::SolidityData {
+                        abi,
+                        bytes,
+                        artifacts: Box::new(artifacts),
+                    },
                 },
             );
         }
@@ -150,7 +264,10 @@ impl ContractCompiler for SolcCompiler {
     }
 }
 
-fn configure_solc_project(compiler_config: &CompilerConfig, paths: &TargetPaths) -> Project {
+fn configure_solc_project(
+    compiler_config: &CompilerConfig,
+    paths: &TargetPaths,
+) -> Result<Project> {
     // Configure compiler project. We only use the ethers_solc to compile a subset of contracts so
     // we scope the config to the solc directory in the build dir.
     let project_paths = ProjectPathsConfig {
@@ -165,7 +282,7 @@ fn configure_solc_project(compiler_config: &CompilerConfig, paths: &TargetPaths)
         tests: paths.build_root.join("unexpected-use-tests"),
         scripts: paths.build_root.join("unexpected-use-scripts"),
         libraries: vec![],
-        remappings: vec![Remapping {
+        remappings: std::iter::once(Remapping {
             name: ":stellar://".to_string(),
             path: paths
                 .build_root
@@ -177,15 +294,80 @@ fn configure_solc_project(compiler_config: &CompilerConfig, paths: &TargetPaths)
                 .join("release")
                 .display()
                 .to_string(),
-        }],
+        })
+        .chain(configured_remappings(compiler_config))
+        .collect(),
     };
-    Project::builder()
+    Ok(Project::builder()
         .paths(project_paths)
+        .solc_config(configure_solc_settings(compiler_config)?)
         .set_cached(true)
         .set_build_info(true)
         .include_paths(compiler_config.import_dirs.clone())
         .build()
-        .or_bug("Configuring 'solc' failed")
+        .or_bug("Configuring 'solc' failed"))
+}
+
+/// Remappings configured by the user (already validated to be of the form `prefix=target`).
+fn configured_remappings(compiler_config: &CompilerConfig) -> impl Iterator<Item = Remapping> + '_ {
+    compiler_config
+        .solc
+        .remappings
+        .iter()
+        .filter_map(|r| r.split_once('='))
+        .map(|(name, path)| Remapping {
+            name: name.to_string(),
+            path: path.to_string(),
+        })
+}
+
+/// Translates a configured EVM version into the one passed to solc, if the pinned ethers-solc
+/// (1.0.2) knows it: it doesn't know any version after London.
+fn solc_evm_version(version: cubist_config::EvmVersion) -> Option<EvmVersion> {
+    use cubist_config::EvmVersion as Configured;
+    Some(match version {
+        Configured::Homestead => EvmVersion::Homestead,
+        Configured::TangerineWhistle => EvmVersion::TangerineWhistle,
+        Configured::SpuriousDragon => EvmVersion::SpuriousDragon,
+        Configured::Byzantium => EvmVersion::Byzantium,
+        Configured::Constantinople => EvmVersion::Constantinople,
+        Configured::Petersburg => EvmVersion::Petersburg,
+        Configured::Istanbul => EvmVersion::Istanbul,
+        Configured::Berlin => EvmVersion::Berlin,
+        Configured::London => EvmVersion::London,
+        Configured::Paris | Configured::Shanghai | Configured::Cancun => return None,
+    })
+}
+
+/// Translates the configured solc settings (optimizer, EVM version, etc.) into a [`SolcConfig`].
+fn configure_solc_settings(compiler_config: &CompilerConfig) -> Result<SolcConfig> {
+    let unsupported = |what: &str, value: &dyn ToString| {
+        CubistSdkError::UnsupportedCompilerSetting(format!("{what} '{}'", value.to_string()))
+    };
+    let solc = &compiler_config.solc;
+    let mut config = SolcConfig::builder()
+        .additional_output(ContractOutputSelection::StorageLayout)
+        .build();
+    let settings = &mut config.settings;
+    if let Some(optimizer) = &solc.optimizer {
+        settings.optimizer.enabled = Some(optimizer.enabled);
+        settings.optimizer.runs = Some(optimizer.runs);
+    }
+    settings.via_ir = solc.via_ir;
+    if let Some(evm_version) = solc.evm_version {
+        let version = solc_evm_version(evm_version).ok_or_else(|| {
+            CubistSdkError::UnsupportedCompilerSetting(format!(
+                "EVM version '{evm_version}' (only versions up to 'london' are supported)"
+            ))
+        })?;
+        settings.evm_version = Some(version);
+    }
+    if let Some(metadata_hash) = solc.metadata_hash {
+        let hash = BytecodeHash::from_str(&metadata_hash.to_string())
+            .map_err(|_| unsupported("metadata hash", &metadata_hash))?;
+        settings.metadata = Some(SettingsMetadata::from(hash));
+    }
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -214,7 +396,8 @@ mod tests {
         let comp = SolcCompiler::new(
             &Default::default(),
             &TargetPaths::new(tmp.path().to_path_buf(), tmp.path().to_path_buf()),
-        );
+        )
+        .unwrap();
         let result = comp.compile_file(&contract_path).unwrap();
         assert_ne!("", result.diagnostics); // there should be some warnings in the diagnostics
     }
@@ -227,7 +410,8 @@ mod tests {
         let comp = SolcCompiler::new(
             &Default::default(),
             &TargetPaths::new(tmp.path().to_path_buf(), tmp.path().to_path_buf()),
-        );
+        )
+        .unwrap();
         match comp.compile_file(&contract_path) {
             Ok(result) => panic!(
                 "Should have failed; instead succeeded with diagnostics: {}",
@@ -240,4 +424,102 @@ mod tests {
             Err(e) => panic!("Unexpected error: {}", e),
         }
     }
+
+    #[test]
+    fn compiled_contract_artifacts() {
+        let tmp = tempdir().unwrap();
+        let paths = TargetPaths::new(tmp.path().to_path_buf(), tmp.path().to_path_buf());
+        let contract_path = paths.contracts.join("sub").join("Test.sol");
+        fs::create_dir_all(contract_path.parent().unwrap()).unwrap();
+        fs::write(
+            &contract_path,
+            r#"
+            // SPDX-License-Identifier: UNLICENSED
+            pragma solidity >=0.8.16;
+            contract Store {
+                uint256 number;
+                function store(uint256 num) public {
+                    number = num;
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let comp = SolcCompiler::new(&Default::default(), &paths).unwrap();
+        comp.compile_file(&contract_path).unwrap();
+
+        let find_store = || {
+            let mut contracts = comp
+                .find_compiled_contracts(&Path::new("sub").join("Test.sol"))
+                .unwrap();
+            match contracts.remove("Store").unwrap().data {
+                ContractData::SolidityData { artifacts, .. } => artifacts,
+                _ => panic!("Expected Solidity data"),
+            }
+        };
+        let artifacts = find_store();
+        assert!(artifacts.deployed_bytes.is_some());
+        assert!(artifacts.source_map.is_some());
+        assert!(artifacts.deployed_source_map.is_some());
+        let storage = artifacts.storage_layout.as_ref().unwrap();
+        assert_eq!(
+            vec!["number"],
+            storage.storage.iter().map(|s| &s.label).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&"6057361d".to_string()),
+            artifacts.method_identifiers.get("store(uint256)")
+        );
+
+        // the build info is the one produced for this file
+        let build_info = artifacts.build_info.clone().unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&build_info).unwrap()).unwrap();
+        let id = json["id"].as_str().unwrap();
+        assert_eq!(build_info.file_stem().unwrap().to_str().unwrap(), id);
+        // (recorded by the path relative to the contracts root)
+        let key = Path::new("sub")
+            .join("Test.sol")
+            .to_string_lossy()
+            .to_string();
+        assert_eq!(
+            Some(id),
+            comp.build_info_ids().get(&key).map(String::as_str)
+        );
+        assert!(json["output"]["contracts"]
+            .as_object()
+            .unwrap()
+            .values()
+            .any(|contracts| contracts.get("Store").is_some()));
+
+        // recompiling (from the cache) keeps pointing at the same build info
+        comp.compile_file(&contract_path).unwrap();
+        assert_eq!(Some(build_info), find_store().build_info);
+    }
+
+    #[test]
+    fn evm_versions() {
+        let with_evm_version = |version| {
+            let mut config = CompilerConfig::default();
+            config.solc.evm_version = Some(version);
+            configure_solc_settings(&config).map(|c| c.settings.evm_version)
+        };
+        assert_eq!(
+            Some(EvmVersion::London),
+            with_evm_version(cubist_config::EvmVersion::London).unwrap()
+        );
+        // versions the pinned ethers-solc doesn't know are rejected (rather than dropped)
+        for version in [
+            cubist_config::EvmVersion::Paris,
+            cubist_config::EvmVersion::Shanghai,
+            cubist_config::EvmVersion::Cancun,
+        ] {
+            match with_evm_version(version) {
+                Err(CubistSdkError::UnsupportedCompilerSetting(setting)) => {
+                    assert!(setting.contains(&version.to_string()))
+                }
+                other => panic!("Expected unsupported EVM version, got {other:?}"),
+            }
+        }
+    }
 }
@@ -0,0 +1,199 @@
+use cubist_config::network::{CommonConfig, NetworkProfile};
+use cubist_config::{BridgeProvider, Config};
+use cubist_localchains::provider::Server;
+use cubist_localchains::UrlExt;
+use cubist_util::net::free_port;
+use lazy_static::lazy_static;
+use tempfile::TempDir;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::core::{Cubist, HttpStack};
+use crate::relayer::{self, RelayerConfig};
+use crate::{CubistSdkError, Result};
+
+lazy_static! {
+    /// Serializes starting test networks, so that networks started in parallel (e.g., from
+    /// different test threads) don't race for the same ports.
+    static ref START_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Builder for a [`TestNetwork`] (see [`TestNetwork::builder`]).
+pub struct TestNetworkBuilder {
+    /// Project configuration (the nearest one, if not set)
+    config: Option<Config>,
+    /// Whether to start a relayer
+    relayer: bool,
+    /// Configuration of the relayer
+    relayer_config: RelayerConfig,
+}
+
+impl Default for TestNetworkBuilder {
+    fn default() -> Self {
+        Self {
+            config: None,
+            relayer: true,
+            relayer_config: Default::default(),
+        }
+    }
+}
+
+impl TestNetworkBuilder {
+    /// Use `config` instead of the configuration of the nearest Cubist project (see
+    /// [`Config::nearest`]).
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Whether to start a relayer (enabled by default).  The relayer is only started when the
+    /// bridge provider is [`BridgeProvider::Cubist`].
+    pub fn relayer(mut self, enabled: bool) -> Self {
+        self.relayer = enabled;
+        self
+    }
+
+    /// Use `config` to configure the relayer.
+    pub fn relayer_config(mut self, config: RelayerConfig) -> Self {
+        self.relayer_config = config;
+        self
+    }
+
+    /// Starts the test network: moves all chains that Cubist starts locally to free ports,
+    /// starts them, starts the relayer (if enabled), and connects to them.
+    ///
+    /// The test network uses its own (temporary) deploy directory, so that it doesn't see (or
+    /// leave behind) deployments of other networks, and its own relayer endpoint (see
+    /// [`Config::relayer_endpoint`]).  The build directory is shared, i.e., the
+    /// project must be built (with `cubist build`) beforehand.
+    pub async fn start(self) -> Result<TestNetwork> {
+        let mut config = match self.config {
+            Some(config) => config,
+            None => Config::nearest()?,
+        };
+        let deploy_dir = tempfile::Builder::new()
+            .prefix("cubist-test-")
+            .tempdir()
+            .map_err(|e| {
+                CubistSdkError::TestNetworkError(format!("cannot create deploy directory: {e}"))
+            })?;
+        config.set_deploy_dir(deploy_dir.path());
+
+        let servers = {
+            let _guard = START_LOCK.lock().await;
+            let profile = config
+                .network_profiles
+                .get_mut(&config.current_network_profile)
+                .ok_or_else(|| {
+                    CubistSdkError::TestNetworkError(format!(
+                        "network profile '{}' not found",
+                        config.current_network_profile
+                    ))
+                })?;
+            allocate_ports(profile)?;
+            // let `when_bridged` subscribe to the relayer instead of polling
+            config.relayer_endpoint = Some(format!("127.0.0.1:{}", port()?));
+            cubist_localchains::start(config.network_profile()).await?
+        };
+
+        let relayer = match config.bridge_provider {
+            BridgeProvider::Cubist if self.relayer => {
+                let relayer = relayer::start(config.clone(), self.relayer_config).await?;
+                Some(tokio::spawn(relayer.run_to_completion()))
+            }
+            _ => None,
+        };
+
+        let cubist = Cubist::<HttpStack>::new(config.clone()).await?;
+        debug!("Started test network in {}", deploy_dir.path().display());
+        Ok(TestNetwork {
+            cubist,
+            config,
+            relayer,
+            servers,
+            deploy_dir,
+        })
+    }
+}
+
+/// Local chains (and a relayer) started in-process for a test, along with a [`Cubist`]
+/// instance connected to them.  Each test network uses its own ports and deploy directory, so
+/// that several of them can run in parallel.  Everything is stopped (and the deploy directory
+/// removed) when the test network is dropped.
+pub struct TestNetwork {
+    /// Cubist instance connected to the chains of this network
+    cubist: Cubist<HttpStack>,
+    /// Project configuration, pointing at the chains of this network
+    config: Config,
+    /// Background task running the relayer, if started
+    relayer: Option<JoinHandle<Result<()>>>,
+    /// Local chains
+    servers: Vec<Box<dyn Server>>,
+    /// Deploy directory (removed when dropped)
+    deploy_dir: TempDir,
+}
+
+impl TestNetwork {
+    /// Returns a builder for a test network.
+    pub fn builder() -> TestNetworkBuilder {
+        TestNetworkBuilder::default()
+    }
+
+    /// Cubist instance connected to the chains of this network.
+    pub fn cubist(&self) -> &Cubist<HttpStack> {
+        &self.cubist
+    }
+
+    /// Project configuration pointing at the chains (and the deploy directory) of this network.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+}
+
+impl Drop for TestNetwork {
+    fn drop(&mut self) {
+        if let Some(relayer) = self.relayer.take() {
+            if relayer.is_finished() {
+                warn!("Relayer of test network stopped early");
+            }
+            relayer.abort();
+        }
+        // dropping the servers stops the chains
+        self.servers.clear();
+        debug!(
+            "Stopped test network in {}",
+            self.deploy_dir.path().display()
+        );
+    }
+}
+
+/// Moves all chains in `profile` that Cubist starts locally (as well as all proxies) to free
+/// ports.
+fn allocate_ports(profile: &mut NetworkProfile) -> Result<()> {
+    let commons: [Option<&mut CommonConfig>; 5] = [
+        profile.ethereum.as_mut().map(|c| &mut c.common),
+        profile.polygon.as_mut().map(|c| &mut c.common),
+        profile.avalanche.as_mut().map(|c| &mut c.common),
+        profile.ava_subnet.as_mut().map(|c| &mut c.common),
+        profile.stellar.as_mut().map(|c| &mut c.common),
+    ];
+    for common in commons.into_iter().flatten() {
+        if common.autostart && common.url.is_loopback()? {
+            let mut url = common.url.expose_url()?;
+            url.set_port(Some(port()?)).map_err(|_| {
+                CubistSdkError::TestNetworkError(format!("cannot set port of {url}"))
+            })?;
+            common.url = url.into();
+        }
+        if let Some(proxy) = common.proxy.as_mut() {
+            proxy.port = port()?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns a free port.
+fn port() -> Result<u16> {
+    free_port().ok_or_else(|| CubistSdkError::TestNetworkError("no free port".to_owned()))
+}
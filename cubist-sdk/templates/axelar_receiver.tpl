@@ -9,8 +9,10 @@ contract {{contract.contract}}Interface is AxelarExecutable {
 
     {{macros::access_control()}}
 
-    constructor(address gateway) AxelarExecutable(gateway) {
-        _owner = msg.sender;
+    {{macros::pausable()}}
+
+    constructor(address initialOwner, address gateway) AxelarExecutable(gateway) {
+        _owner = initialOwner;
     }
 
     function {{AXELAR_SET_TARGET_ADDR_METHOD_NAME}}(address contractAddress) public onlyOwner {
@@ -21,7 +23,7 @@ contract {{contract.contract}}Interface is AxelarExecutable {
         string calldata,
         string calldata,
         bytes calldata payload
-    ) internal override {
+    ) internal override whenNotPaused {
         (bool success,) = address(_contractObject).call(payload);
         require(success, "Calling target contract failed");
     }
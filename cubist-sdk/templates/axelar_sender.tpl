@@ -13,18 +13,21 @@ contract {{contract.contract}} is AxelarExecutable {
 
     {{macros::access_control()}}
 
+    {{macros::pausable()}}
+
     constructor(
+        address initialOwner,
         address gateway,
         address gasReceiver,
         string memory interfaceAddress
     ) AxelarExecutable(gateway) {
         _gasReceiver = IAxelarGasService(gasReceiver);
         _interfaceAddress = interfaceAddress;
-        _owner = msg.sender;
+        _owner = initialOwner;
     }
 
     {% for function in contract.functions %}
-    function {{function.name}}({{macros::params(args=function.params)}}) external payable onlyCaller {
+    function {{function.name}}({{macros::params(args=function.params)}}) external payable onlyCaller whenNotPaused {
       bytes memory payload = abi.encodeWithSignature("{{function.name}}({{macros::arg_types(args=function.params)}})"{{macros::comma(list=function.params)}}{{macros::arg_names(args=function.params)}});
       _gasReceiver.payNativeGasForContractCall{value: msg.value}(
             address(this),
@@ -15,14 +15,16 @@ contract {{contract.contract}} {
 
     {{macros::access_control()}}
 
-    constructor() {
-        _owner = msg.sender;
+    {{macros::pausable()}}
+
+    constructor(address initialOwner) {
+        _owner = initialOwner;
     }
 
     {% for function in contract.functions -%}
     event __cubist_event_{{contract.contract}}_{{function.name}}({{macros::params(args=function.params)}});
 
-    function {{function.name}}({{macros::params(args=function.params)}}) public onlyCaller {{macros::payable(is_payable=function.is_payable)}}{
+    function {{function.name}}({{macros::params(args=function.params)}}) public onlyCaller whenNotPaused {{macros::payable(is_payable=function.is_payable)}}{
         emit __cubist_event_{{contract.contract}}_{{function.name}}({{macros::arg_names(args=function.params)}});
     }
     {% endfor %}
@@ -26,7 +26,14 @@ import {IAxelarGasService} from "{{AXELAR_PACKAGE}}/contracts/interfaces/IAxelar
 
 {%- macro access_control() -%}
     address private _owner;
+    address private _pendingOwner;
     mapping(address => bool) private _callers;
+    address[] private _callerList;
+
+    event ShimOwnershipTransferStarted(address indexed previousOwner, address indexed newOwner);
+    event ShimOwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event CallerApproved(address indexed account);
+    event CallerRevoked(address indexed account);
 
     function _isOwner(address account) private view returns (bool) {
         return _owner == account;
@@ -47,6 +54,101 @@ import {IAxelarGasService} from "{{AXELAR_PACKAGE}}/contracts/interfaces/IAxelar
     }
 
     function {{APPROVE_CALLER_METHOD_NAME}}(address account) public onlyOwner {
-        _callers[account] = true;
+        if (!_callers[account]) {
+            _callers[account] = true;
+            _callerList.push(account);
+            emit CallerApproved(account);
+        }
+    }
+
+    function {{REVOKE_CALLER_METHOD_NAME}}(address account) public onlyOwner {
+        require(_callers[account], "Cubist: account is not a caller");
+        _callers[account] = false;
+        for (uint256 i = 0; i < _callerList.length; i++) {
+            if (_callerList[i] == account) {
+                _callerList[i] = _callerList[_callerList.length - 1];
+                _callerList.pop();
+                break;
+            }
+        }
+        emit CallerRevoked(account);
+    }
+
+    function {{APPROVED_CALLERS_METHOD_NAME}}() public view returns (address[] memory) {
+        return _callerList;
+    }
+
+    function {{SHIM_OWNER_METHOD_NAME}}() public view returns (address) {
+        return _owner;
+    }
+
+    function {{PENDING_SHIM_OWNER_METHOD_NAME}}() public view returns (address) {
+        return _pendingOwner;
+    }
+
+    function {{TRANSFER_SHIM_OWNERSHIP_METHOD_NAME}}(address newOwner) public onlyOwner {
+        _pendingOwner = newOwner;
+        emit ShimOwnershipTransferStarted(_owner, newOwner);
+    }
+
+    function {{ACCEPT_SHIM_OWNERSHIP_METHOD_NAME}}() public {
+        require(msg.sender == _pendingOwner, "Cubist: sender is not the pending owner");
+        emit ShimOwnershipTransferred(_owner, _pendingOwner);
+        _owner = _pendingOwner;
+        delete _pendingOwner;
     }
 {% endmacro access_control %}
+
+{%- macro pausable() -%}
+    bool private _paused;
+    mapping(address => bool) private _guardians;
+    address[] private _guardianList;
+
+    event ShimPaused(address account);
+    event ShimUnpaused(address account);
+    event GuardianSet(address indexed account, bool enabled);
+
+    modifier onlyOwnerOrGuardian() {
+        require(_isOwner(msg.sender) || _guardians[msg.sender], "Cubist: sender is not the owner or a guardian");
+        _;
+    }
+
+    modifier whenNotPaused() {
+        require(!_paused, "Cubist: shim is paused");
+        _;
+    }
+
+    function {{SET_SHIM_GUARDIAN_METHOD_NAME}}(address account, bool enabled) public onlyOwner {
+        if (enabled && !_guardians[account]) {
+            _guardianList.push(account);
+        } else if (!enabled && _guardians[account]) {
+            for (uint256 i = 0; i < _guardianList.length; i++) {
+                if (_guardianList[i] == account) {
+                    _guardianList[i] = _guardianList[_guardianList.length - 1];
+                    _guardianList.pop();
+                    break;
+                }
+            }
+        }
+        _guardians[account] = enabled;
+        emit GuardianSet(account, enabled);
+    }
+
+    function {{SHIM_GUARDIANS_METHOD_NAME}}() public view returns (address[] memory) {
+        return _guardianList;
+    }
+
+    function {{PAUSE_SHIM_METHOD_NAME}}() public onlyOwnerOrGuardian {
+        _paused = true;
+        emit ShimPaused(msg.sender);
+    }
+
+    function {{UNPAUSE_SHIM_METHOD_NAME}}() public onlyOwnerOrGuardian {
+        _paused = false;
+        emit ShimUnpaused(msg.sender);
+    }
+
+    function {{SHIM_PAUSED_METHOD_NAME}}() public view returns (bool) {
+        return _paused;
+    }
+{% endmacro pausable %}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT
+{% for pragma in pragmas -%}
+{{pragma}}
+{% endfor %}
+// Multicall3-compatible contract that Cubist batches calls (and transactions) through; see
+// https://github.com/mds1/multicall.  Only the subset of Multicall3 that Cubist uses is included,
+// with the same signatures, so that an existing Multicall3 deployment can be used instead.
+contract {{MULTICALL}} {
+    struct Call3 {
+        address target;
+        bool allowFailure;
+        bytes callData;
+    }
+
+    struct Call3Value {
+        address target;
+        bool allowFailure;
+        uint256 value;
+        bytes callData;
+    }
+
+    struct Result {
+        bool success;
+        bytes returnData;
+    }
+
+    // Calls each target with its call data, reverting if any call that doesn't allow failure
+    // fails.
+    function aggregate3(Call3[] calldata calls) public payable returns (Result[] memory returnData) {
+        uint256 length = calls.length;
+        returnData = new Result[](length);
+        for (uint256 i = 0; i < length; i++) {
+            Call3 calldata call = calls[i];
+            Result memory result = returnData[i];
+            (result.success, result.returnData) = call.target.call(call.callData);
+            if (!call.allowFailure && !result.success) {
+                _bubble(result.returnData);
+            }
+        }
+    }
+
+    // Like aggregate3, but also sends the given value with each call (their sum must be equal to
+    // the value sent with this call).
+    function aggregate3Value(Call3Value[] calldata calls) public payable returns (Result[] memory returnData) {
+        uint256 valAccumulator;
+        uint256 length = calls.length;
+        returnData = new Result[](length);
+        for (uint256 i = 0; i < length; i++) {
+            Call3Value calldata call = calls[i];
+            Result memory result = returnData[i];
+            valAccumulator += call.value;
+            (result.success, result.returnData) = call.target.call{value: call.value}(call.callData);
+            if (!call.allowFailure && !result.success) {
+                _bubble(result.returnData);
+            }
+        }
+        require(msg.value == valAccumulator, "{{MULTICALL}}: value mismatch");
+    }
+
+    function getBlockNumber() public view returns (uint256 blockNumber) {
+        blockNumber = block.number;
+    }
+
+    function getEthBalance(address addr) public view returns (uint256 balance) {
+        balance = addr.balance;
+    }
+
+    // Reverts with the revert data of a failed call (so that its revert reason can be decoded).
+    function _bubble(bytes memory returndata) private pure {
+        assembly {
+            revert(add(returndata, 32), mload(returndata))
+        }
+    }
+}
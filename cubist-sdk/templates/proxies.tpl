@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+// ERC-1967 proxies that Cubist deploys upgradeable contracts behind.
+
+abstract contract {{BASE_PROXY}} {
+    // bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)
+    bytes32 internal constant _IMPLEMENTATION_SLOT = 0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;
+
+    event Upgraded(address indexed implementation);
+
+    function _implementation() internal view returns (address impl) {
+        bytes32 slot = _IMPLEMENTATION_SLOT;
+        assembly {
+            impl := sload(slot)
+        }
+    }
+
+    function _upgradeToAndCall(address newImplementation, bytes memory data) internal {
+        require(newImplementation.code.length > 0, "ERC1967: new implementation is not a contract");
+        bytes32 slot = _IMPLEMENTATION_SLOT;
+        assembly {
+            sstore(slot, newImplementation)
+        }
+        emit Upgraded(newImplementation);
+        if (data.length > 0) {
+            (bool success, bytes memory returndata) = newImplementation.delegatecall(data);
+            if (!success) {
+                assembly {
+                    revert(add(returndata, 32), mload(returndata))
+                }
+            }
+        }
+    }
+
+    function _delegate(address impl) internal {
+        assembly {
+            calldatacopy(0, 0, calldatasize())
+            let result := delegatecall(gas(), impl, 0, calldatasize(), 0, 0)
+            returndatacopy(0, 0, returndatasize())
+            switch result
+            case 0 {
+                revert(0, returndatasize())
+            }
+            default {
+                return(0, returndatasize())
+            }
+        }
+    }
+
+    function _fallback() internal virtual {
+        _delegate(_implementation());
+    }
+
+    fallback() external payable {
+        _fallback();
+    }
+
+    receive() external payable {
+        _fallback();
+    }
+}
+
+// Proxy whose upgrade logic lives in the implementation (e.g., OpenZeppelin's UUPSUpgradeable).
+contract {{UUPS_PROXY}} is {{BASE_PROXY}} {
+    constructor(address implementation, bytes memory data) payable {
+        _upgradeToAndCall(implementation, data);
+    }
+}
+
+interface I{{TRANSPARENT_PROXY}} {
+    function upgradeToAndCall(address newImplementation, bytes calldata data) external payable;
+}
+
+// Proxy that can only be upgraded by its admin; calls from any other account are forwarded to the
+// implementation.
+contract {{TRANSPARENT_PROXY}} is {{BASE_PROXY}} {
+    // bytes32(uint256(keccak256("eip1967.proxy.admin")) - 1)
+    bytes32 internal constant _ADMIN_SLOT = 0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103;
+
+    event AdminChanged(address previousAdmin, address newAdmin);
+
+    constructor(address implementation, address admin, bytes memory data) payable {
+        _upgradeToAndCall(implementation, data);
+        bytes32 slot = _ADMIN_SLOT;
+        assembly {
+            sstore(slot, admin)
+        }
+        emit AdminChanged(address(0), admin);
+    }
+
+    function _admin() internal view returns (address adm) {
+        bytes32 slot = _ADMIN_SLOT;
+        assembly {
+            adm := sload(slot)
+        }
+    }
+
+    function _fallback() internal override {
+        if (msg.sender == _admin()) {
+            require(
+                msg.sig == I{{TRANSPARENT_PROXY}}.upgradeToAndCall.selector,
+                "{{TRANSPARENT_PROXY}}: admin cannot fallback to proxy target"
+            );
+            (address newImplementation, bytes memory data) = abi.decode(msg.data[4:], (address, bytes));
+            _upgradeToAndCall(newImplementation, data);
+        } else {
+            super._fallback();
+        }
+    }
+}
+
+// Admin of transparent proxies. The owner is passed explicitly (rather than taken from msg.sender)
+// so that the admin can also be deployed through a CREATE2 factory.
+contract {{PROXY_ADMIN}} {
+    address public owner;
+
+    constructor(address initialOwner) {
+        owner = initialOwner;
+    }
+
+    modifier onlyOwner() {
+        require(msg.sender == owner, "{{PROXY_ADMIN}}: caller is not the owner");
+        _;
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        owner = newOwner;
+    }
+
+    function upgradeAndCall(I{{TRANSPARENT_PROXY}} proxy, address implementation, bytes memory data) public payable onlyOwner {
+        proxy.upgradeToAndCall{value: msg.value}(implementation, data);
+    }
+}
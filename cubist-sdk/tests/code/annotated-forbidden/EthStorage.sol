@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.17;
+
+contract EthStorage {
+
+    uint256 number;
+
+    constructor (uint256 num) {
+      number = num;
+    }
+
+    /// @custom:cubist-expose never
+    function store(uint256 num) public {
+      number = num;
+    }
+
+    function retrieve() public view returns (uint256){
+        return number;
+    }
+}
@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.17;
+
+/// @custom:cubist-target ethereum
+contract EthStorage {
+
+    uint256 number;
+
+    function store(uint256 num) public {
+      number = num;
+    }
+}
+
+contract AvaStorage {
+
+    EthStorage ethStorage;
+
+    function store(uint256 num) public {
+        ethStorage.store(num);
+    }
+}
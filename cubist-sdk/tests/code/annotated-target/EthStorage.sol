@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.17;
+
+contract EthStorage {
+
+    uint256 number;
+
+    constructor (uint256 num) {
+      number = num;
+    }
+
+    function store(uint256 num) public {
+      number = num;
+    }
+
+    /// @custom:cubist-expose
+    function inc(uint256 num) public {
+      number += num;
+    }
+
+    function retrieve() public view returns (uint256){
+        return number;
+    }
+
+    /// @custom:cubist-expose never
+    function reset() public {
+        number = 0;
+    }
+}
+
+/// @custom:cubist-target polygon
+contract PolyLogger {
+
+    uint256 count;
+
+    function inc() public {
+        count += 1;
+    }
+}
@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.17;
+
+contract PolyCounter {
+
+    uint256 count;
+
+    function inc() public {
+        count += 1;
+    }
+}
@@ -10,7 +10,13 @@ import "./CharityRaffle.sol";
 contract CharityRaffleInterface is AxelarExecutable {
     CharityRaffle _contractObject;
     address private _owner;
+    address private _pendingOwner;
     mapping(address => bool) private _callers;
+    address[] private _callerList;
+    event ShimOwnershipTransferStarted(address indexed previousOwner, address indexed newOwner);
+    event ShimOwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event CallerApproved(address indexed account);
+    event CallerRevoked(address indexed account);
     function _isOwner(address account) private view returns (bool) {
         return _owner == account;
     }
@@ -26,10 +32,88 @@ contract CharityRaffleInterface is AxelarExecutable {
         _;
     }
     function approveCaller(address account) public onlyOwner {
-        _callers[account] = true;
+        if (!_callers[account]) {
+            _callers[account] = true;
+            _callerList.push(account);
+            emit CallerApproved(account);
+        }
     }
-    constructor(address gateway) AxelarExecutable(gateway) {
-        _owner = msg.sender;
+    function revokeCaller(address account) public onlyOwner {
+        require(_callers[account], "Cubist: account is not a caller");
+        _callers[account] = false;
+        for (uint256 i = 0; i < _callerList.length; i++) {
+            if (_callerList[i] == account) {
+                _callerList[i] = _callerList[_callerList.length - 1];
+                _callerList.pop();
+                break;
+            }
+        }
+        emit CallerRevoked(account);
+    }
+    function approvedCallers() public view returns (address[] memory) {
+        return _callerList;
+    }
+    function shimOwner() public view returns (address) {
+        return _owner;
+    }
+    function pendingShimOwner() public view returns (address) {
+        return _pendingOwner;
+    }
+    function transferShimOwnership(address newOwner) public onlyOwner {
+        _pendingOwner = newOwner;
+        emit ShimOwnershipTransferStarted(_owner, newOwner);
+    }
+    function acceptShimOwnership() public {
+        require(msg.sender == _pendingOwner, "Cubist: sender is not the pending owner");
+        emit ShimOwnershipTransferred(_owner, _pendingOwner);
+        _owner = _pendingOwner;
+        delete _pendingOwner;
+    }
+    bool private _paused;
+    mapping(address => bool) private _guardians;
+    address[] private _guardianList;
+    event ShimPaused(address account);
+    event ShimUnpaused(address account);
+    event GuardianSet(address indexed account, bool enabled);
+    modifier onlyOwnerOrGuardian() {
+        require(_isOwner(msg.sender) || _guardians[msg.sender], "Cubist: sender is not the owner or a guardian");
+        _;
+    }
+    modifier whenNotPaused() {
+        require(!_paused, "Cubist: shim is paused");
+        _;
+    }
+    function setShimGuardian(address account, bool enabled) public onlyOwner {
+        if (enabled && !_guardians[account]) {
+            _guardianList.push(account);
+        } else if (!enabled && _guardians[account]) {
+            for (uint256 i = 0; i < _guardianList.length; i++) {
+                if (_guardianList[i] == account) {
+                    _guardianList[i] = _guardianList[_guardianList.length - 1];
+                    _guardianList.pop();
+                    break;
+                }
+            }
+        }
+        _guardians[account] = enabled;
+        emit GuardianSet(account, enabled);
+    }
+    function shimGuardians() public view returns (address[] memory) {
+        return _guardianList;
+    }
+    function pauseShim() public onlyOwnerOrGuardian {
+        _paused = true;
+        emit ShimPaused(msg.sender);
+    }
+    function unpauseShim() public onlyOwnerOrGuardian {
+        _paused = false;
+        emit ShimUnpaused(msg.sender);
+    }
+    function shimPaused() public view returns (bool) {
+        return _paused;
+    }
+    constructor(address initialOwner, address gateway) AxelarExecutable(gateway) {
+        _owner = initialOwner;
     }
     function setTargetAddress(address contractAddress) public onlyOwner {
         _contractObject = CharityRaffle(contractAddress);
@@ -38,7 +122,7 @@ contract CharityRaffleInterface is AxelarExecutable {
         string calldata,
         string calldata,
         bytes calldata payload
-    ) internal override {
+    ) internal override whenNotPaused {
         (bool success,) = address(_contractObject).call(payload);
         require(success, "Calling target contract failed");
     }
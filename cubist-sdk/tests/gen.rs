@@ -275,6 +275,68 @@ fn bad_config() {
     );
 }
 
+#[test]
+fn annotated_forbidden() {
+    error_test_case!(
+        "annotated-forbidden/config.json",
+        InterfaceGenError::ForbiddenCrossChainCall(..)
+    );
+}
+
+#[test]
+fn annotated_mixed() {
+    error_test_case!(
+        "annotated-mixed/config.json",
+        InterfaceGenError::MixedTargetFile(_, Target::Avalanche)
+    );
+}
+
+#[test]
+fn annotated_target() {
+    let config = Config::from_file(code_path().join("annotated-target/config.json")).unwrap();
+    let source_files = parse_files(config.contracts()).expect("Error parsing files");
+    let mut contract_targets: Vec<_> = source_files
+        .iter()
+        .map(|source| (source.contract_names(), source.target.to_string()))
+        .collect();
+    contract_targets.sort();
+    assert_eq!(
+        vec![
+            (vec!["AvaStorage".to_string()], "avalanche".to_string()),
+            (vec!["EthStorage".to_string()], "ethereum".to_string()),
+            (vec!["PolyCounter".to_string()], "polygon".to_string()),
+            (vec!["PolyLogger".to_string()], "polygon".to_string()),
+        ],
+        contract_targets
+    );
+
+    // Only `EthStorage` is exposed (on Avalanche), with `inc` forced by its annotation
+    let interfaces = Interfaces::new(&source_files).unwrap();
+    assert_eq!(1, interfaces.interfaces.len());
+    let file_interfaces = &interfaces.interfaces[0];
+    assert_eq!(Target::Avalanche, file_interfaces.get_sender_target());
+    assert_eq!(Target::Ethereum, file_interfaces.get_receiver_target());
+    assert_eq!(1, file_interfaces.interfaces.len());
+    let interface = &file_interfaces.interfaces[0];
+    assert_eq!("EthStorage", interface.get_contract_name());
+    let functions: Vec<_> = interface.get_functions().iter().map(|f| f.name()).collect();
+    assert_eq!(vec!["store", "inc"], functions);
+
+    // Explicitly exposing a contract leaves out functions annotated with `never`
+    let interfaces = get_interface_for_contract(
+        &source_files,
+        &"EthStorage".to_string(),
+        &Set::from([Target::Avalanche]),
+    )
+    .unwrap();
+    let functions: Vec<_> = interfaces.interfaces[0].interfaces[0]
+        .get_functions()
+        .iter()
+        .map(|f| f.name())
+        .collect();
+    assert!(!functions.contains(&&"reset".to_string()));
+}
+
 #[rstest]
 #[case::ava_eth("ava-eth")]
 #[case::ava_eth_payable("ava-eth-payable")]
@@ -1,6 +1,8 @@
-use cubist_config::Config;
+use cubist_config::{Config, Target};
 use cubist_sdk::parse::parse_files;
+use cubist_sdk::parse::source_file::Exposure;
 use cubist_sdk::CubistSdkError;
+use std::collections::BTreeMap as Map;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tempfile::tempdir;
@@ -91,3 +93,105 @@ fn absolute_path_error() {
     assert!(fs::write(dummy_file, "").is_ok());
     import_error_test!(config_path, CubistSdkError::AbsolutePathError(..))
 }
+
+#[test]
+fn annotations() {
+    let tmp = tempdir().unwrap().into_path();
+    let contract = r#"
+    // SPDX-License-Identifier: MIT
+    pragma solidity ^0.8.17;
+
+    /**
+     * @notice Stores a number
+     * @custom:cubist-target avalanche
+     */
+    contract Storage {
+        uint256 number;
+
+        /// @custom:cubist-expose
+        function retrieve() public view returns (uint256) {
+            return number;
+        }
+
+        /// @custom:cubist-expose never
+        function reset() public {
+            number = 0;
+        }
+
+        // @custom:cubist-expose never
+        uint256 other;
+
+        function store(uint256 num) public {
+            number = num;
+        }
+    }
+    "#;
+    let config_path = setup_proj(&tmp, contract);
+    let config = Config::from_file(config_path).unwrap();
+    let source_files = parse_files(config.contracts()).expect("Error parsing files");
+    assert_eq!(1, source_files.len());
+    let source = source_files.first().unwrap();
+    assert!(!source.is_partial());
+    let annotations = &source.annotations;
+    assert_eq!(
+        Map::from([("Storage".to_string(), Target::Avalanche)]),
+        annotations.targets
+    );
+    assert_eq!(
+        Map::from([(
+            "Storage".to_string(),
+            Map::from([
+                ("retrieve".to_string(), Exposure::Always),
+                ("reset".to_string(), Exposure::Never),
+            ])
+        )]),
+        annotations.exposure
+    );
+}
+
+#[test]
+fn invalid_target_annotation() {
+    let tmp = tempdir().unwrap().into_path();
+    let contract = r#"
+    /// @custom:cubist-target mars
+    contract Storage {}
+    "#;
+    let config_path = setup_proj(&tmp, contract);
+    let config = Config::from_file(config_path).unwrap();
+    let result = parse_files(config.contracts());
+    assert!(matches!(result, Err(CubistSdkError::InvalidAnnotation(..))));
+}
+
+#[test]
+fn invalid_expose_annotation() {
+    let tmp = tempdir().unwrap().into_path();
+    let contract = r#"
+    contract Storage {
+        /// @custom:cubist-expose sometimes
+        function store() public {}
+    }
+    "#;
+    let config_path = setup_proj(&tmp, contract);
+    let config = Config::from_file(config_path).unwrap();
+    let result = parse_files(config.contracts());
+    assert!(matches!(result, Err(CubistSdkError::InvalidAnnotation(..))));
+}
+
+#[test]
+fn unconfigured_target_annotation() {
+    let tmp = tempdir().unwrap().into_path();
+    let contract = r#"
+    /// @custom:cubist-target polygon
+    contract Storage {}
+    "#;
+    let config_path = setup_proj(&tmp, contract);
+    let config = Config::from_file(config_path).unwrap();
+    let result = parse_files(config.contracts());
+    assert!(matches!(
+        result,
+        Err(CubistSdkError::UnconfiguredAnnotatedTarget(
+            _,
+            Target::Polygon
+        ))
+    ));
+}
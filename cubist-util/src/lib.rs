@@ -4,3 +4,4 @@ pub mod net;
 pub mod proc;
 pub mod tasks;
 pub mod tera;
+pub mod vendor;
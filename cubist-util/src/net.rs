@@ -29,6 +29,15 @@ pub fn next_available_port(start: u16) -> u16 {
         .unwrap_or_else(|| panic!("Could not find an open port starting from {}", start + 1))
 }
 
+/// Returns a port that the OS currently considers available (by
+/// binding to port 0), or [`None`] if binding fails.
+pub fn free_port() -> Option<u16> {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|l| l.local_addr())
+        .map(|addr| addr.port())
+        .ok()
+}
+
 /// Returns whether a given port is currently availble.
 pub fn is_available_port(port: u16) -> bool {
     TcpListener::bind(("127.0.0.1", port)).is_ok()
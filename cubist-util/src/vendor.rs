@@ -0,0 +1,812 @@
+//! Native resolver for Solidity dependencies. This module is an alternative to
+//! [`js_pkg_manager`](crate::js_pkg_manager) for projects without a JavaScript toolchain: it
+//! downloads packages (from an npm-compatible registry, a tarball URL, or a git repository) into
+//! a vendor directory and pins them (version, source, and content hash) in a lock file.
+use base64::Engine;
+use cubist_config::util::OrBug;
+use cubist_config::{ConfigError, LockFile, LockedPackage, VendorConfig};
+use flate2::read::GzDecoder;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tar::{Archive, EntryType};
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+/// Error type related to native dependency resolution
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error raised when a package name is not a valid (relative) package path
+    #[error("Invalid package name '{0}'")]
+    InvalidPackageName(String),
+    /// Error raised when a version spec cannot be parsed
+    #[error("Invalid version spec '{1}' for package '{0}'")]
+    InvalidSpec(String, String),
+    /// Error raised when no published version satisfies a version spec
+    #[error("No version of package '{0}' matches '{1}'")]
+    NoMatchingVersion(String, String),
+    /// Error raised when downloading fails
+    #[error("Failed to download {0}")]
+    RequestError(Url, #[source] reqwest::Error),
+    /// Error raised when registry metadata is malformed
+    #[error("Malformed registry metadata for package '{0}'")]
+    MalformedMetadata(String, #[source] serde_json::Error),
+    /// Error raised when the registry publishes no (supported) checksum for a tarball
+    #[error("Registry metadata for package '{0}' has no supported checksum for {1}")]
+    MissingChecksum(String, Url),
+    /// Error raised when a downloaded tarball does not match the checksum published by the
+    /// registry
+    #[error("Tarball {url} of package '{name}' does not match the registry checksum (expected {expected}, found {actual})")]
+    ChecksumMismatch {
+        /// Package name
+        name: String,
+        /// Tarball URL
+        url: Url,
+        /// Checksum published by the registry
+        expected: String,
+        /// Checksum of the downloaded tarball
+        actual: String,
+    },
+    /// Error raised when a git command fails
+    #[error("Failed to fetch '{0}' with git: {1}")]
+    GitError(String, String),
+    /// Error raised when a vendored package does not match its locked hash
+    #[error("Package '{name}' in {} does not match the hash in the lock file (expected {expected}, found {actual})", dir.display())]
+    IntegrityMismatch {
+        /// Package name
+        name: String,
+        /// Vendored package directory
+        dir: PathBuf,
+        /// Hash recorded in the lock file
+        expected: String,
+        /// Hash of the vendored contents
+        actual: String,
+    },
+    /// Generic filesystem error
+    #[error("{0}. Path: {1}")]
+    FsError(&'static str, PathBuf, #[source] io::Error),
+    /// Error reading or writing the lock file
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+}
+
+/// Result type for native dependency resolution
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Where a dependency is downloaded from, as determined by its version spec.
+#[derive(Debug, PartialEq, Eq)]
+enum Source {
+    /// Version, version range, or dist-tag resolved against the registry
+    Registry(String),
+    /// Tarball URL
+    Tarball(Url),
+    /// Git repository and ref (branch, tag, or commit)
+    Git { repo: String, reference: String },
+}
+
+impl Source {
+    /// Determine the source of a package from its version spec.
+    fn parse(name: &str, spec: &str) -> Result<Self> {
+        let invalid = || Error::InvalidSpec(name.to_string(), spec.to_string());
+        if let Some(git) = spec.strip_prefix("git+") {
+            let (repo, reference) = git.split_once('#').unwrap_or((git, "HEAD"));
+            // reject anything git would parse as an option (e.g., `--upload-pack=...`)
+            if repo.is_empty()
+                || reference.is_empty()
+                || repo.starts_with('-')
+                || reference.starts_with('-')
+            {
+                return Err(invalid());
+            }
+            Ok(Source::Git {
+                repo: repo.to_string(),
+                reference: reference.to_string(),
+            })
+        } else if spec.contains("://") {
+            Url::parse(spec).map(Source::Tarball).map_err(|_| invalid())
+        } else if spec.trim().is_empty() {
+            Err(invalid())
+        } else {
+            Ok(Source::Registry(spec.trim().to_string()))
+        }
+    }
+}
+
+/// Subset of the npm registry package metadata that we need.
+#[derive(Debug, Deserialize)]
+struct PackageMetadata {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: BTreeMap<String, String>,
+    #[serde(default)]
+    versions: BTreeMap<String, VersionMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionMetadata {
+    dist: Dist,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Dist {
+    tarball: Url,
+    /// Subresource integrity string (e.g., `sha512-<base64>`)
+    #[serde(default)]
+    integrity: Option<String>,
+    /// Hex-encoded SHA-1 of the tarball (published for older packages without `integrity`)
+    #[serde(default)]
+    shasum: Option<String>,
+}
+
+impl Dist {
+    /// Check downloaded tarball `data` against the checksum published by the registry, preferring
+    /// `integrity` over `shasum`.  Tarballs without a supported checksum are rejected.
+    fn verify(&self, name: &str, data: &[u8]) -> Result<()> {
+        let mismatch = |expected: &str, actual: String| Error::ChecksumMismatch {
+            name: name.to_string(),
+            url: self.tarball.clone(),
+            expected: expected.to_string(),
+            actual,
+        };
+        // An integrity string may list several hashes; any supported one has to match
+        let sri = self.integrity.iter().flat_map(|i| i.split_whitespace());
+        for expected in sri {
+            let Some((algo, _)) = expected.split_once('-') else {
+                continue;
+            };
+            let digest = match algo {
+                "sha512" => Sha512::digest(data).to_vec(),
+                "sha384" => Sha384::digest(data).to_vec(),
+                "sha256" => Sha256::digest(data).to_vec(),
+                _ => continue,
+            };
+            let b64 = base64::engine::general_purpose::STANDARD.encode(digest);
+            let actual = format!("{algo}-{b64}");
+            return match expected == actual {
+                true => Ok(()),
+                false => Err(mismatch(expected, actual)),
+            };
+        }
+        match &self.shasum {
+            Some(expected) => {
+                let actual = hex::encode(Sha1::digest(data));
+                match expected.eq_ignore_ascii_case(&actual) {
+                    true => Ok(()),
+                    false => Err(mismatch(expected, actual)),
+                }
+            }
+            None => Err(Error::MissingChecksum(
+                name.to_string(),
+                self.tarball.clone(),
+            )),
+        }
+    }
+}
+
+impl PackageMetadata {
+    /// Select the version matching `spec` (a dist-tag, an exact version, or a semver version
+    /// requirement), returning the version and its distribution (tarball URL and checksums).
+    fn select(&self, name: &str, spec: &str) -> Result<(String, Dist)> {
+        let no_match = || Error::NoMatchingVersion(name.to_string(), spec.to_string());
+        let version = match self.dist_tags.get(spec) {
+            Some(version) => version.clone(),
+            None if self.versions.contains_key(spec) => spec.to_string(),
+            // Unlike npm, semver treats a bare version as a caret requirement
+            None if Version::parse(spec).is_ok() => return Err(no_match()),
+            None => {
+                let req = VersionReq::parse(spec)
+                    .map_err(|_| Error::InvalidSpec(name.to_string(), spec.to_string()))?;
+                self.versions
+                    .keys()
+                    .filter_map(|v| Version::parse(v).ok())
+                    .filter(|v| req.matches(v))
+                    .max()
+                    .ok_or_else(no_match)?
+                    .to_string()
+            }
+        };
+        let meta = self.versions.get(&version).ok_or_else(no_match)?;
+        Ok((version, meta.dist.clone()))
+    }
+}
+
+/// Native dependency resolver, vendoring packages into a directory and pinning them in a lock
+/// file.
+pub struct Vendor {
+    /// Directory where packages are vendored (each in `{dir}/{name}`)
+    dir: PathBuf,
+    /// npm-compatible registry
+    registry: Url,
+    /// Lock file path
+    lock_file: PathBuf,
+    /// Current lock file contents
+    lock: LockFile,
+}
+
+impl Vendor {
+    /// Create a resolver, loading the lock file (if it exists).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Resolver settings (with an absolute vendor directory)
+    /// * `lock_file` - Path to the lock file
+    pub fn new(config: &VendorConfig, lock_file: PathBuf) -> Result<Self> {
+        let lock = LockFile::from_file_or_default(&lock_file)?;
+        let mut registry = config.registry.clone();
+        if !registry.path().ends_with('/') {
+            registry.set_path(&format!("{}/", registry.path()));
+        }
+        Ok(Vendor {
+            dir: config.dir.clone(),
+            registry,
+            lock_file,
+            lock,
+        })
+    }
+
+    /// Current lock file contents.
+    pub fn lock(&self) -> &LockFile {
+        &self.lock
+    }
+
+    /// Whether package `name` is pinned in the lock file for version spec `spec`.
+    pub fn is_locked(&self, name: &str, spec: &str) -> bool {
+        self.lock
+            .packages
+            .get(name)
+            .map_or(false, |pkg| pkg.spec == spec)
+    }
+
+    /// Bring the vendor directory in sync with the lock file and the requested packages:
+    /// - packages in `deps` that are not locked (or are locked for a different spec) are resolved,
+    ///   downloaded, and added to the lock file,
+    /// - locked packages that are not in `deps` are removed from the lock file and the vendor
+    ///   directory,
+    /// - locked packages missing from the vendor directory are downloaded from their locked source,
+    /// - all locked packages are checked against their locked hashes.
+    ///
+    /// The lock file is written if it changed. Returns the names of all downloaded packages.
+    ///
+    /// # Arguments
+    ///
+    /// * `deps` - All packages of the project: package names mapped to version specs
+    pub fn sync<'a>(
+        &mut self,
+        deps: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Vec<String>> {
+        let deps: BTreeMap<&str, &str> = deps.into_iter().collect();
+        let mut downloaded = vec![];
+        let mut changed = false;
+
+        let unused: Vec<String> = self
+            .lock
+            .packages
+            .keys()
+            .filter(|name| !deps.contains_key(name.as_str()))
+            .cloned()
+            .collect();
+        for name in unused {
+            debug!("Removing unused package '{name}'");
+            let pkg_dir = self.package_dir(&name)?;
+            if pkg_dir.exists() {
+                fs::remove_dir_all(&pkg_dir)
+                    .map_err(|e| Error::FsError("Failed to remove directory", pkg_dir, e))?;
+            }
+            self.lock.packages.remove(&name);
+            changed = true;
+        }
+
+        for (name, spec) in deps {
+            if self.is_locked(name, spec) {
+                continue;
+            }
+            debug!("Resolving '{name}@{spec}'");
+            let pkg = self.fetch(name, spec, None)?;
+            self.lock.packages.insert(name.to_string(), pkg);
+            downloaded.push(name.to_string());
+            changed = true;
+        }
+
+        for (name, pkg) in &self.lock.packages {
+            if downloaded.contains(name) {
+                continue;
+            }
+            let pkg_dir = self.package_dir(name)?;
+            if pkg_dir.exists() {
+                debug!("Verifying vendored package '{name}'");
+                let actual = hash_dir(&pkg_dir)?;
+                if actual != pkg.integrity {
+                    return Err(Error::IntegrityMismatch {
+                        name: name.clone(),
+                        dir: pkg_dir,
+                        expected: pkg.integrity.clone(),
+                        actual,
+                    });
+                }
+            } else {
+                debug!("Restoring vendored package '{name}' from {}", pkg.resolved);
+                self.fetch(name, &pkg.resolved, Some(&pkg.integrity))?;
+                downloaded.push(name.clone());
+            }
+        }
+
+        if changed {
+            fs::create_dir_all(self.lock_file.parent().or_bug("Lock file has a parent dir"))
+                .map_err(|e| {
+                    Error::FsError("Failed to create directory", self.lock_file.clone(), e)
+                })?;
+            self.lock.to_file(&self.lock_file)?;
+        }
+        Ok(downloaded)
+    }
+
+    /// Directory where package `name` is vendored.
+    fn package_dir(&self, name: &str) -> Result<PathBuf> {
+        let rel = Path::new(name);
+        let valid = !name.is_empty() && rel.components().all(|c| matches!(c, Component::Normal(_)));
+        if !valid {
+            return Err(Error::InvalidPackageName(name.to_string()));
+        }
+        Ok(self.dir.join(rel))
+    }
+
+    /// Download package `name` for version spec `spec` into the vendor directory, returning its
+    /// lock file entry.  If `expected_integrity` is set, the downloaded contents must match it.
+    fn fetch(
+        &self,
+        name: &str,
+        spec: &str,
+        expected_integrity: Option<&str>,
+    ) -> Result<LockedPackage> {
+        let pkg_dir = self.package_dir(name)?;
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            Error::FsError("Failed to create vendor directory", self.dir.clone(), e)
+        })?;
+        let staging = tempfile::tempdir_in(&self.dir)
+            .map_err(|e| Error::FsError("Failed to create directory", self.dir.clone(), e))?;
+
+        let (version, resolved) = match Source::parse(name, spec)? {
+            Source::Registry(spec) => {
+                let url = self
+                    .registry
+                    .join(&name.replace('/', "%2f"))
+                    .map_err(|_| Error::InvalidPackageName(name.to_string()))?;
+                let metadata: PackageMetadata = serde_json::from_slice(&download(&url)?)
+                    .map_err(|e| Error::MalformedMetadata(name.to_string(), e))?;
+                let (version, dist) = metadata.select(name, &spec)?;
+                let data = download(&dist.tarball)?;
+                dist.verify(name, &data)?;
+                extract_tarball(&data, staging.path())?;
+                (Some(version), dist.tarball.to_string())
+            }
+            Source::Tarball(url) => {
+                extract_tarball(&download(&url)?, staging.path())?;
+                (package_json_version(staging.path()), url.to_string())
+            }
+            Source::Git { repo, reference } => {
+                let commit = git_checkout(&repo, &reference, staging.path())?;
+                let resolved = format!("git+{repo}#{commit}");
+                (Some(commit), resolved)
+            }
+        };
+
+        let integrity = hash_dir(staging.path())?;
+        if let Some(expected) = expected_integrity {
+            if expected != integrity {
+                return Err(Error::IntegrityMismatch {
+                    name: name.to_string(),
+                    dir: pkg_dir,
+                    expected: expected.to_string(),
+                    actual: integrity,
+                });
+            }
+        }
+
+        // Move the staged package into place
+        if pkg_dir.exists() {
+            fs::remove_dir_all(&pkg_dir)
+                .map_err(|e| Error::FsError("Failed to remove directory", pkg_dir.clone(), e))?;
+        }
+        let parent = pkg_dir.parent().or_bug("Package dir has a parent dir");
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::FsError("Failed to create directory", parent.to_path_buf(), e))?;
+        fs::rename(staging.into_path(), &pkg_dir)
+            .map_err(|e| Error::FsError("Failed to move vendored package", pkg_dir.clone(), e))?;
+
+        Ok(LockedPackage {
+            spec: spec.to_string(),
+            version,
+            resolved,
+            integrity,
+        })
+    }
+}
+
+/// Download `url` (or read it, for `file://` URLs).  The request runs on a dedicated thread
+/// because reqwest's blocking client must not be used from within an async runtime (which is
+/// where cubist commands run).
+fn download(url: &Url) -> Result<Vec<u8>> {
+    if url.scheme() == "file" {
+        let path = url.to_file_path().map_err(|_| {
+            Error::FsError(
+                "Invalid file URL",
+                url.path().into(),
+                io::ErrorKind::InvalidInput.into(),
+            )
+        })?;
+        return fs::read(&path).map_err(|e| Error::FsError("Failed to read file", path, e));
+    }
+    debug!("Downloading {url}");
+    let url = url.clone();
+    std::thread::spawn(move || {
+        reqwest::blocking::get(url.clone())
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.bytes())
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| Error::RequestError(url, e))
+    })
+    .join()
+    .or_bug("Download thread panicked")
+}
+
+/// Extract a gzipped tarball into `dest`, stripping the top-level directory (e.g., `package/`
+/// in npm tarballs).  Only regular files and directories are extracted.
+fn extract_tarball(data: &[u8], dest: &Path) -> Result<()> {
+    let to_err = |e| Error::FsError("Failed to extract tarball", dest.to_path_buf(), e);
+    let mut archive = Archive::new(GzDecoder::new(data));
+    for entry in archive.entries().map_err(to_err)? {
+        let mut entry = entry.map_err(to_err)?;
+        let path = entry.path().map_err(to_err)?.into_owned();
+        let mut components = path.components();
+        components.next();
+        let rel = components.as_path();
+        if rel.as_os_str().is_empty()
+            || !rel.components().all(|c| matches!(c, Component::Normal(_)))
+        {
+            continue;
+        }
+        let target = dest.join(rel);
+        match entry.header().entry_type() {
+            EntryType::Directory => fs::create_dir_all(&target).map_err(to_err)?,
+            EntryType::Regular => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(to_err)?;
+                }
+                entry.unpack(&target).map_err(to_err)?;
+            }
+            _ => debug!("Skipping tarball entry {}", path.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Check out `reference` of git repository `repo` into `dest` (without the `.git` directory),
+/// returning the checked out commit.
+fn git_checkout(repo: &str, reference: &str, dest: &Path) -> Result<String> {
+    let git = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dest)
+            .output()
+            .map_err(|e| Error::GitError(repo.to_string(), e.to_string()))?;
+        if !output.status.success() {
+            return Err(Error::GitError(
+                repo.to_string(),
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    git(&["init", "--quiet"])?;
+    git(&["fetch", "--quiet", "--depth", "1", "--", repo, reference])?;
+    git(&["checkout", "--quiet", "FETCH_HEAD"])?;
+    let commit = git(&["rev-parse", "HEAD"])?;
+    let git_dir = dest.join(".git");
+    fs::remove_dir_all(&git_dir)
+        .map_err(|e| Error::FsError("Failed to remove directory", git_dir, e))?;
+    Ok(commit)
+}
+
+/// Version declared in the `package.json` file in `dir` (if any).
+fn package_json_version(dir: &Path) -> Option<String> {
+    #[derive(Deserialize)]
+    struct PackageJson {
+        version: String,
+    }
+    let json = fs::read(dir.join("package.json")).ok()?;
+    serde_json::from_slice::<PackageJson>(&json)
+        .ok()
+        .map(|p| p.version)
+}
+
+/// Hash the contents of directory `dir`: the relative paths and contents of all regular files
+/// (in a deterministic order).
+fn hash_dir(dir: &Path) -> Result<String> {
+    fn collect(dir: &Path, rel: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir.join(rel))? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let rel = rel.join(entry.file_name());
+            if file_type.is_dir() {
+                collect(dir, &rel, files)?;
+            } else if file_type.is_file() {
+                files.push(rel);
+            }
+        }
+        Ok(())
+    }
+
+    let to_err = |e| Error::FsError("Failed to hash directory", dir.to_path_buf(), e);
+    let mut files = vec![];
+    collect(dir, Path::new(""), &mut files).map_err(to_err)?;
+    let mut files = files
+        .into_iter()
+        .map(|rel| {
+            let key = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            (key, rel)
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut hasher = blake3::Hasher::new();
+    for (key, rel) in files {
+        let bytes = fs::read(dir.join(rel)).map_err(to_err)?;
+        hasher.update(key.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&(bytes.len() as u64).to_le_bytes());
+        hasher.update(&bytes);
+    }
+    Ok(format!("blake3-{}", hasher.finalize().to_hex()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use tempfile::tempdir;
+
+    fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("package/{path}"), contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            Source::Registry("^4.8.0".into()),
+            Source::parse("a", "^4.8.0").unwrap()
+        );
+        assert_eq!(
+            Source::Tarball(Url::parse("https://example.com/a.tgz").unwrap()),
+            Source::parse("a", "https://example.com/a.tgz").unwrap()
+        );
+        assert_eq!(
+            Source::Git {
+                repo: "https://github.com/foo/bar.git".into(),
+                reference: "v1.0.0".into()
+            },
+            Source::parse("a", "git+https://github.com/foo/bar.git#v1.0.0").unwrap()
+        );
+        assert_eq!(
+            Source::Git {
+                repo: "https://github.com/foo/bar.git".into(),
+                reference: "HEAD".into()
+            },
+            Source::parse("a", "git+https://github.com/foo/bar.git").unwrap()
+        );
+        assert!(Source::parse("a", "").is_err());
+        assert!(Source::parse("a", "git+#v1").is_err());
+        // options smuggled into git's arguments
+        assert!(Source::parse("a", "git+--upload-pack=touch /tmp/x").is_err());
+        assert!(Source::parse("a", "git+https://github.com/foo/bar.git#--help").is_err());
+    }
+
+    #[test]
+    fn test_select_version() {
+        let metadata: PackageMetadata = serde_json::from_value(serde_json::json!({
+            "dist-tags": { "latest": "4.9.6", "next": "5.0.0-rc.0" },
+            "versions": {
+                "4.8.0": { "dist": { "tarball": "https://r/a-4.8.0.tgz" } },
+                "4.9.6": { "dist": { "tarball": "https://r/a-4.9.6.tgz" } },
+                "5.0.0-rc.0": { "dist": { "tarball": "https://r/a-5.0.0-rc.0.tgz" } },
+                "5.0.1": { "dist": { "tarball": "https://r/a-5.0.1.tgz" } }
+            }
+        }))
+        .unwrap();
+        let version = |spec| metadata.select("a", spec).map(|(v, _)| v);
+        assert_eq!("4.9.6", version("latest").unwrap());
+        assert_eq!("5.0.0-rc.0", version("next").unwrap());
+        assert_eq!("4.8.0", version("4.8.0").unwrap());
+        assert_eq!("4.9.6", version("^4.8.0").unwrap());
+        assert_eq!("5.0.1", version(">=4").unwrap());
+        assert!(matches!(version("^6"), Err(Error::NoMatchingVersion(..))));
+        assert!(matches!(
+            version("4.8.1"),
+            Err(Error::NoMatchingVersion(..))
+        ));
+        assert!(matches!(version("nope"), Err(Error::InvalidSpec(..))));
+    }
+
+    #[test]
+    fn test_sync_tarball() {
+        let tmp = tempdir().unwrap();
+        let archive = tmp.path().join("pkg.tgz");
+        fs::write(
+            &archive,
+            tarball(&[
+                (
+                    "package.json",
+                    r#"{ "name": "@foo/bar", "version": "1.2.3" }"#,
+                ),
+                ("contracts/Bar.sol", "contract Bar {}"),
+            ]),
+        )
+        .unwrap();
+        let spec = Url::from_file_path(&archive).unwrap().to_string();
+        let config = VendorConfig {
+            dir: tmp.path().join("lib"),
+            ..Default::default()
+        };
+        let lock_file = tmp.path().join("cubist.lock");
+
+        // Resolve and pin the package
+        let mut vendor = Vendor::new(&config, lock_file.clone()).unwrap();
+        assert_eq!(
+            vec!["@foo/bar".to_string()],
+            vendor.sync([("@foo/bar", spec.as_str())]).unwrap()
+        );
+        let pkg_dir = tmp.path().join("lib/@foo/bar");
+        assert!(pkg_dir.join("contracts/Bar.sol").is_file());
+        let locked = &vendor.lock().packages["@foo/bar"];
+        assert_eq!(Some("1.2.3".to_string()), locked.version);
+        assert_eq!(hash_dir(&pkg_dir).unwrap(), locked.integrity);
+
+        // Nothing to do once locked
+        let mut vendor = Vendor::new(&config, lock_file.clone()).unwrap();
+        assert!(vendor.is_locked("@foo/bar", &spec));
+        assert!(vendor
+            .sync([("@foo/bar", spec.as_str())])
+            .unwrap()
+            .is_empty());
+
+        // Missing packages are restored from the lock file
+        fs::remove_dir_all(&pkg_dir).unwrap();
+        assert_eq!(
+            vec!["@foo/bar".to_string()],
+            vendor.sync([("@foo/bar", spec.as_str())]).unwrap()
+        );
+        assert!(pkg_dir.join("contracts/Bar.sol").is_file());
+
+        // Modified packages are rejected
+        fs::write(pkg_dir.join("contracts/Bar.sol"), "contract Baz {}").unwrap();
+        assert!(matches!(
+            vendor.sync([("@foo/bar", spec.as_str())]),
+            Err(Error::IntegrityMismatch { .. })
+        ));
+
+        // Packages that are no longer used are removed
+        assert!(vendor.sync([]).unwrap().is_empty());
+        assert!(!pkg_dir.exists());
+        assert!(vendor.lock().packages.is_empty());
+        let vendor = Vendor::new(&config, lock_file).unwrap();
+        assert!(!vendor.is_locked("@foo/bar", &spec));
+    }
+
+    #[test]
+    fn test_verify_tarball_checksum() {
+        let data = tarball(&[("contracts/Bar.sol", "contract Bar {}")]);
+        let sha512 = base64::engine::general_purpose::STANDARD.encode(Sha512::digest(&data));
+        let dist = |integrity: Option<String>, shasum: Option<String>| Dist {
+            tarball: Url::parse("https://r/bar-1.0.0.tgz").unwrap(),
+            integrity,
+            shasum,
+        };
+        let sha1 = Some(hex::encode(Sha1::digest(&data)));
+
+        assert!(dist(Some(format!("sha512-{sha512}")), None)
+            .verify("bar", &data)
+            .is_ok());
+        // unsupported algorithms are skipped
+        assert!(dist(Some(format!("md5-abc sha512-{sha512}")), None)
+            .verify("bar", &data)
+            .is_ok());
+        assert!(dist(None, sha1.clone()).verify("bar", &data).is_ok());
+        // integrity takes precedence over shasum
+        assert!(matches!(
+            dist(Some("sha512-AAAA".into()), sha1).verify("bar", &data),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            dist(None, Some("00".repeat(20))).verify("bar", &data),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            dist(None, None).verify("bar", &data),
+            Err(Error::MissingChecksum(..))
+        ));
+    }
+
+    #[test]
+    fn test_sync_registry() {
+        let tmp = tempdir().unwrap();
+        let registry = tmp.path().join("registry");
+        fs::create_dir_all(registry.join("@foo")).unwrap();
+        let archive = registry.join("bar-1.0.0.tgz");
+        let data = tarball(&[("contracts/Bar.sol", "contract Bar {}")]);
+        fs::write(&archive, &data).unwrap();
+        let sha512 = base64::engine::general_purpose::STANDARD.encode(Sha512::digest(&data));
+        let write_metadata = |integrity: &str| {
+            let metadata = serde_json::json!({
+                "dist-tags": { "latest": "1.0.0" },
+                "versions": {
+                    "1.0.0": {
+                        "dist": {
+                            "tarball": Url::from_file_path(&archive).unwrap().to_string(),
+                            "integrity": integrity
+                        }
+                    }
+                }
+            });
+            // '@foo/bar' is requested as '@foo%2fbar'
+            fs::write(registry.join("@foo").join("bar"), metadata.to_string()).unwrap();
+        };
+        let config = VendorConfig {
+            dir: tmp.path().join("lib"),
+            registry: Url::from_directory_path(&registry).unwrap(),
+        };
+        let pkg_dir = tmp.path().join("lib/@foo/bar");
+
+        // Tampered tarballs are rejected before anything is unpacked
+        write_metadata("sha512-AAAA");
+        let mut vendor = Vendor::new(&config, tmp.path().join("cubist.lock")).unwrap();
+        assert!(matches!(
+            vendor.sync([("@foo/bar", "^1.0.0")]),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!pkg_dir.exists());
+
+        write_metadata(&format!("sha512-{sha512}"));
+        assert_eq!(
+            vec!["@foo/bar".to_string()],
+            vendor.sync([("@foo/bar", "^1.0.0")]).unwrap()
+        );
+        assert!(pkg_dir.join("contracts/Bar.sol").is_file());
+        assert_eq!(
+            Some("1.0.0".to_string()),
+            vendor.lock().packages["@foo/bar"].version
+        );
+    }
+
+    #[test]
+    fn test_invalid_package_name() {
+        let tmp = tempdir().unwrap();
+        let config = VendorConfig {
+            dir: tmp.path().join("lib"),
+            ..Default::default()
+        };
+        let mut vendor = Vendor::new(&config, tmp.path().join("cubist.lock")).unwrap();
+        assert!(matches!(
+            vendor.sync([("../evil", "1.0.0")]),
+            Err(Error::InvalidPackageName(..))
+        ));
+    }
+}